@@ -0,0 +1,15 @@
+#version 120
+
+uniform sampler2D texture2d;
+uniform vec2 texel_size;
+uniform float pixel_size;
+
+varying vec2 vertex_texture_coords;
+
+void main()
+{
+	vec2 block = texel_size * pixel_size;
+	vec2 center = (floor(vertex_texture_coords / block) + 0.5) * block;
+
+	gl_FragColor = texture2D(texture2d, center);
+}
\ No newline at end of file
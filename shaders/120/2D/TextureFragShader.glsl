@@ -0,0 +1,14 @@
+#version 120
+
+uniform sampler2D texture2d;
+//NOTE(fpalacios): La textura y el tinte vienen premultiplicados
+uniform vec4 tint;
+
+varying vec2 vertex_texture_coords;
+
+void main()
+{
+	vec4 tex_color = texture2D(texture2d, vertex_texture_coords);
+
+	gl_FragColor = tex_color * tint;
+}
\ No newline at end of file
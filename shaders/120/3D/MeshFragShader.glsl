@@ -0,0 +1,19 @@
+#version 120
+
+uniform vec3 light_direction;
+uniform sampler2D texture2d;
+
+varying vec3 vertex_normal;
+varying vec2 vertex_texture_coords;
+varying vec3 vertex_color;
+
+void main()
+{
+	float diffuse = max(dot(normalize(vertex_normal), -light_direction), 0.0);
+
+	//NOTE(fpalacios): La textura esta premultiplicada, multiplicar el rgb no cambia eso
+	vec4 texture_color = texture2D(texture2d, vertex_texture_coords);
+	vec3 base_color    = vertex_color * texture_color.rgb;
+
+	gl_FragColor = vec4(base_color * (0.35 + 0.65 * diffuse), texture_color.a);
+}
\ No newline at end of file
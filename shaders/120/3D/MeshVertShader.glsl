@@ -0,0 +1,23 @@
+#version 120
+
+uniform mat4 model;
+uniform mat4 view;
+uniform mat4 projection;
+
+attribute vec3 position;
+attribute vec3 normal;
+attribute vec2 texture_coords;
+attribute vec3 color;
+
+varying vec3 vertex_normal;
+varying vec2 vertex_texture_coords;
+varying vec3 vertex_color;
+
+void main()
+{
+	gl_Position           = projection * view * model * vec4(position, 1.0);
+	//NOTE(fpalacios): GLSL 1.20 no tiene el constructor mat3(mat4)
+	vertex_normal         = (model * vec4(normal, 0.0)).xyz;
+	vertex_texture_coords = texture_coords;
+	vertex_color          = color;
+}
\ No newline at end of file
@@ -0,0 +1,44 @@
+#version 140
+
+uniform sampler2D texture2d;
+uniform vec2 texel_size;
+uniform float threshold;
+uniform float radius;
+uniform float intensity;
+
+in vec2 vertex_texture_coords;
+
+out vec4 fragment_color;
+
+//NOTE(fpalacios): Un solo pase: junta lo que supera el umbral en tres anillos alrededor del pixel, los mas cercanos pesan mas.
+//La escena esta premultiplicada asi que lo transparente ya no suma
+void main()
+{
+	vec4 color = texture(texture2d, vertex_texture_coords);
+
+	vec3 glow = vec3(0.0);
+	float total_weight = 0.0;
+
+	for(int ring = 1; ring <= 3; ring++)
+	{
+		for(int tap = 0; tap < 8; tap++)
+		{
+			float angle  = float(tap) * 0.785398 + float(ring) * 0.392699;
+			vec2  offset = vec2(cos(angle), sin(angle)) * texel_size * radius * float(ring) / 3.0;
+
+			vec4  tap_color  = texture(texture2d, vertex_texture_coords + offset);
+			float brightness = max(tap_color.r, max(tap_color.g, tap_color.b));
+			float weight     = 1.0 / float(ring);
+
+			glow         += tap_color.rgb * smoothstep(threshold, 1.0, brightness) * weight;
+			total_weight += weight;
+		}
+	}
+
+	glow = glow / total_weight * intensity;
+
+	//NOTE(fpalacios): La ventana es transparente, el brillo tambien tiene que sumar alpha para verse sobre el escritorio
+	float glow_alpha = clamp(max(glow.r, max(glow.g, glow.b)), 0.0, 1.0);
+
+	fragment_color = vec4(color.rgb + glow, max(color.a, glow_alpha));
+}
\ No newline at end of file
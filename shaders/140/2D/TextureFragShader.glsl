@@ -0,0 +1,16 @@
+#version 140
+
+uniform sampler2D texture2d;
+//NOTE(fpalacios): La textura y el tinte vienen premultiplicados
+uniform vec4 tint;
+
+in vec2 vertex_texture_coords;
+
+out vec4 fragment_color;
+
+void main()
+{
+	vec4 tex_color = texture(texture2d, vertex_texture_coords);
+
+	fragment_color = tex_color * tint;
+}
\ No newline at end of file
@@ -0,0 +1,14 @@
+#version 140
+
+in vec2 position;
+in vec2 texture_coords;
+
+uniform mat3 transform;
+
+out vec2 vertex_texture_coords;
+
+void main()
+{
+	gl_Position = vec4((transform * vec3(position, 1.0)).xy, 0.0, 1.0);
+	vertex_texture_coords = texture_coords;
+}
\ No newline at end of file
@@ -0,0 +1,21 @@
+#version 140
+
+uniform sampler2D texture2d;
+uniform float strength;
+uniform float radius;
+uniform float softness;
+
+in vec2 vertex_texture_coords;
+
+out vec4 fragment_color;
+
+void main()
+{
+	vec4 color = texture(texture2d, vertex_texture_coords);
+
+	//NOTE(fpalacios): 0 en el centro y 1 en las esquinas
+	float edge_distance = length(vertex_texture_coords - 0.5) * 1.414214;
+	float vignette      = 1.0 - strength * smoothstep(radius, radius + softness, edge_distance);
+
+	fragment_color = vec4(color.rgb * vignette, color.a);
+}
\ No newline at end of file
@@ -0,0 +1,21 @@
+#version 140
+
+uniform vec3 light_direction;
+uniform sampler2D texture2d;
+
+in vec3 vertex_normal;
+in vec2 vertex_texture_coords;
+in vec3 vertex_color;
+
+out vec4 fragment_color;
+
+void main()
+{
+	float diffuse = max(dot(normalize(vertex_normal), -light_direction), 0.0);
+
+	//NOTE(fpalacios): La textura esta premultiplicada, multiplicar el rgb no cambia eso
+	vec4 texture_color = texture(texture2d, vertex_texture_coords);
+	vec3 base_color    = vertex_color * texture_color.rgb;
+
+	fragment_color = vec4(base_color * (0.35 + 0.65 * diffuse), texture_color.a);
+}
\ No newline at end of file
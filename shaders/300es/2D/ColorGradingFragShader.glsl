@@ -0,0 +1,39 @@
+#version 300 es
+
+precision mediump float;
+
+uniform sampler2D texture2d;
+uniform sampler2D lut;
+uniform float lut_size;
+uniform float strength;
+
+in vec2 vertex_texture_coords;
+
+out vec4 fragment_color;
+
+//NOTE(fpalacios): La LUT es una tira de lut_size cuadrados de lut_size x lut_size, el azul elige el cuadrado y dentro
+//de cada uno el rojo va en x y el verde en y. Se mezclan los dos cuadrados mas cercanos
+vec3 lookup(vec3 color)
+{
+	float blue       = clamp(color.b, 0.0, 1.0) * (lut_size - 1.0);
+	float slice      = floor(blue);
+	float next_slice = min(slice + 1.0, lut_size - 1.0);
+
+	vec2 inside = (clamp(color.rg, 0.0, 1.0) * (lut_size - 1.0) + 0.5) / lut_size;
+
+	vec3 low  = texture(lut, vec2((slice      + inside.x) / lut_size, inside.y)).rgb;
+	vec3 high = texture(lut, vec2((next_slice + inside.x) / lut_size, inside.y)).rgb;
+
+	return mix(low, high, blue - slice);
+}
+
+void main()
+{
+	vec4 color = texture(texture2d, vertex_texture_coords);
+
+	//NOTE(fpalacios): La escena esta en lineal y premultiplicada, y las LUT se arman sobre colores en sRGB sin premultiplicar
+	vec3 straight = color.a > 0.0 ? color.rgb / color.a : vec3(0.0);
+	vec3 graded   = pow(lookup(pow(straight, vec3(1.0 / 2.2))), vec3(2.2));
+
+	fragment_color = vec4(mix(straight, graded, strength) * color.a, color.a);
+}
\ No newline at end of file
@@ -0,0 +1,21 @@
+#version 300 es
+
+precision mediump float;
+
+uniform sampler2D texture2d;
+
+in vec2 vertex_texture_coords;
+
+out vec4 fragment_color;
+
+void main()
+{
+	vec4 color = texture(texture2d, vertex_texture_coords);
+
+	//NOTE(fpalacios): La escena esta en lineal y premultiplicada. Se saca el alpha, se codifica en sRGB y se vuelve a
+	//multiplicar, que es lo que espera el compositor de una ventana ARGB
+	vec3 straight = color.a > 0.0 ? clamp(color.rgb / color.a, 0.0, 1.0) : vec3(0.0);
+	vec3 encoded  = mix(straight * 12.92, 1.055 * pow(straight, vec3(1.0 / 2.4)) - 0.055, step(0.0031308, straight));
+
+	fragment_color = vec4(encoded * color.a, color.a);
+}
\ No newline at end of file
@@ -0,0 +1,17 @@
+#version 460
+
+uniform sampler2D texture2d;
+uniform vec2 texel_size;
+uniform float pixel_size;
+
+in vec2 vertex_texture_coords;
+
+out vec4 fragment_color;
+
+void main()
+{
+	vec2 block = texel_size * pixel_size;
+	vec2 center = (floor(vertex_texture_coords / block) + 0.5) * block;
+
+	fragment_color = texture(texture2d, center);
+}
\ No newline at end of file
@@ -1,6 +1,8 @@
 #version 460
 
 uniform sampler2D texture2d;
+//NOTE(fpalacios): La textura y el tinte vienen premultiplicados
+uniform vec4 tint;
 
 in vec2 vertex_texture_coords;
 
@@ -10,7 +12,5 @@ void main()
 {
 	vec4 tex_color = texture(texture2d, vertex_texture_coords);
 
-	if(tex_color.a < 0.1) discard;
-
-	fragment_color = tex_color;
+	fragment_color = tex_color * tint;
 }
\ No newline at end of file
@@ -1,10 +0,0 @@
-#version 460
-
-in vec3 vertex_color;
-
-out vec4 fragment_color;
-
-void main()
-{
-	fragment_color = vec4(vertex_color, 1.0);
-}
\ No newline at end of file
@@ -0,0 +1,23 @@
+#version 460
+
+uniform mat4 model;
+uniform mat4 view;
+uniform mat4 projection;
+
+in vec3 position;
+in vec3 normal;
+in vec2 texture_coords;
+in vec3 color;
+
+out vec3 vertex_normal;
+out vec2 vertex_texture_coords;
+out vec3 vertex_color;
+
+void main()
+{
+	gl_Position           = projection * view * model * vec4(position, 1.0);
+	//NOTE(fpalacios): Alcanza con mat3(model) mientras el modelo solo rote y escale igual en todos los ejes
+	vertex_normal         = mat3(model) * normal;
+	vertex_texture_coords = texture_coords;
+	vertex_color          = color;
+}
\ No newline at end of file
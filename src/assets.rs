@@ -0,0 +1,671 @@
+//NOTE(fpalacios): Archivo con las opciones de cada textura, se busca igual que los assets:
+//{ "default": { "mipmaps": true }, "textures": { "Bonsai_1-500x500.png": { "filter": "nearest" } } }
+pub const DEFINITIONS_FILE: &str = "textures.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextureFilter
+{
+	Linear,
+	//NOTE(fpalacios): Para las plantas en pixel art
+	Nearest,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextureWrap
+{
+	Clamp,
+	Repeat,
+	MirroredRepeat,
+}
+
+//NOTE(fpalacios): Como sube y muestrea la textura el backend de glium, el de software las ignora
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextureOptions
+{
+	//NOTE(fpalacios): La compresion de la placa tiene perdida y se nota en el arte pintado a mano, por eso no es el default
+	pub compressed: bool,
+	pub mipmaps   : bool,
+	pub filter    : TextureFilter,
+	pub wrap      : TextureWrap,
+}
+
+impl Default for TextureOptions
+{
+	fn default() -> TextureOptions
+	{
+		return TextureOptions::new();
+	}
+}
+
+impl TextureOptions
+{
+	pub fn new() -> TextureOptions
+	{
+		return TextureOptions
+		{
+			compressed: false,
+			mipmaps   : true,
+			filter    : TextureFilter::Linear,
+			wrap      : TextureWrap::Clamp,
+		};
+	}
+
+	//NOTE(fpalacios): Pisa con lo que haya en el JSON, los campos que no esten quedan como en self
+	fn merge(&self, json: &crate::json::Json, context: &str) -> Result<TextureOptions, AssetError>
+	{
+		let invalid = |message: String| AssetError::Definitions(format!("{}: {}", context, message));
+
+		if json.as_object().is_none()
+		{
+			return Err(invalid(String::from("tiene que ser un objeto")));
+		}
+
+		let mut options = *self;
+
+		if let Some(value) = json.get("compressed")
+		{
+			options.compressed = value.as_bool().ok_or_else(|| invalid(String::from("\"compressed\" tiene que ser true o false")))?;
+		}
+
+		if let Some(value) = json.get("mipmaps")
+		{
+			options.mipmaps = value.as_bool().ok_or_else(|| invalid(String::from("\"mipmaps\" tiene que ser true o false")))?;
+		}
+
+		if let Some(value) = json.get("filter")
+		{
+			options.filter = match value.as_str()
+			{
+				Some("linear")  => TextureFilter::Linear,
+				Some("nearest") => TextureFilter::Nearest,
+				_               => return Err(invalid(String::from("\"filter\" tiene que ser \"linear\" o \"nearest\""))),
+			};
+		}
+
+		if let Some(value) = json.get("wrap")
+		{
+			options.wrap = match value.as_str()
+			{
+				Some("clamp")           => TextureWrap::Clamp,
+				Some("repeat")          => TextureWrap::Repeat,
+				Some("mirrored_repeat") => TextureWrap::MirroredRepeat,
+				_                       => return Err(invalid(String::from("\"wrap\" tiene que ser \"clamp\", \"repeat\" o \"mirrored_repeat\""))),
+			};
+		}
+
+		return Ok(options);
+	}
+}
+
+//NOTE(fpalacios): Lo que dice DEFINITIONS_FILE, ya resuelto para cada textura nombrada
+struct TextureDefinitions
+{
+	default : TextureOptions,
+	textures: std::collections::HashMap<String, TextureOptions>,
+	path    : Option<std::path::PathBuf>,
+	modified: Option<std::time::SystemTime>,
+}
+
+impl TextureDefinitions
+{
+	fn load(paths: &AssetPaths) -> Result<TextureDefinitions, AssetError>
+	{
+		let mut definitions = TextureDefinitions
+		{
+			default : TextureOptions::new(),
+			textures: std::collections::HashMap::new(),
+			path    : paths.resolve(DEFINITIONS_FILE),
+			modified: None,
+		};
+
+		let path = match &definitions.path
+		{
+			Some(path) => path.clone(),
+			None       => return Ok(definitions),
+		};
+
+		definitions.modified = modified_time(&path);
+
+		let source = std::fs::read_to_string(&path)
+			.map_err(|err| AssetError::Definitions(format!("No se pudo leer {}: {}", path.display(), err)))?;
+
+		let json = crate::json::parse(&source)
+			.map_err(|err| AssetError::Definitions(format!("{} en la posicion {}: {}", path.display(), err.position, err.message)))?;
+
+		if let Some(default) = json.get("default")
+		{
+			definitions.default = TextureOptions::new().merge(default, "default")?;
+		}
+
+		if let Some(textures) = json.get("textures")
+		{
+			let textures = textures.as_object()
+				.ok_or_else(|| AssetError::Definitions(String::from("\"textures\" tiene que ser un objeto")))?;
+
+			for (name, options) in textures
+			{
+				definitions.textures.insert(name.clone(), definitions.default.merge(options, name)?);
+			}
+		}
+
+		return Ok(definitions);
+	}
+
+	fn options(&self, name: &str) -> TextureOptions
+	{
+		return self.textures.get(name).cloned().unwrap_or(self.default);
+	}
+}
+
+//NOTE(fpalacios): Textura compartida. El mundo guarda el Rc<Texture> y le pide la imagen cada vez que dibuja, asi cuando el
+//PNG cambia en disco se reemplaza la imagen adentro y todos los que la usan ven la nueva sin enterarse
+pub struct Texture
+{
+	pub name: String,
+	//NOTE(fpalacios): None si viene de los assets embebidos, esas no se recargan
+	pub path: Option<std::path::PathBuf>,
+	image   : std::cell::RefCell<std::rc::Rc<image::RgbaImage>>,
+	options : std::cell::Cell<TextureOptions>,
+	modified: std::cell::Cell<Option<std::time::SystemTime>>,
+}
+
+impl Texture
+{
+	pub fn image(&self) -> std::rc::Rc<image::RgbaImage>
+	{
+		return self.image.borrow().clone();
+	}
+
+	pub fn options(&self) -> TextureOptions
+	{
+		return self.options.get();
+	}
+}
+
+#[derive(Debug)]
+pub enum AssetError
+{
+	NotFound
+	{
+		name    : String,
+		searched: Vec<std::path::PathBuf>,
+	},
+	Image
+	{
+		path : std::path::PathBuf,
+		error: image::ImageError,
+	},
+	EmbeddedImage
+	{
+		name : String,
+		error: image::ImageError,
+	},
+	Definitions(String),
+}
+
+impl std::fmt::Display for AssetError
+{
+	fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result
+	{
+		return match self
+		{
+			AssetError::NotFound { name, searched } =>
+			{
+				write!(formatter, "No se encontro el asset {}, se busco en:", name)?;
+				for directory in searched
+				{
+					write!(formatter, "\n\t{}", directory.display())?;
+				}
+				if searched.is_empty()
+				{
+					write!(formatter, "\n\t(ningun directorio)")?;
+				}
+				write!(formatter, "\nSe puede indicar el directorio de datos con JARDINCHO_DATA_DIR")
+			},
+			AssetError::Image { path, error }         => write!(formatter, "No se pudo leer la imagen {}: {}", path.display(), error),
+			AssetError::EmbeddedImage { name, error } => write!(formatter, "No se pudo leer la imagen embebida {}: {}", name, error),
+			AssetError::Definitions(message)          => write!(formatter, "Error en {}: {}", DEFINITIONS_FILE, message),
+		};
+	}
+}
+
+//NOTE(fpalacios): Copia de los assets por defecto dentro del binario, para instalar solo el ejecutable
+#[cfg(feature = "embed-assets")]
+const EMBEDDED_ASSETS: &[(&str, &[u8])] =
+&[
+	("Bonsai_1-500x500.png", include_bytes!("../assets/Bonsai_1-500x500.png")),
+	("Bonsai_2-500x500.png", include_bytes!("../assets/Bonsai_2-500x500.png")),
+	("Bonsai_3-500x500.png", include_bytes!("../assets/Bonsai_3-500x500.png")),
+];
+
+#[cfg(not(feature = "embed-assets"))]
+const EMBEDDED_ASSETS: &[(&str, &[u8])] = &[];
+
+//NOTE(fpalacios): Directorios donde se buscan los assets, en orden. Cada directorio de datos tiene los assets en assets/
+#[derive(Debug, Clone)]
+pub struct AssetPaths
+{
+	pub directories: Vec<std::path::PathBuf>,
+}
+
+impl AssetPaths
+{
+	//NOTE(fpalacios): JARDINCHO_DATA_DIR, despues los directorios de datos de XDG, despues al lado del ejecutable (o en
+	//../share/jardincho si esta instalado en un bin/). En debug tambien el repo, asi anda cargo run desde cualquier lado
+	pub fn from_env() -> AssetPaths
+	{
+		let mut data_directories = Vec::new();
+
+		if let Some(data_directory) = std::env::var_os("JARDINCHO_DATA_DIR")
+		{
+			data_directories.push(std::path::PathBuf::from(data_directory));
+		}
+
+		let data_home = std::env::var_os("XDG_DATA_HOME")
+			.filter(|value| !value.is_empty())
+			.map(std::path::PathBuf::from)
+			.or_else(|| std::env::var_os("HOME").map(|home| std::path::Path::new(&home).join(".local/share")));
+
+		if let Some(data_home) = data_home
+		{
+			data_directories.push(data_home.join("jardincho"));
+		}
+
+		//NOTE(fpalacios): El default es el que pide la especificacion si la variable no esta o esta vacia
+		let data_dirs = std::env::var("XDG_DATA_DIRS")
+			.ok()
+			.filter(|value| !value.is_empty())
+			.unwrap_or_else(|| String::from("/usr/local/share:/usr/share"));
+
+		for data_dir in data_dirs.split(':').filter(|data_dir| !data_dir.is_empty())
+		{
+			data_directories.push(std::path::Path::new(data_dir).join("jardincho"));
+		}
+
+		if let Some(executable_directory) = std::env::current_exe().ok().and_then(|path| path.parent().map(std::path::Path::to_path_buf))
+		{
+			data_directories.push(executable_directory.clone());
+			data_directories.push(executable_directory.join("../share/jardincho"));
+		}
+
+		if cfg!(debug_assertions)
+		{
+			data_directories.push(std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")));
+		}
+
+		return AssetPaths
+		{
+			directories: data_directories.into_iter().map(|data_directory| data_directory.join("assets")).collect(),
+		};
+	}
+
+	pub fn resolve(&self, name: &str) -> Option<std::path::PathBuf>
+	{
+		return self.directories.iter()
+			.map(|directory| directory.join(name))
+			.find(|path| path.is_file());
+	}
+
+	//NOTE(fpalacios): Los que existen, que son los que vale la pena mirar para recargar
+	pub fn existing_directories(&self) -> Vec<std::path::PathBuf>
+	{
+		let mut existing: Vec<std::path::PathBuf> = Vec::new();
+
+		for directory in &self.directories
+		{
+			let directory = match directory.canonicalize()
+			{
+				Ok(directory) => directory,
+				Err(_)        => continue,
+			};
+
+			if directory.is_dir() && !existing.contains(&directory)
+			{
+				existing.push(directory);
+			}
+		}
+
+		return existing;
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AssetMemory
+{
+	pub textures: usize,
+	//NOTE(fpalacios): Lo que ocupan las imagenes en RAM, en la placa depende del formato que elija cada backend
+	pub bytes   : usize,
+}
+
+//NOTE(fpalacios): Carga cada archivo una sola vez. Una textura se desaloja cuando el unico Rc que queda es el del cache
+pub struct AssetCache
+{
+	paths      : AssetPaths,
+	definitions: TextureDefinitions,
+	textures   : std::collections::HashMap<String, std::rc::Rc<Texture>>,
+	//NOTE(fpalacios): Imagenes reemplazadas o desalojadas que los renderers todavia tienen subidas
+	stale   : Vec<std::rc::Rc<image::RgbaImage>>,
+}
+
+impl AssetCache
+{
+	pub fn new(paths: AssetPaths) -> Result<AssetCache, AssetError>
+	{
+		return Ok(
+			AssetCache
+			{
+				definitions: TextureDefinitions::load(&paths)?,
+				paths,
+				textures   : std::collections::HashMap::new(),
+				stale      : Vec::new(),
+			}
+		);
+	}
+
+	pub fn paths(&self) -> &AssetPaths
+	{
+		return &self.paths;
+	}
+
+	//NOTE(fpalacios): name es relativo al directorio de assets. Lo que este en disco tiene prioridad sobre lo embebido, asi
+	//se puede pisar un asset sin recompilar
+	pub fn texture(&mut self, name: &str) -> Result<std::rc::Rc<Texture>, AssetError>
+	{
+		if let Some(texture) = self.textures.get(name)
+		{
+			return Ok(texture.clone());
+		}
+
+		let (image, path) = match self.paths.resolve(name)
+		{
+			Some(path) => (load_image(&path)?, Some(path)),
+			None =>
+			{
+				let (_, bytes) = EMBEDDED_ASSETS.iter()
+					.find(|(embedded_name, _)| *embedded_name == name)
+					.ok_or_else(|| AssetError::NotFound { name: String::from(name), searched: self.paths.directories.clone() })?;
+
+				let image = image::load_from_memory(bytes)
+					.map_err(|error| AssetError::EmbeddedImage { name: String::from(name), error })?
+					.to_rgba8();
+
+				(image, None)
+			},
+		};
+
+		let texture = std::rc::Rc::new(
+			Texture
+			{
+				name    : String::from(name),
+				modified: std::cell::Cell::new(path.as_deref().and_then(modified_time)),
+				image   : std::cell::RefCell::new(std::rc::Rc::new(image)),
+				options : std::cell::Cell::new(self.definitions.options(name)),
+				path,
+			}
+		);
+
+		self.textures.insert(String::from(name), texture.clone());
+
+		return Ok(texture);
+	}
+
+	//NOTE(fpalacios): Vuelve a leer las texturas cuyo archivo cambió. Si el PNG no se puede leer (ej: se esta guardando
+	//todavia) se sigue usando la imagen anterior y se reintenta en el proximo cambio
+	pub fn reload_changed(&mut self) -> usize
+	{
+		let mut reloaded = 0;
+
+		let definitions_path = self.paths.resolve(DEFINITIONS_FILE);
+		if definitions_path != self.definitions.path || definitions_path.as_deref().and_then(modified_time) != self.definitions.modified
+		{
+			match TextureDefinitions::load(&self.paths)
+			{
+				Ok(definitions) => self.definitions = definitions,
+				Err(err)        => println!("Error al recargar {}", err),
+			}
+		}
+
+		for texture in self.textures.values()
+		{
+			//NOTE(fpalacios): Si cambian las opciones hay que volver a subirla. Con un Rc nuevo el renderer la ve como otra
+			//imagen y la que tenia queda para soltar
+			let options = self.definitions.options(&texture.name);
+			if options != texture.options.get()
+			{
+				let image = texture.image();
+				let old_image = texture.image.replace(std::rc::Rc::new((*image).clone()));
+				self.stale.push(old_image);
+				texture.options.set(options);
+				reloaded += 1;
+			}
+
+			let path = match &texture.path
+			{
+				Some(path) => path,
+				None       => continue,
+			};
+
+			let modified = modified_time(path);
+			if modified == texture.modified.get()
+			{
+				continue;
+			}
+
+			match load_image(path)
+			{
+				Ok(image) =>
+				{
+					let old_image = texture.image.replace(std::rc::Rc::new(image));
+					self.stale.push(old_image);
+					texture.modified.set(modified);
+					reloaded += 1;
+				},
+				Err(err) => println!("Error al recargar {}", err),
+			}
+		}
+
+		return reloaded;
+	}
+
+	pub fn evict_unused(&mut self) -> usize
+	{
+		let unused: Vec<String> = self.textures.iter()
+			.filter(|(_, texture)| std::rc::Rc::strong_count(texture) == 1)
+			.map(|(name, _)| name.clone())
+			.collect();
+
+		for name in &unused
+		{
+			if let Some(texture) = self.textures.remove(name)
+			{
+				self.stale.push(texture.image());
+			}
+		}
+
+		return unused.len();
+	}
+
+	//NOTE(fpalacios): Avisa al renderer que suelte lo que tenga de las imagenes que ya no se usan
+	pub fn release_stale(&mut self, renderer: &mut impl crate::renderer::Renderer)
+	{
+		for image in self.stale.drain(..)
+		{
+			renderer.release_texture(&image);
+		}
+	}
+
+	pub fn memory(&self) -> AssetMemory
+	{
+		return AssetMemory
+		{
+			textures: self.textures.len(),
+			bytes   : self.textures.values().map(|texture| texture.image().as_raw().len()).sum(),
+		};
+	}
+}
+
+fn load_image(path: &std::path::Path) -> Result<image::RgbaImage, AssetError>
+{
+	return image::open(path)
+		.map(|image| image.to_rgba8())
+		.map_err(|error| AssetError::Image { path: path.to_path_buf(), error });
+}
+
+fn modified_time(path: &std::path::Path) -> Option<std::time::SystemTime>
+{
+	return std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok();
+}
+
+//NOTE(fpalacios): Un directorio de assets propio para cada test, con dos imagenes distintas y un triangulo en OBJ. Lo usan
+//tambien los tests del mundo
+#[cfg(test)]
+pub(crate) fn test_assets(test: &str) -> (AssetCache, std::path::PathBuf)
+{
+	let directory = std::env::temp_dir().join(format!("jardincho-{}-{}", test, std::process::id()));
+	let _ = std::fs::remove_dir_all(&directory);
+	std::fs::create_dir_all(&directory).unwrap();
+
+	image::RgbaImage::from_pixel(2, 2, image::Rgba([255, 0, 0, 255])).save(directory.join("a.png")).unwrap();
+	image::RgbaImage::from_pixel(2, 2, image::Rgba([0, 255, 0, 255])).save(directory.join("b.png")).unwrap();
+	std::fs::write(directory.join("plant.obj"), "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n").unwrap();
+
+	let assets = AssetCache::new(AssetPaths { directories: vec![directory.clone()] }).unwrap();
+
+	return (assets, directory);
+}
+
+#[cfg(test)]
+mod tests
+{
+	//NOTE(fpalacios): Las pruebas corren mas rapido que la resolucion del reloj de algunos sistemas de archivos, se adelanta
+	//la fecha a mano para que se note el cambio
+	fn touch(path: &std::path::Path, seconds: u64)
+	{
+		std::fs::File::options().write(true).open(path).unwrap()
+			.set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(seconds))
+			.unwrap();
+	}
+
+	fn released(renderer: &crate::recording_renderer::RecordingRenderer) -> Vec<usize>
+	{
+		return renderer.calls.iter().filter_map(|call| match call
+		{
+			crate::recording_renderer::RenderCall::ReleaseTexture { image } => Some(*image),
+			_                                                              => None,
+		}).collect();
+	}
+
+	#[test]
+	fn same_name_is_loaded_once()
+	{
+		let (mut assets, directory) = super::test_assets("assets-dedup");
+
+		let first  = assets.texture("a.png").unwrap();
+		let second = assets.texture("a.png").unwrap();
+		let other  = assets.texture("b.png").unwrap();
+
+		assert!(std::rc::Rc::ptr_eq(&first, &second));
+		assert!(!std::rc::Rc::ptr_eq(&first, &other));
+		assert_eq!(first.path, Some(directory.join("a.png")));
+		assert_eq!(assets.memory(), super::AssetMemory { textures: 2, bytes: 2 * 2 * 2 * 4 });
+
+		std::fs::remove_dir_all(&directory).unwrap();
+	}
+
+	#[test]
+	fn evicts_only_unused_textures()
+	{
+		let (mut assets, directory) = super::test_assets("assets-evict");
+
+		let kept    = assets.texture("a.png").unwrap();
+		let evicted = assets.texture("b.png").unwrap().image();
+
+		assert_eq!(assets.evict_unused(), 1);
+		assert_eq!(assets.evict_unused(), 0);
+		assert_eq!(assets.memory().textures, 1);
+
+		let mut renderer = crate::recording_renderer::RecordingRenderer::new();
+		assets.release_stale(&mut renderer);
+		assert_eq!(released(&renderer), vec![std::rc::Rc::as_ptr(&evicted) as usize]);
+
+		//NOTE(fpalacios): Lo que se sigue usando queda en el cache y se puede volver a pedir lo desalojado
+		assert!(std::rc::Rc::ptr_eq(&kept, &assets.texture("a.png").unwrap()));
+		assert_eq!(assets.texture("b.png").unwrap().image().get_pixel(0, 0).0, [0, 255, 0, 255]);
+
+		std::fs::remove_dir_all(&directory).unwrap();
+	}
+
+	#[test]
+	fn reloads_changed_files()
+	{
+		let (mut assets, directory) = super::test_assets("assets-reload");
+
+		let texture = assets.texture("a.png").unwrap();
+		let old_image = texture.image();
+		assert_eq!(assets.reload_changed(), 0);
+
+		image::RgbaImage::from_pixel(1, 1, image::Rgba([0, 0, 255, 255])).save(directory.join("a.png")).unwrap();
+		touch(&directory.join("a.png"), 10);
+
+		assert_eq!(assets.reload_changed(), 1);
+		assert_eq!(texture.image().get_pixel(0, 0).0, [0, 0, 255, 255]);
+		assert_eq!(assets.reload_changed(), 0);
+
+		//NOTE(fpalacios): A medio guardar no se puede leer, se sigue con la imagen anterior
+		std::fs::write(directory.join("a.png"), b"no es un png").unwrap();
+		touch(&directory.join("a.png"), 20);
+
+		assert_eq!(assets.reload_changed(), 0);
+		assert_eq!(texture.image().get_pixel(0, 0).0, [0, 0, 255, 255]);
+
+		//NOTE(fpalacios): Cambiar las opciones tambien cuenta, la imagen es la misma pero con otro Rc para volver a subirla
+		std::fs::write(directory.join(super::DEFINITIONS_FILE), "{ \"textures\": { \"a.png\": { \"filter\": \"nearest\" } } }").unwrap();
+		let before_options = texture.image();
+
+		assert_eq!(assets.reload_changed(), 1);
+		assert_eq!(texture.options().filter, super::TextureFilter::Nearest);
+		assert!(!std::rc::Rc::ptr_eq(&before_options, &texture.image()));
+
+		let mut renderer = crate::recording_renderer::RecordingRenderer::new();
+		assets.release_stale(&mut renderer);
+		assert_eq!(
+			released(&renderer),
+			vec![std::rc::Rc::as_ptr(&old_image) as usize, std::rc::Rc::as_ptr(&before_options) as usize]
+		);
+
+		std::fs::remove_dir_all(&directory).unwrap();
+	}
+
+	#[test]
+	fn resolves_in_order()
+	{
+		let (_, first) = super::test_assets("assets-resolve-first");
+		let (_, second) = super::test_assets("assets-resolve-second");
+		let missing = first.join("no-existe");
+
+		std::fs::remove_file(first.join("b.png")).unwrap();
+		image::RgbaImage::new(1, 1).save(second.join("c.png")).unwrap();
+
+		let paths = super::AssetPaths { directories: vec![missing.clone(), first.clone(), second.clone(), first.clone()] };
+
+		assert_eq!(paths.resolve("a.png"), Some(first.join("a.png")));
+		assert_eq!(paths.resolve("b.png"), Some(second.join("b.png")));
+		assert_eq!(paths.resolve("c.png"), Some(second.join("c.png")));
+		assert_eq!(paths.resolve("d.png"), None);
+		assert_eq!(paths.existing_directories(), vec![first.canonicalize().unwrap(), second.canonicalize().unwrap()]);
+
+		match super::AssetCache::new(paths).unwrap().texture("d.png")
+		{
+			Err(super::AssetError::NotFound { name, searched }) =>
+			{
+				assert_eq!(name, "d.png");
+				assert_eq!(searched, vec![missing, first.clone(), second.clone(), first.clone()]);
+			},
+			Err(err) => panic!("{}", err),
+			Ok(_)    => panic!("d.png no existe"),
+		}
+
+		std::fs::remove_dir_all(&first).unwrap();
+		std::fs::remove_dir_all(&second).unwrap();
+	}
+}
@@ -0,0 +1,48 @@
+//NOTE(fpalacios): Camara en perspectiva para el modo 3D. Las matrices salen con la convencion de OpenGL (mano derecha,
+//z del clip space entre -1 y 1)
+pub struct Camera
+{
+	pub position: nalgebra::Point3<f32>,
+	pub target  : nalgebra::Point3<f32>,
+	pub up      : nalgebra::Vector3<f32>,
+	//NOTE(fpalacios): En radianes, es el angulo vertical
+	pub fov_y   : f32,
+	pub near    : f32,
+	pub far     : f32,
+}
+
+impl Default for Camera
+{
+	fn default() -> Camera
+	{
+		return Camera::new();
+	}
+}
+
+impl Camera
+{
+	//NOTE(fpalacios): Mirando al origen desde un poco arriba, a la distancia justa para que entre un modelo normalizado
+	//a [-1, 1] (ver mesh::Mesh::normalize)
+	pub fn new() -> Camera
+	{
+		return Camera
+		{
+			position: nalgebra::Point3::new(0.0, 0.4, 3.2),
+			target  : nalgebra::Point3::new(0.0, 0.0, 0.0),
+			up      : nalgebra::Vector3::y(),
+			fov_y   : std::f32::consts::FRAC_PI_4,
+			near    : 0.1,
+			far     : 100.0,
+		};
+	}
+
+	pub fn view_matrix(&self) -> nalgebra::Matrix4<f32>
+	{
+		return nalgebra::Matrix4::look_at_rh(&self.position, &self.target, &self.up);
+	}
+
+	pub fn projection_matrix(&self, aspect_ratio: f32) -> nalgebra::Matrix4<f32>
+	{
+		return nalgebra::Matrix4::new_perspective(aspect_ratio, self.fov_y, self.near, self.far);
+	}
+}
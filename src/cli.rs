@@ -0,0 +1,584 @@
+//NOTE(fpalacios): Subcomandos de la linea de comandos. Sin argumentos es lo mismo que "run", asi sigue andando el autostart
+//que ya tiene la gente
+pub const USAGE: &str = "\
+Uso: jardincho [comando] [opciones]
+
+Comandos:
+  run            Muestra el jardin en el escritorio, es el comando por defecto
+      --config ARCHIVO       Archivo de configuracion, por defecto ~/.config/jardincho/config.toml
+      --window-mode MODO     desktop, overlay o window
+      --monitor MONITOR      primary, all o el nombre de una salida de RandR
+      --fps N                Maximo de cuadros por segundo
+  render         Dibuja las plantas fuera de pantalla y las guarda en un PNG o en un GIF animado
+      --output ARCHIVO       Terminado en .png o .gif, obligatorio
+      --config ARCHIVO
+      --plant NOMBRE         Solo esa planta de la configuracion
+      --size ANCHOxALTO      Por defecto 960x540
+      --time SEGUNDOS        PNG: momento de la animacion que se dibuja, por defecto 0
+      --duration SEGUNDOS    GIF: cuanto dura, por defecto una vuelta de la animacion mas larga
+      --fps N                GIF: cuadros por segundo, por defecto 12
+  list-monitors  Lista las salidas de RandR, para usar con --monitor
+  validate       Revisa el archivo de configuracion y los assets que usa
+      --config ARCHIVO
+  ctl COMANDO    Maneja el jardin que esta corriendo, por el socket de control
+      water [PLANTA]         Riega esa planta o todo el jardin
+      add-plant PLANTA       Siembra otra planta de la configuracion
+      remove-plant [PLANTA]  Arranca esa planta o la ultima que se sembro
+      set-weather CLIMA      clear, cloudy o rain
+      pause [on|off]         Pausa el jardin, sin on/off cambia de uno al otro
+      screenshot ARCHIVO     Guarda lo que se ve en un PNG
+      status                 Estado del jardin en JSON
+      quit                   Cierra el jardin
+  reset          Borra el estado guardado del jardin, con el jardin cerrado
+  help           Muestra esta ayuda";
+
+//NOTE(fpalacios): Lo que se pasa por la linea de comandos pisa lo del archivo de configuracion, tambien al recargarlo
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunOptions
+{
+	pub config     : Option<std::path::PathBuf>,
+	pub window_mode: Option<crate::config::WindowMode>,
+	pub monitor    : Option<crate::layout::MonitorSelection>,
+	pub fps        : Option<u32>,
+}
+
+impl Default for RunOptions
+{
+	fn default() -> RunOptions
+	{
+		return RunOptions::new();
+	}
+}
+
+impl RunOptions
+{
+	pub fn new() -> RunOptions
+	{
+		return RunOptions
+		{
+			config     : None,
+			window_mode: None,
+			monitor    : None,
+			fps        : None,
+		};
+	}
+
+	pub fn apply(&self, config: &mut crate::config::Config)
+	{
+		if let Some(window_mode) = self.window_mode
+		{
+			config.window.mode = window_mode;
+		}
+
+		if let Some(monitor) = &self.monitor
+		{
+			config.window.monitor = monitor.clone();
+		}
+
+		if let Some(fps) = self.fps
+		{
+			config.fps = fps;
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderOptions
+{
+	pub config  : Option<std::path::PathBuf>,
+	pub output  : std::path::PathBuf,
+	pub plant   : Option<String>,
+	pub width   : u32,
+	pub height  : u32,
+	pub time    : std::time::Duration,
+	pub duration: Option<std::time::Duration>,
+	pub fps     : u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command
+{
+	Run(RunOptions),
+	Render(RenderOptions),
+	ListMonitors,
+	Validate
+	{
+		config: Option<std::path::PathBuf>,
+	},
+	Control(crate::ipc::ControlCommand),
+	Reset,
+	Help,
+}
+
+//NOTE(fpalacios): args sin el nombre del ejecutable
+pub fn parse(args: &[String]) -> Result<Command, String>
+{
+	if args.iter().any(|arg| arg == "--help" || arg == "-h")
+	{
+		return Ok(Command::Help);
+	}
+
+	let (command, args) = match args.split_first()
+	{
+		Some((command, rest)) if !command.starts_with("--") => (command.as_str(), rest),
+		//NOTE(fpalacios): "jardincho --fps 30" es "jardincho run --fps 30"
+		_ => ("run", args),
+	};
+
+	//NOTE(fpalacios): ctl lleva argumentos sueltos en vez de opciones
+	if command == "ctl"
+	{
+		return crate::ipc::ControlCommand::from_args(args).map(Command::Control);
+	}
+
+	let options = options(args)?;
+	let unknown = |name: &str| format!("{} no tiene la opcion {}", command, name);
+
+	return match command
+	{
+		"run" =>
+		{
+			let mut run = RunOptions::new();
+
+			for (name, value) in options
+			{
+				match name.as_str()
+				{
+					"--config"      => run.config = Some(std::path::PathBuf::from(value)),
+					"--window-mode" =>
+					{
+						run.window_mode = Some(
+							crate::config::WindowMode::parse(&value)
+								.ok_or_else(|| format!("--window-mode tiene que ser desktop, overlay o window, no {}", value))?
+						);
+					},
+					"--monitor" => run.monitor = Some(crate::layout::MonitorSelection::parse(&value)),
+					"--fps"     => run.fps = Some(parse_number(&name, &value, 1, crate::config::MAX_FPS)?),
+					_           => return Err(unknown(&name)),
+				}
+			}
+
+			Ok(Command::Run(run))
+		},
+		"render" =>
+		{
+			let mut config   = None;
+			let mut output   = None;
+			let mut plant    = None;
+			let mut size     = (960, 540);
+			let mut time     = std::time::Duration::from_secs(0);
+			let mut duration = None;
+			let mut fps      = 12;
+
+			for (name, value) in options
+			{
+				match name.as_str()
+				{
+					"--config"   => config = Some(std::path::PathBuf::from(value)),
+					"--output"   => output = Some(std::path::PathBuf::from(value)),
+					"--plant"    => plant = Some(value),
+					"--size"     => size = parse_size(&value)?,
+					"--time"     => time = parse_seconds(&name, &value)?,
+					"--duration" => duration = Some(parse_seconds(&name, &value)?),
+					//NOTE(fpalacios): Las demoras del GIF van en centesimas, mas de 50 fps no se puede
+					"--fps"      => fps = parse_number(&name, &value, 1, 50)?,
+					_            => return Err(unknown(&name)),
+				}
+			}
+
+			Ok(
+				Command::Render(
+					RenderOptions
+					{
+						config,
+						output: output.ok_or_else(|| String::from("render necesita --output ARCHIVO.png o ARCHIVO.gif"))?,
+						plant,
+						width : size.0,
+						height: size.1,
+						time,
+						duration,
+						fps,
+					}
+				)
+			)
+		},
+		"validate" =>
+		{
+			let mut config = None;
+
+			for (name, value) in options
+			{
+				match name.as_str()
+				{
+					"--config" => config = Some(std::path::PathBuf::from(value)),
+					_          => return Err(unknown(&name)),
+				}
+			}
+
+			Ok(Command::Validate { config })
+		},
+		"list-monitors" | "reset" | "help" =>
+		{
+			if let Some((name, _)) = options.first()
+			{
+				return Err(unknown(name));
+			}
+
+			Ok(
+				match command
+				{
+					"list-monitors" => Command::ListMonitors,
+					"reset"         => Command::Reset,
+					_               => Command::Help,
+				}
+			)
+		},
+		_ => Err(format!("No existe el comando {}", command)),
+	};
+}
+
+//NOTE(fpalacios): Separa "--fps 30" y "--fps=30" en pares de opcion y valor. Todas las opciones llevan valor
+fn options(args: &[String]) -> Result<Vec<(String, String)>, String>
+{
+	let mut options = Vec::new();
+	let mut index = 0;
+
+	while index < args.len()
+	{
+		let arg = &args[index];
+
+		if !arg.starts_with("--")
+		{
+			return Err(format!("Argumento inesperado: {}", arg));
+		}
+
+		match arg.split_once('=')
+		{
+			Some((name, value)) => options.push((String::from(name), String::from(value))),
+			None =>
+			{
+				let value = args.get(index + 1).ok_or_else(|| format!("Falta el valor de {}", arg))?;
+				options.push((arg.clone(), value.clone()));
+				index += 1;
+			},
+		}
+
+		index += 1;
+	}
+
+	return Ok(options);
+}
+
+fn parse_number(name: &str, value: &str, min: u32, max: u32) -> Result<u32, String>
+{
+	return value.parse::<u32>()
+		.ok()
+		.filter(|number| *number >= min && *number <= max)
+		.ok_or_else(|| format!("{} tiene que ser un numero entero entre {} y {}, no {}", name, min, max, value));
+}
+
+fn parse_seconds(name: &str, value: &str) -> Result<std::time::Duration, String>
+{
+	return value.parse::<f64>()
+		.ok()
+		.filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+		.map(std::time::Duration::from_secs_f64)
+		.ok_or_else(|| format!("{} tiene que ser una cantidad de segundos, ej: 2.5, no {}", name, value));
+}
+
+fn parse_size(value: &str) -> Result<(u32, u32), String>
+{
+	let invalid = || format!("--size tiene que ser ANCHOxALTO, ej: 960x540, no {}", value);
+
+	let (width, height) = value.split_once('x').ok_or_else(invalid)?;
+	let width  = parse_number("--size", width,  1, 8192).map_err(|_| invalid())?;
+	let height = parse_number("--size", height, 1, 8192).map_err(|_| invalid())?;
+
+	return Ok((width, height));
+}
+
+//NOTE(fpalacios): Si se paso --config el archivo tiene que existir, el de por defecto puede no estar
+pub fn load_config(path: Option<&std::path::Path>) -> Result<(Option<std::path::PathBuf>, crate::config::Config), String>
+{
+	let path = match path
+	{
+		Some(path) if !path.is_file() => return Err(format!("No existe el archivo de configuracion {}", path.display())),
+		Some(path)                    => Some(path.to_path_buf()),
+		None                          => crate::config::Config::default_path(),
+	};
+
+	let config = match &path
+	{
+		Some(path) => crate::config::Config::load(path).map_err(|err| err.to_string())?,
+		None       => crate::config::Config::new(),
+	};
+
+	return Ok((path, config));
+}
+
+pub fn render(options: &RenderOptions) -> Result<(), String>
+{
+	let extension = options.output.extension().and_then(|extension| extension.to_str()).unwrap_or("").to_lowercase();
+	if extension != "png" && extension != "gif"
+	{
+		return Err(format!("{} tiene que terminar en .png o .gif", options.output.display()));
+	}
+
+	let (_, mut config) = load_config(options.config.as_deref())?;
+
+	if let Some(plant) = &options.plant
+	{
+		let names: Vec<String> = config.plants.iter().map(|definition| definition.name.clone()).collect();
+		config.plants.retain(|definition| &definition.name == plant);
+
+		if config.plants.is_empty()
+		{
+			return Err(format!("No hay ninguna planta {} en la configuracion, las que hay son: {}", plant, names.join(", ")));
+		}
+	}
+
+	let mut assets = crate::assets::AssetCache::new(crate::assets::AssetPaths::from_env()).map_err(|err| err.to_string())?;
+	let mut world = crate::world::World::new(&mut assets, &config).map_err(|err| err.to_string())?;
+
+	//NOTE(fpalacios): La imagen hace de un monitor, las plantas quedan igual que en el escritorio
+	let mut target = crate::offscreen::OffscreenTarget::new(options.width, options.height)
+		.map_err(|err| format!("No se pudo crear el contexto de OpenGL fuera de pantalla {:?}", err))?;
+	target.renderer.apply_theme(&config.theme);
+
+	if extension == "png"
+	{
+		world.update(&options.time);
+		target.render_png(&world, 0.0, &options.output).map_err(|err| format!("Error al dibujar {:?}", err))?;
+	}
+	else
+	{
+		let duration    = options.duration.or_else(|| world.animation_period()).unwrap_or_else(|| std::time::Duration::from_secs(3));
+		let frame_delay = std::time::Duration::from_secs(1) / options.fps;
+		let frame_count = std::cmp::max((duration.as_secs_f64() * options.fps as f64).round() as u32, 1);
+
+		let file = std::fs::File::create(&options.output).map_err(|err| format!("No se pudo crear {}: {}", options.output.display(), err))?;
+
+		//NOTE(fpalacios): Velocidad 10 es la que recomienda el crate de gif, con 1 tarda varios segundos por cuadro
+		let mut encoder = image::codecs::gif::GifEncoder::new_with_speed(std::io::BufWriter::new(file), 10);
+		encoder.set_repeat(image::codecs::gif::Repeat::Infinite).map_err(|err| format!("Error al escribir el GIF {}", err))?;
+
+		world.update(&options.time);
+
+		for _ in 0 .. frame_count
+		{
+			let image = target.render(&world, 0.0).map_err(|err| format!("Error al dibujar {:?}", err))?;
+
+			encoder.encode_frame(image::Frame::from_parts(image, 0, 0, image::Delay::from_numer_denom_ms(1000, options.fps)))
+				.map_err(|err| format!("Error al escribir el GIF {}", err))?;
+
+			world.update(&frame_delay);
+		}
+	}
+
+	println!("Se guardo {}", options.output.display());
+
+	return Ok(());
+}
+
+pub fn list_monitors() -> Result<(), String>
+{
+	let (xcb_conn, screen_id) = xcb::Connection::connect(None).map_err(|err| format!("No se pudo conectar al servidor X {:?}", err))?;
+
+	let xcb_client = crate::platform::xcb::Client::new(&xcb_conn);
+	let xcb_screen = crate::platform::xcb::Screen::from_id(&xcb_client, screen_id).unwrap();
+
+	let monitors = xcb_screen.monitors().map_err(|err| format!("Error al obtener los monitores {:?}", err))?;
+
+	if monitors.is_empty()
+	{
+		println!("No hay ninguna salida de RandR conectada");
+	}
+
+	for monitor in monitors
+	{
+		println!(
+			"{:<12} {}x{}+{}+{}{}",
+			monitor.name,
+			monitor.width,
+			monitor.height,
+			monitor.x,
+			monitor.y,
+			if monitor.primary { " primario" } else { "" }
+		);
+	}
+
+	return Ok(());
+}
+
+//NOTE(fpalacios): Carga todo lo que cargaria run pero sin abrir la ventana, asi los errores salen antes de reiniciar
+pub fn validate(config: Option<&std::path::Path>) -> Result<(), String>
+{
+	let (path, config) = load_config(config)?;
+
+	match &path
+	{
+		Some(path) if path.is_file() => println!("Configuracion: {}", path.display()),
+		Some(path)                   => println!("Configuracion: {} no existe, se usan los valores por defecto", path.display()),
+		None                         => println!("Configuracion: no se sabe donde buscarla, se usan los valores por defecto"),
+	}
+
+	let mut assets = crate::assets::AssetCache::new(crate::assets::AssetPaths::from_env()).map_err(|err| err.to_string())?;
+	crate::world::World::new(&mut assets, &config).map_err(|err| err.to_string())?;
+
+	for pass in &config.theme.postprocess
+	{
+		if let crate::postprocess::PostProcessPass::ColorGrading { lut, .. } = pass
+		{
+			image::open(lut).map_err(|err| format!("No se pudo leer la LUT {}: {}", lut.display(), err))?;
+		}
+	}
+
+	let memory = assets.memory();
+	println!("Todo bien: {} plantas, {} texturas ({:.1} MB)", config.plants.len(), memory.textures, memory.bytes as f64 / (1024.0 * 1024.0));
+
+	return Ok(());
+}
+
+//NOTE(fpalacios): Imprime la respuesta tal cual para que se pueda usar con jq desde una barra de estado
+pub fn control(command: &crate::ipc::ControlCommand) -> Result<(), String>
+{
+	let response = crate::ipc::send(command)?;
+
+	if response.get("ok").and_then(crate::json::Json::as_bool) != Some(true)
+	{
+		return Err(
+			response.get("error")
+				.and_then(crate::json::Json::as_str)
+				.map(String::from)
+				.unwrap_or_else(|| response.to_string())
+		);
+	}
+
+	println!("{}", response);
+
+	return Ok(());
+}
+
+//NOTE(fpalacios): Con el jardin abierto se volveria a guardar el estado al cerrarlo, asi que ni se intenta
+pub fn reset() -> Result<(), String>
+{
+	//NOTE(fpalacios): Si no hay donde poner el socket tampoco hay un jardin escuchando
+	if let Ok(socket_path) = crate::ipc::socket_path()
+	{
+		if std::os::unix::net::UnixStream::connect(&socket_path).is_ok()
+		{
+			return Err(format!("Hay un jardincho abierto escuchando en {}, cerralo antes de borrar el estado", socket_path.display()));
+		}
+	}
+
+	let directory = crate::state::directory().ok_or_else(|| String::from("No se sabe donde esta el estado guardado, falta HOME"))?;
+
+	if !directory.exists()
+	{
+		println!("No hay estado guardado en {}", directory.display());
+		return Ok(());
+	}
+
+	std::fs::remove_dir_all(&directory).map_err(|err| format!("No se pudo borrar {}: {}", directory.display(), err))?;
+	println!("Se borro el estado guardado en {}", directory.display());
+
+	return Ok(());
+}
+
+#[cfg(test)]
+mod tests
+{
+	fn args(args: &[&str]) -> Vec<String>
+	{
+		return args.iter().map(|arg| String::from(*arg)).collect();
+	}
+
+	fn run(fps: Option<u32>) -> super::Command
+	{
+		return super::Command::Run(super::RunOptions { fps, ..super::RunOptions::new() });
+	}
+
+	#[test]
+	fn run_is_the_default()
+	{
+		assert_eq!(super::parse(&args(&[])), Ok(run(None)));
+		assert_eq!(super::parse(&args(&["--fps", "30"])), Ok(run(Some(30))));
+		assert_eq!(super::parse(&args(&["run"])), Ok(run(None)));
+	}
+
+	#[test]
+	fn options_with_and_without_equals()
+	{
+		assert_eq!(super::parse(&args(&["run", "--fps=30"])), Ok(run(Some(30))));
+		assert_eq!(super::parse(&args(&["run", "--fps", "30"])), Ok(run(Some(30))));
+
+		assert_eq!(
+			super::options(&args(&["--config=a=b.toml", "--monitor", "--fps"])),
+			Ok(vec![
+				(String::from("--config"),  String::from("a=b.toml")),
+				(String::from("--monitor"), String::from("--fps")),
+			])
+		);
+
+		assert_eq!(
+			super::parse(&args(&["run", "--config", "jardin.toml", "--window-mode=overlay", "--monitor", "HDMI-1"])),
+			Ok(
+				super::Command::Run(
+					super::RunOptions
+					{
+						config     : Some(std::path::PathBuf::from("jardin.toml")),
+						window_mode: Some(crate::config::WindowMode::Overlay),
+						monitor    : Some(crate::layout::MonitorSelection::Named(String::from("HDMI-1"))),
+						fps        : None,
+					}
+				)
+			)
+		);
+	}
+
+	#[test]
+	fn errors()
+	{
+		assert_eq!(super::parse(&args(&["run", "--fps"])), Err(String::from("Falta el valor de --fps")));
+		assert_eq!(super::parse(&args(&["run", "--fsp=30"])), Err(String::from("run no tiene la opcion --fsp")));
+		assert_eq!(super::parse(&args(&["reset", "--config", "a.toml"])), Err(String::from("reset no tiene la opcion --config")));
+		assert_eq!(super::parse(&args(&["run", "30"])), Err(String::from("Argumento inesperado: 30")));
+		assert_eq!(super::parse(&args(&["plantar"])), Err(String::from("No existe el comando plantar")));
+		assert_eq!(super::parse(&args(&["run", "--fps=0"])), Err(String::from("--fps tiene que ser un numero entero entre 1 y 240, no 0")));
+		assert_eq!(super::parse(&args(&["run", "--window-mode=kiosk"])), Err(String::from("--window-mode tiene que ser desktop, overlay o window, no kiosk")));
+		assert_eq!(super::parse(&args(&["render"])), Err(String::from("render necesita --output ARCHIVO.png o ARCHIVO.gif")));
+		assert_eq!(super::parse(&args(&["render", "--output=a.png", "--size=10"])), Err(String::from("--size tiene que ser ANCHOxALTO, ej: 960x540, no 10")));
+		assert_eq!(super::parse(&args(&["render", "--output=a.gif", "--fps=60"])), Err(String::from("--fps tiene que ser un numero entero entre 1 y 50, no 60")));
+		assert_eq!(super::parse(&args(&["render", "--output=a.png", "--time=-1"])), Err(String::from("--time tiene que ser una cantidad de segundos, ej: 2.5, no -1")));
+	}
+
+	#[test]
+	fn commands()
+	{
+		assert_eq!(super::parse(&args(&["run", "--fps", "30", "--help"])), Ok(super::Command::Help));
+		assert_eq!(super::parse(&args(&["help"])), Ok(super::Command::Help));
+		assert_eq!(super::parse(&args(&["reset"])), Ok(super::Command::Reset));
+		assert_eq!(super::parse(&args(&["list-monitors"])), Ok(super::Command::ListMonitors));
+		assert_eq!(super::parse(&args(&["validate", "--config=a.toml"])), Ok(super::Command::Validate { config: Some(std::path::PathBuf::from("a.toml")) }));
+		assert_eq!(super::parse(&args(&["ctl", "water", "bonsai"])), Ok(super::Command::Control(crate::ipc::ControlCommand::Water(Some(String::from("bonsai"))))));
+
+		assert_eq!(
+			super::parse(&args(&["render", "--output", "jardin.gif", "--plant=bonsai", "--size", "320x200", "--time=1.5", "--duration=2", "--fps=10"])),
+			Ok(
+				super::Command::Render(
+					super::RenderOptions
+					{
+						config  : None,
+						output  : std::path::PathBuf::from("jardin.gif"),
+						plant   : Some(String::from("bonsai")),
+						width   : 320,
+						height  : 200,
+						time    : std::time::Duration::from_millis(1500),
+						duration: Some(std::time::Duration::from_secs(2)),
+						fps     : 10,
+					}
+				)
+			)
+		);
+	}
+}
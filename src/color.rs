@@ -1,7 +1,133 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct RgbaColor
 {
 	pub r: f32,
 	pub g: f32,
 	pub b: f32,
 	pub a: f32,
-}
\ No newline at end of file
+}
+
+impl RgbaColor
+{
+	pub const WHITE: RgbaColor = RgbaColor { r: 1.0, g: 1.0, b: 1.0, a: 1.0 };
+
+	pub fn new(r: f32, g: f32, b: f32, a: f32) -> RgbaColor
+	{
+		return RgbaColor
+		{
+			r,
+			g,
+			b,
+			a,
+		};
+	}
+
+	//NOTE(fpalacios): "#rrggbb" o "#rrggbbaa", como en los editores de imagenes
+	pub fn from_hex(value: &str) -> Option<RgbaColor>
+	{
+		let digits = value.strip_prefix('#')?;
+
+		if (digits.len() != 6 && digits.len() != 8) || !digits.chars().all(|digit| digit.is_ascii_hexdigit())
+		{
+			return None;
+		}
+
+		let channel = |index: usize| u8::from_str_radix(&digits[index * 2 .. index * 2 + 2], 16).unwrap() as f32 / 255.0;
+
+		return Some(
+			RgbaColor
+			{
+				r: channel(0),
+				g: channel(1),
+				b: channel(2),
+				a: if digits.len() == 8 { channel(3) } else { 1.0 },
+			}
+		);
+	}
+
+	pub fn to_array(&self) -> [f32; 4]
+	{
+		return [self.r, self.g, self.b, self.a];
+	}
+
+	pub fn multiply(&self, other: &RgbaColor) -> RgbaColor
+	{
+		return RgbaColor::new(self.r * other.r, self.g * other.g, self.b * other.b, self.a * other.a);
+	}
+
+	//NOTE(fpalacios): amount 0.0 es self y 1.0 es other
+	pub fn lerp(&self, other: &RgbaColor, amount: f32) -> RgbaColor
+	{
+		return RgbaColor::new(
+			self.r + (other.r - self.r) * amount,
+			self.g + (other.g - self.g) * amount,
+			self.b + (other.b - self.b) * amount,
+			self.a + (other.a - self.a) * amount
+		);
+	}
+}
+
+//NOTE(fpalacios): La curva exacta de sRGB, no la aproximacion con potencia 2.2
+pub fn srgb_to_linear(value: f32) -> f32
+{
+	if value <= 0.04045
+	{
+		return value / 12.92;
+	}
+
+	return ((value + 0.055) / 1.055).powf(2.4);
+}
+
+pub fn linear_to_srgb(value: f32) -> f32
+{
+	if value <= 0.0031308
+	{
+		return value * 12.92;
+	}
+
+	return 1.055 * value.powf(1.0 / 2.4) - 0.055;
+}
+
+//NOTE(fpalacios): Multiplica un canal de 0 a 255 por un alpha de 0 a 255 redondeando
+pub fn premultiply(channel: u8, alpha: u8) -> u8
+{
+	return ((channel as u32 * alpha as u32 + 127) / 255) as u8;
+}
+
+//NOTE(fpalacios): Multiplica por alpha en lineal y vuelve a codificar. Asi al muestrear la textura sRGB la placa decodifica
+//y queda el color lineal ya multiplicado, que es lo que necesita el blending premultiplicado
+pub fn premultiply_srgb(image: &image::RgbaImage) -> image::RgbaImage
+{
+	let decode: Vec<f32> = (0 ..= 255).map(|value| srgb_to_linear(value as f32 / 255.0)).collect();
+
+	let mut premultiplied = image.clone();
+	for pixel in premultiplied.pixels_mut()
+	{
+		let alpha = pixel.0[3] as f32 / 255.0;
+
+		for channel in 0 .. 3
+		{
+			pixel.0[channel] = (linear_to_srgb(decode[pixel.0[channel] as usize] * alpha) * 255.0).round() as u8;
+		}
+	}
+
+	return premultiplied;
+}
+
+//NOTE(fpalacios): Para guardar en PNG lo que se leyo de la placa o del backend por software. Lo que va a la ventana esta
+//premultiplicado despues de codificar en sRGB, asi que se divide directo
+pub fn unpremultiply(image: &image::RgbaImage) -> image::RgbaImage
+{
+	let mut straight = image.clone();
+	for pixel in straight.pixels_mut()
+	{
+		let alpha = pixel.0[3] as u32;
+
+		for channel in 0 .. 3
+		{
+			pixel.0[channel] = (pixel.0[channel] as u32 * 255 + alpha / 2).checked_div(alpha).map_or(0, |value| value.min(255) as u8);
+		}
+	}
+
+	return straight;
+}
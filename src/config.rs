@@ -0,0 +1,571 @@
+//NOTE(fpalacios): Archivo de configuracion, por defecto en ~/.config/jardincho/config.toml. Lo que no este en el archivo
+//(o el archivo entero si no existe) queda como en Config::new. Ejemplo con todas las opciones en config.example.toml
+pub const CONFIG_FILE: &str = "config.toml";
+
+pub const MAX_FPS: u32 = 240;
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowMode
+{
+	//NOTE(fpalacios): Abajo de todas las ventanas y en todos los escritorios, como si fuera parte del fondo de pantalla
+	Desktop,
+	//NOTE(fpalacios): Igual pero arriba de todas las ventanas
+	Overlay,
+	//NOTE(fpalacios): Ventana comun que aparece en la barra de tareas, para probar
+	Window,
+}
+
+impl WindowMode
+{
+	pub fn parse(value: &str) -> Option<WindowMode>
+	{
+		return match value
+		{
+			"desktop" => Some(WindowMode::Desktop),
+			"overlay" => Some(WindowMode::Overlay),
+			"window"  => Some(WindowMode::Window),
+			_         => None,
+		};
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WindowConfig
+{
+	pub mode   : WindowMode,
+	#[serde(deserialize_with = "monitor")]
+	pub monitor: crate::layout::MonitorSelection,
+	#[serde(deserialize_with = "anchor")]
+	pub anchor : crate::layout::Anchor,
+	//NOTE(fpalacios): Con 1.0 cada planta ocupa la mitad del alto del monitor
+	#[serde(deserialize_with = "scale")]
+	pub scale  : f32,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme
+{
+	#[serde(deserialize_with = "tint")]
+	pub tint       : crate::color::RgbaColor,
+	#[serde(deserialize_with = "opacity")]
+	pub opacity    : f32,
+	//NOTE(fpalacios): Tablas [[theme.postprocess]], se aplican en el orden en que aparecen (ver postprocess.rs)
+	pub postprocess: Vec<crate::postprocess::PostProcessPass>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GardenConfig
+{
+	//NOTE(fpalacios): Cuanto tarda en secarse una planta recien regada. Con None no se secan nunca y no hace falta regarlas
+	#[serde(rename = "drying_hours", deserialize_with = "drying_hours")]
+	pub drying: Option<std::time::Duration>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config
+{
+	pub window: WindowConfig,
+	#[serde(deserialize_with = "fps")]
+	pub fps   : u32,
+	#[serde(deserialize_with = "plants")]
+	pub plants: Vec<crate::world::PlantDefinition>,
+	pub theme : Theme,
+	pub garden: GardenConfig,
+}
+
+#[derive(Debug)]
+pub enum ConfigError
+{
+	Io
+	{
+		path : std::path::PathBuf,
+		error: std::io::Error,
+	},
+	Toml
+	{
+		path : std::path::PathBuf,
+		error: toml::de::Error,
+	},
+}
+
+impl std::fmt::Display for ConfigError
+{
+	fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result
+	{
+		return match self
+		{
+			ConfigError::Io { path, error }   => write!(formatter, "No se pudo leer {}: {}", path.display(), error),
+			ConfigError::Toml { path, error } => write!(formatter, "Error en {}, {}", path.display(), error),
+		};
+	}
+}
+
+impl Default for WindowConfig
+{
+	fn default() -> WindowConfig
+	{
+		return WindowConfig
+		{
+			mode   : WindowMode::Desktop,
+			monitor: crate::layout::MonitorSelection::Primary,
+			anchor : crate::layout::Anchor::CENTER,
+			scale  : 1.0,
+		};
+	}
+}
+
+impl Default for Theme
+{
+	fn default() -> Theme
+	{
+		return Theme
+		{
+			tint       : crate::color::RgbaColor::WHITE,
+			opacity    : 1.0,
+			postprocess: Vec::new(),
+		};
+	}
+}
+
+impl Default for GardenConfig
+{
+	fn default() -> GardenConfig
+	{
+		return GardenConfig
+		{
+			drying: None,
+		};
+	}
+}
+
+impl Default for Config
+{
+	fn default() -> Config
+	{
+		return Config::new();
+	}
+}
+
+impl Config
+{
+	pub fn new() -> Config
+	{
+		return Config
+		{
+			window: WindowConfig::default(),
+			fps   : 60,
+			plants: vec![
+				crate::world::PlantDefinition
+				{
+					name: String::from("bonsai"),
+					kind: crate::world::PlantKind::Sprite
+					{
+						frames        : vec![
+							String::from("Bonsai_1-500x500.png"),
+							String::from("Bonsai_2-500x500.png"),
+							String::from("Bonsai_3-500x500.png"),
+						],
+						frame_duration: std::time::Duration::from_millis(1000),
+					},
+				},
+			],
+			theme : Theme::default(),
+			garden: GardenConfig::default(),
+		};
+	}
+
+	//NOTE(fpalacios): $XDG_CONFIG_HOME/jardincho/config.toml, o ~/.config si la variable no esta
+	pub fn default_path() -> Option<std::path::PathBuf>
+	{
+		let config_home = std::env::var_os("XDG_CONFIG_HOME")
+			.filter(|value| !value.is_empty())
+			.map(std::path::PathBuf::from)
+			.or_else(|| std::env::var_os("HOME").map(|home| std::path::Path::new(&home).join(".config")))?;
+
+		return Some(config_home.join("jardincho").join(CONFIG_FILE));
+	}
+
+	//NOTE(fpalacios): Si el archivo no existe no es un error, es la configuracion por defecto
+	pub fn load(path: &std::path::Path) -> Result<Config, ConfigError>
+	{
+		let source = match std::fs::read_to_string(path)
+		{
+			Ok(source) => source,
+			Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Config::new()),
+			Err(err) => return Err(ConfigError::Io { path: path.to_path_buf(), error: err }),
+		};
+
+		return Config::parse(&source, path.parent().unwrap_or_else(|| std::path::Path::new(".")))
+			.map_err(|err| ConfigError::Toml { path: path.to_path_buf(), error: err });
+	}
+
+	//NOTE(fpalacios): Los modelos y las LUTs se escriben relativos al archivo de configuracion
+	pub fn parse(source: &str, base_directory: &std::path::Path) -> Result<Config, toml::de::Error>
+	{
+		let mut config: Config = toml::from_str(source)?;
+
+		for plant in &mut config.plants
+		{
+			if let crate::world::PlantKind::Model(path) = &mut plant.kind
+			{
+				*path = base_directory.join(&*path);
+			}
+		}
+
+		for pass in &mut config.theme.postprocess
+		{
+			if let crate::postprocess::PostProcessPass::ColorGrading { lut, .. } = pass
+			{
+				*lut = base_directory.join(&*lut);
+			}
+		}
+
+		return Ok(config);
+	}
+}
+
+//NOTE(fpalacios): Una [[plants]] tal cual esta en el archivo, despues se valida y se pasa a PlantDefinition
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PlantTable
+{
+	name             : String,
+	frames           : Option<Vec<String>>,
+	frame_duration_ms: Option<u64>,
+	model            : Option<std::path::PathBuf>,
+}
+
+fn plant_definition(plant: PlantTable, context: &str) -> Result<crate::world::PlantDefinition, String>
+{
+	if plant.name.is_empty()
+	{
+		return Err(format!("{}: name no puede estar vacio", context));
+	}
+
+	let kind = match (plant.frames, plant.model)
+	{
+		(Some(frames), None) =>
+		{
+			if frames.is_empty()
+			{
+				return Err(format!("{}.frames tiene que ser una lista de nombres de imagenes, ej: [\"Bonsai_1-500x500.png\"]", context));
+			}
+
+			let frame_duration_ms = plant.frame_duration_ms.unwrap_or(1000);
+
+			if !(1 ..= 3_600_000).contains(&frame_duration_ms)
+			{
+				return Err(format!("{}.frame_duration_ms tiene que ser un numero entero entre 1 y 3600000", context));
+			}
+
+			crate::world::PlantKind::Sprite
+			{
+				frames,
+				frame_duration: std::time::Duration::from_millis(frame_duration_ms),
+			}
+		},
+		(None, Some(model)) => crate::world::PlantKind::Model(model),
+		(Some(_), Some(_))  => return Err(format!("{}: tiene frames y model, tiene que ser una cosa o la otra", context)),
+		(None, None)        => return Err(format!("{}: falta frames (para una planta 2D) o model (para una 3D)", context)),
+	};
+
+	return Ok(
+		crate::world::PlantDefinition
+		{
+			name: plant.name,
+			kind,
+		}
+	);
+}
+
+fn plants<'de, D>(deserializer: D) -> Result<Vec<crate::world::PlantDefinition>, D::Error>
+where
+	D: serde::Deserializer<'de>,
+{
+	let tables: Vec<PlantTable> = serde::Deserialize::deserialize(deserializer)?;
+
+	if tables.is_empty()
+	{
+		return Err(serde::de::Error::custom("plants tiene que ser una lista de [[plants]] con al menos una planta"));
+	}
+
+	let mut plants: Vec<crate::world::PlantDefinition> = Vec::new();
+
+	for (index, table) in tables.into_iter().enumerate()
+	{
+		let context = format!("plants[{}]", index);
+		let definition = plant_definition(table, &context).map_err(serde::de::Error::custom)?;
+
+		if plants.iter().any(|other| other.name == definition.name)
+		{
+			return Err(serde::de::Error::custom(format!("{}: ya hay otra planta que se llama \"{}\"", context, definition.name)));
+		}
+
+		plants.push(definition);
+	}
+
+	return Ok(plants);
+}
+
+fn fps<'de, D>(deserializer: D) -> Result<u32, D::Error>
+where
+	D: serde::Deserializer<'de>,
+{
+	let fps: i64 = serde::Deserialize::deserialize(deserializer)?;
+
+	if !(1 ..= MAX_FPS as i64).contains(&fps)
+	{
+		return Err(serde::de::Error::custom(format!("fps tiene que ser un numero entero entre 1 y {}", MAX_FPS)));
+	}
+
+	return Ok(fps as u32);
+}
+
+fn monitor<'de, D>(deserializer: D) -> Result<crate::layout::MonitorSelection, D::Error>
+where
+	D: serde::Deserializer<'de>,
+{
+	let monitor: String = serde::Deserialize::deserialize(deserializer)?;
+
+	return Ok(crate::layout::MonitorSelection::parse(&monitor));
+}
+
+fn anchor<'de, D>(deserializer: D) -> Result<crate::layout::Anchor, D::Error>
+where
+	D: serde::Deserializer<'de>,
+{
+	let anchor: String = serde::Deserialize::deserialize(deserializer)?;
+
+	return crate::layout::Anchor::parse(&anchor).ok_or_else(||
+		serde::de::Error::custom(format!("window.anchor tiene que ser uno de {}, no \"{}\"", crate::layout::Anchor::NAMES.join(", "), anchor))
+	);
+}
+
+fn tint<'de, D>(deserializer: D) -> Result<crate::color::RgbaColor, D::Error>
+where
+	D: serde::Deserializer<'de>,
+{
+	let tint: String = serde::Deserialize::deserialize(deserializer)?;
+
+	return crate::color::RgbaColor::from_hex(&tint).ok_or_else(||
+		serde::de::Error::custom(format!("theme.tint tiene que ser un color \"#rrggbb\" o \"#rrggbbaa\", no \"{}\"", tint))
+	);
+}
+
+fn scale<'de, D>(deserializer: D) -> Result<f32, D::Error>
+where
+	D: serde::Deserializer<'de>,
+{
+	return number(deserializer, "window.scale", 0.05, 4.0);
+}
+
+fn opacity<'de, D>(deserializer: D) -> Result<f32, D::Error>
+where
+	D: serde::Deserializer<'de>,
+{
+	return number(deserializer, "theme.opacity", 0.0, 1.0);
+}
+
+fn drying_hours<'de, D>(deserializer: D) -> Result<Option<std::time::Duration>, D::Error>
+where
+	D: serde::Deserializer<'de>,
+{
+	let hours = number(deserializer, "garden.drying_hours", 0.1, 24.0 * 30.0)?;
+
+	return Ok(Some(std::time::Duration::from_secs_f32(hours * 60.0 * 60.0)));
+}
+
+//NOTE(fpalacios): TOML acepta nan e inf, no pasan el rango porque las comparaciones con nan son siempre falsas
+fn number<'de, D>(deserializer: D, name: &str, min: f32, max: f32) -> Result<f32, D::Error>
+where
+	D: serde::Deserializer<'de>,
+{
+	let value: f64 = serde::Deserialize::deserialize(deserializer)?;
+	let value = value as f32;
+
+	if !(value >= min && value <= max)
+	{
+		return Err(serde::de::Error::custom(format!("{} tiene que ser un numero entre {} y {}", name, min, max)));
+	}
+
+	return Ok(value);
+}
+
+#[cfg(test)]
+mod tests
+{
+	fn parse(source: &str) -> Result<super::Config, String>
+	{
+		return super::Config::parse(source, std::path::Path::new("/home/jardin/.config/jardincho")).map_err(|err| err.to_string());
+	}
+
+	fn assert_error(source: &str, expected: &str)
+	{
+		match parse(source)
+		{
+			Ok(_)      => panic!("{:?} tendria que fallar", source),
+			Err(error) => assert!(error.contains(expected), "{:?} en vez de algo con {:?}", error, expected),
+		}
+	}
+
+	#[test]
+	fn empty_file_is_the_default()
+	{
+		assert_eq!(parse(""), Ok(super::Config::new()));
+	}
+
+	#[test]
+	fn every_option()
+	{
+		let source = "\
+fps = 30
+
+[window]
+mode = \"overlay\"
+monitor = \"HDMI-1\"
+anchor = \"bottom-right\"
+scale = 0.5
+
+[theme]
+tint = \"#ff000080\"
+opacity = 0.75
+
+[[theme.postprocess]]
+type = \"color_grading\"
+lut = \"luts/otono.png\"
+
+[[theme.postprocess]]
+type = \"vignette\"
+strength = 0.25
+
+[garden]
+drying_hours = 1.5
+
+[[plants]]
+name = \"bonsai\"
+frames = [\"a.png\", \"b.png\"]
+frame_duration_ms = 250
+
+[[plants]]
+name = \"helecho\"
+model = \"modelos/helecho.glb\"
+";
+
+		let config = parse(source).unwrap();
+
+		assert_eq!(config.fps, 30);
+		assert_eq!(config.window.mode, super::WindowMode::Overlay);
+		assert_eq!(config.window.monitor, crate::layout::MonitorSelection::Named(String::from("HDMI-1")));
+		assert_eq!(config.window.anchor, crate::layout::Anchor::parse("bottom-right").unwrap());
+		assert_eq!(config.window.scale, 0.5);
+		assert_eq!(config.theme.tint, crate::color::RgbaColor::from_hex("#ff000080").unwrap());
+		assert_eq!(config.theme.opacity, 0.75);
+		assert_eq!(
+			config.theme.postprocess,
+			vec![
+				crate::postprocess::PostProcessPass::ColorGrading
+				{
+					lut     : std::path::PathBuf::from("/home/jardin/.config/jardincho/luts/otono.png"),
+					strength: 1.0,
+				},
+				crate::postprocess::PostProcessPass::Vignette
+				{
+					strength: 0.25,
+					radius  : 0.6,
+					softness: 0.45,
+				},
+			]
+		);
+		assert_eq!(config.garden.drying, Some(std::time::Duration::from_secs(90 * 60)));
+		assert_eq!(
+			config.plants,
+			vec![
+				crate::world::PlantDefinition
+				{
+					name: String::from("bonsai"),
+					kind: crate::world::PlantKind::Sprite
+					{
+						frames        : vec![String::from("a.png"), String::from("b.png")],
+						frame_duration: std::time::Duration::from_millis(250),
+					},
+				},
+				crate::world::PlantDefinition
+				{
+					name: String::from("helecho"),
+					kind: crate::world::PlantKind::Model(std::path::PathBuf::from("/home/jardin/.config/jardincho/modelos/helecho.glb")),
+				},
+			]
+		);
+	}
+
+	#[test]
+	fn toml_syntax()
+	{
+		assert_eq!(parse("fps = 0x1E").unwrap().fps, 30);
+		assert_eq!(parse("fps = 1_2").unwrap().fps, 12);
+		assert_eq!(parse("window = { scale = 2 }").unwrap().window.scale, 2.0);
+		assert_eq!(parse("# comentario\n[theme] # otro\nopacity = 5e-1").unwrap().theme.opacity, 0.5);
+
+		assert_error("fps = 30\nfps = 31", "duplicate key");
+		assert_error("[window]\nscale = ", "TOML parse error at line 2, column 9");
+		assert_error("[window\nscale = 1", "invalid table header");
+	}
+
+	#[test]
+	fn unknown_keys()
+	{
+		assert_error("fsp = 30", "unknown field `fsp`");
+		assert_error("[window]\nmonitr = \"all\"", "unknown field `monitr`");
+		assert_error("[theme]\nopacity = 1\ncolor = \"#ffffff\"", "unknown field `color`");
+		assert_error("[garden]\ndrying = 1", "unknown field `drying`");
+		assert_error("[[plants]]\nname = \"a\"\nframes = [\"a.png\"]\nframe_duration = 1", "unknown field `frame_duration`");
+		assert_error("[[theme.postprocess]]\ntype = \"glow\"\nradio = 1", "unknown field `radio`");
+		assert_error("[[theme.postprocess]]\ntype = \"blur\"", "unknown variant `blur`");
+		assert_error("window = 1", "invalid type: integer `1`");
+	}
+
+	#[test]
+	fn ranges()
+	{
+		assert_error("fps = 0", "fps tiene que ser un numero entero entre 1 y 240");
+		assert_error("fps = 241", "fps tiene que ser un numero entero entre 1 y 240");
+		assert_error("fps = 29.5", "invalid type: floating point `29.5`");
+		assert_error("fps = \"30\"", "invalid type: string \"30\"");
+		assert_error("[window]\nscale = nan", "window.scale tiene que ser un numero entre 0.05 y 4");
+		assert_error("[window]\nscale = inf", "window.scale tiene que ser un numero entre 0.05 y 4");
+		assert_error("[window]\nscale = 0", "window.scale tiene que ser un numero entre 0.05 y 4");
+		assert_error("[theme]\nopacity = 1.5", "theme.opacity tiene que ser un numero entre 0 y 1");
+		assert_error("[[theme.postprocess]]\ntype = \"glow\"\nthreshold = 1", "glow.threshold tiene que ser un numero entre 0 y 0.99");
+		assert_error("[[theme.postprocess]]\ntype = \"pixelate\"\npixel_size = -inf", "pixelate.pixel_size tiene que ser un numero entre 1 y 256");
+		assert_error("[garden]\ndrying_hours = 0", "garden.drying_hours tiene que ser un numero entre 0.1 y 720");
+		assert_error("[[plants]]\nname = \"a\"\nframes = [\"a.png\"]\nframe_duration_ms = 0", "plants[0].frame_duration_ms tiene que ser un numero");
+
+		assert_eq!(parse("fps = 1").unwrap().fps, 1);
+		assert_eq!(parse("fps = 240").unwrap().fps, 240);
+	}
+
+	#[test]
+	fn invalid_values()
+	{
+		assert_error("[window]\nmode = \"fullscreen\"", "unknown variant `fullscreen`, expected one of `desktop`, `overlay`, `window`");
+		assert_error("[window]\nanchor = \"middle\"", "window.anchor tiene que ser uno de center");
+		assert_error("[window]\nmode = 1", "wanted string or table");
+		assert_error("[theme]\ntint = \"rojo\"", "theme.tint tiene que ser un color");
+		assert_error("[[theme.postprocess]]\ntype = \"color_grading\"", "missing field `lut`");
+		assert_error("[[theme.postprocess]]\nstrength = 1", "missing field `type`");
+		assert_error("plants = []", "plants tiene que ser una lista de [[plants]] con al menos una planta");
+		assert_error("[[plants]]\nframes = [\"a.png\"]", "missing field `name`");
+		assert_error("[[plants]]\nname = \"a\"", "plants[0]: falta frames");
+		assert_error("[[plants]]\nname = \"a\"\nframes = []", "plants[0].frames tiene que ser una lista");
+		assert_error("[[plants]]\nname = \"a\"\nframes = [\"a.png\"]\nmodel = \"a.obj\"", "plants[0]: tiene frames y model");
+		assert_error(
+			"[[plants]]\nname = \"a\"\nframes = [\"a.png\"]\n[[plants]]\nname = \"a\"\nmodel = \"a.obj\"",
+			"plants[1]: ya hay otra planta que se llama \"a\""
+		);
+	}
+}
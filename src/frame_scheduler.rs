@@ -0,0 +1,55 @@
+//NOTE(fpalacios): Decide cuando hay que despertarse para el proximo frame. Si no hay nada moviendose se duerme hasta
+//el proximo cambio de paso de las animaciones en vez de dibujar a max_fps
+pub struct FrameScheduler
+{
+	pub frame_delay          : std::time::Duration,
+	pub throttled_frame_delay: std::time::Duration,
+	last_frame               : std::time::Instant,
+}
+
+impl FrameScheduler
+{
+	pub fn new(max_fps: u32) -> FrameScheduler
+	{
+		return FrameScheduler
+		{
+			frame_delay          : std::time::Duration::from_secs(1) / std::cmp::max(max_fps, 1),
+			throttled_frame_delay: std::time::Duration::from_secs(1) / 5,
+			last_frame           : std::time::Instant::now(),
+		};
+	}
+
+	pub fn frame_drawn(&mut self, now: std::time::Instant)
+	{
+		self.last_frame = now;
+	}
+
+	//NOTE(fpalacios): None quiere decir que no hay nada programado y se puede esperar hasta el proximo evento
+	pub fn next_deadline(
+		&self,
+		now        : std::time::Instant,
+		world      : &crate::world::World,
+		game_loop  : &crate::game_loop::GameLoop,
+		render_mode: crate::visibility::RenderMode
+	)
+	-> Option<std::time::Instant>
+	{
+		let frame_delay = match render_mode
+		{
+			crate::visibility::RenderMode::Normal    => self.frame_delay,
+			crate::visibility::RenderMode::Throttled => std::cmp::max(self.frame_delay, self.throttled_frame_delay),
+			crate::visibility::RenderMode::Suspended => return None,
+		};
+
+		let earliest_frame = self.last_frame + frame_delay;
+
+		if world.is_animating()
+		{
+			return Some(std::cmp::max(earliest_frame, now));
+		}
+
+		let next_change = now + game_loop.time_until_simulated(world.time_until_next_change()?);
+
+		return Some(std::cmp::max(earliest_frame, next_change));
+	}
+}
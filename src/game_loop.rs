@@ -0,0 +1,110 @@
+//NOTE(fpalacios): Simulacion a paso fijo con acumulador. El tiempo real que pasa entre frames se va juntando y se
+//consume de a ticks de duracion fija, lo que sobra queda para el proximo frame y se usa para interpolar al dibujar
+pub struct GameLoop
+{
+	pub tick        : std::time::Duration,
+	//NOTE(fpalacios): Lo maximo que se simula de a ticks en un frame. Si la compu vuelve de una suspension o el frame se trabo,
+	//lo que pase de esto no se simula tick por tick, vuelve en Step::skipped
+	pub max_catch_up: std::time::Duration,
+	accumulator     : std::time::Duration,
+	last_time       : std::time::Instant,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Step
+{
+	pub ticks        : u32,
+	//NOTE(fpalacios): Tiempo que paso y no entro en los ticks, para adelantar de una lo que cambia lento (agua, animaciones)
+	pub skipped      : std::time::Duration,
+	//NOTE(fpalacios): Fraccion de tick (0.0 a 1.0) que quedó en el acumulador, para interpolar entre el estado anterior y el actual
+	pub interpolation: f32,
+}
+
+impl GameLoop
+{
+	pub fn new(tick: std::time::Duration, max_catch_up: std::time::Duration) -> GameLoop
+	{
+		return GameLoop
+		{
+			tick,
+			max_catch_up,
+			accumulator: std::time::Duration::from_nanos(0),
+			last_time  : std::time::Instant::now(),
+		};
+	}
+
+	pub fn advance(&mut self, now: std::time::Instant) -> Step
+	{
+		let elapsed = now.saturating_duration_since(self.last_time);
+		self.last_time = now;
+
+		self.accumulator += elapsed;
+
+		let skipped = self.accumulator.saturating_sub(self.max_catch_up);
+		self.accumulator -= skipped;
+
+		let mut ticks = 0;
+		while self.accumulator >= self.tick
+		{
+			self.accumulator -= self.tick;
+			ticks += 1;
+		}
+
+		return Step
+		{
+			ticks,
+			skipped,
+			interpolation: self.accumulator.as_secs_f32() / self.tick.as_secs_f32(),
+		};
+	}
+
+	//NOTE(fpalacios): Cuanto tiempo real falta para que se simule `simulated` tiempo mas, redondeado al proximo tick
+	pub fn time_until_simulated(&self, simulated: std::time::Duration) -> std::time::Duration
+	{
+		let missing = simulated.checked_sub(self.accumulator).unwrap_or_default();
+		//NOTE(fpalacios): Como minimo un tick, si no el deadline queda en el pasado y el loop se pone a girar sin simular nada
+		let ticks = std::cmp::max(missing.as_nanos().div_ceil(self.tick.as_nanos()), 1);
+
+		return self.tick * ticks as u32;
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	const TICK        : std::time::Duration = std::time::Duration::from_millis(10);
+	const MAX_CATCH_UP: std::time::Duration = std::time::Duration::from_millis(250);
+
+	#[test]
+	fn ticks_and_interpolation()
+	{
+		let mut game_loop = super::GameLoop::new(TICK, MAX_CATCH_UP);
+		let start = game_loop.last_time;
+
+		let step = game_loop.advance(start + std::time::Duration::from_millis(35));
+		assert_eq!(step.ticks, 3);
+		assert_eq!(step.skipped, std::time::Duration::from_millis(0));
+		assert!((step.interpolation - 0.5).abs() < 1e-4);
+
+		let step = game_loop.advance(start + std::time::Duration::from_millis(40));
+		assert_eq!(step.ticks, 1);
+		assert!(step.interpolation.abs() < 1e-4);
+	}
+
+	#[test]
+	fn long_pauses_are_skipped()
+	{
+		let mut game_loop = super::GameLoop::new(TICK, MAX_CATCH_UP);
+		let start = game_loop.last_time;
+
+		//NOTE(fpalacios): Una suspension de una hora no son 360000 ticks
+		let step = game_loop.advance(start + std::time::Duration::from_secs(60 * 60) + std::time::Duration::from_millis(5));
+		assert_eq!(step.ticks, 25);
+		assert_eq!(step.skipped, std::time::Duration::from_secs(60 * 60) + std::time::Duration::from_millis(5) - MAX_CATCH_UP);
+		assert!(step.interpolation.abs() < 1e-4);
+
+		let step = game_loop.advance(start + std::time::Duration::from_secs(60 * 60) + std::time::Duration::from_millis(20));
+		assert_eq!(step.ticks, 1);
+		assert_eq!(step.skipped, std::time::Duration::from_millis(0));
+	}
+}
@@ -0,0 +1,155 @@
+//NOTE(fpalacios): El agua de las plantas sembradas y el clima. World guarda el estado y llama a estas funciones en cada tick
+
+//NOTE(fpalacios): Segundos que tarda en llenarse con lluvia una planta seca. Lo que tarda en secarse esta en la configuracion
+const RAIN_SECONDS: f32 = 10.0 * 60.0;
+
+//NOTE(fpalacios): Por debajo de este nivel de agua la planta se empieza a poner amarilla, en 0 deja de crecer
+const THIRSTY_WATER: f32 = 0.25;
+const DRY_TINT     : crate::color::RgbaColor = crate::color::RgbaColor { r: 0.8, g: 0.65, b: 0.4, a: 1.0 };
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Weather
+{
+	Clear,
+	Cloudy,
+	//NOTE(fpalacios): Riega todas las plantas mientras dura
+	Rain,
+}
+
+impl Weather
+{
+	pub const NAMES: &'static [&'static str] = &["clear", "cloudy", "rain"];
+
+	pub fn parse(value: &str) -> Option<Weather>
+	{
+		return match value
+		{
+			"clear"  => Some(Weather::Clear),
+			"cloudy" => Some(Weather::Cloudy),
+			"rain"   => Some(Weather::Rain),
+			_        => None,
+		};
+	}
+
+	pub fn name(&self) -> &'static str
+	{
+		return match self
+		{
+			Weather::Clear  => "clear",
+			Weather::Cloudy => "cloudy",
+			Weather::Rain   => "rain",
+		};
+	}
+
+	pub fn tint(&self) -> crate::color::RgbaColor
+	{
+		return match self
+		{
+			Weather::Clear  => crate::color::RgbaColor::WHITE,
+			Weather::Cloudy => crate::color::RgbaColor::new(0.85, 0.87, 0.92, 1.0),
+			Weather::Rain   => crate::color::RgbaColor::new(0.72, 0.76, 0.86, 1.0),
+		};
+	}
+}
+
+//NOTE(fpalacios): Una planta sembrada. Cada una se dibuja una vez en cada monitor
+#[derive(Debug, Clone, PartialEq)]
+pub struct GardenPlant
+{
+	//NOTE(fpalacios): El name de la PlantDefinition
+	pub name : String,
+	//NOTE(fpalacios): De 0.0 (seca) a 1.0 (recien regada)
+	pub water: f32,
+}
+
+impl GardenPlant
+{
+	pub fn new(name: &str) -> GardenPlant
+	{
+		return GardenPlant
+		{
+			name : String::from(name),
+			water: 1.0,
+		};
+	}
+}
+
+
+//NOTE(fpalacios): Las plantas se secan si esta activado en la configuracion, o se llenan si llueve
+pub fn pass_time(plants: &mut [GardenPlant], weather: Weather, drying: Option<std::time::Duration>, seconds: f32)
+{
+	for plant in plants
+	{
+		plant.water = match (weather, drying)
+		{
+			(Weather::Rain, _) => (plant.water + seconds / RAIN_SECONDS).min(1.0),
+			(_, Some(drying))  => (plant.water - seconds / drying.as_secs_f32()).max(0.0),
+			(_, None)          => plant.water,
+		};
+	}
+}
+
+//NOTE(fpalacios): Sin agua deja de crecer, salvo que no se sequen nunca
+pub fn has_water(drying: Option<std::time::Duration>, plant: &GardenPlant) -> bool
+{
+	return drying.is_none() || plant.water > 0.0;
+}
+
+//NOTE(fpalacios): El tinte del clima, y mas amarillo cuanto mas seca esta la planta. Se multiplica por el del tema
+pub fn tint(weather: Weather, drying: Option<std::time::Duration>, plant: &GardenPlant) -> crate::color::RgbaColor
+{
+	let dryness = match drying
+	{
+		Some(_) => ((THIRSTY_WATER - plant.water) / THIRSTY_WATER).max(0.0),
+		None    => 0.0,
+	};
+
+	return weather.tint().multiply(&crate::color::RgbaColor::WHITE.lerp(&DRY_TINT, dryness));
+}
+
+#[cfg(test)]
+mod tests
+{
+	#[test]
+	fn rain_fills_and_drying_empties()
+	{
+		let drying = Some(std::time::Duration::from_secs(100));
+
+		let mut plants = vec![super::GardenPlant::new("a")];
+		super::pass_time(&mut plants, super::Weather::Clear, drying, 75.0);
+		assert_eq!(plants[0].water, 0.25);
+
+		super::pass_time(&mut plants, super::Weather::Clear, drying, 1000.0);
+		assert_eq!(plants[0].water, 0.0);
+		assert!(!super::has_water(drying, &plants[0]));
+		assert!(super::has_water(None, &plants[0]));
+
+		super::pass_time(&mut plants, super::Weather::Rain, drying, super::RAIN_SECONDS / 2.0);
+		assert_eq!(plants[0].water, 0.5);
+
+		super::pass_time(&mut plants, super::Weather::Rain, drying, super::RAIN_SECONDS);
+		assert_eq!(plants[0].water, 1.0);
+
+		//NOTE(fpalacios): Sin drying_hours el agua no cambia salvo que llueva
+		plants[0].water = 0.5;
+		super::pass_time(&mut plants, super::Weather::Cloudy, None, 1000.0);
+		assert_eq!(plants[0].water, 0.5);
+	}
+
+	#[test]
+	fn tint_follows_weather_and_water()
+	{
+		let drying = Some(std::time::Duration::from_secs(100));
+		let mut plant = super::GardenPlant::new("a");
+
+		assert_eq!(super::tint(super::Weather::Clear, drying, &plant), crate::color::RgbaColor::WHITE);
+		assert_eq!(super::tint(super::Weather::Rain, drying, &plant), super::Weather::Rain.tint());
+
+		plant.water = 0.125;
+		assert_eq!(super::tint(super::Weather::Clear, drying, &plant), crate::color::RgbaColor::WHITE.lerp(&super::DRY_TINT, 0.5));
+
+		plant.water = 0.0;
+		assert_eq!(super::tint(super::Weather::Clear, drying, &plant), crate::color::RgbaColor::WHITE.lerp(&super::DRY_TINT, 1.0));
+		assert_eq!(super::tint(super::Weather::Clear, None, &plant), crate::color::RgbaColor::WHITE);
+	}
+}
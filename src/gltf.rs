@@ -0,0 +1,1159 @@
+//NOTE(fpalacios): Cargador de glTF 2.0 (.gltf con buffers aparte o embebidos en base64, y .glb). Se arma un
+//mesh::Model con las mallas de la escena, sus materiales (color base y textura), la jerarquia de nodos, los esqueletos
+//y la primera animacion
+#[derive(Debug)]
+pub enum GltfError
+{
+	Io(std::io::Error),
+	Json(crate::json::JsonError),
+	Glb(String),
+	Invalid(String),
+	Unsupported(String),
+	Image(image::ImageError),
+}
+
+const GLB_MAGIC     : &[u8] = b"glTF";
+const GLB_CHUNK_JSON: u32   = 0x4E4F534A;
+const GLB_CHUNK_BIN : u32   = 0x004E4942;
+
+const COMPONENT_I8 : usize = 5120;
+const COMPONENT_U8 : usize = 5121;
+const COMPONENT_I16: usize = 5122;
+const COMPONENT_U16: usize = 5123;
+const COMPONENT_U32: usize = 5125;
+const COMPONENT_F32: usize = 5126;
+
+const MODE_TRIANGLES     : usize = 4;
+const MODE_TRIANGLE_STRIP: usize = 5;
+const MODE_TRIANGLE_FAN  : usize = 6;
+
+fn invalid(message: String) -> GltfError
+{
+	return GltfError::Invalid(message);
+}
+
+//NOTE(fpalacios): Sin bufferView no hay datos que limiten el count del accessor, asi que se pone un tope antes de llenarlo
+//de ceros para que un archivo roto no pida toda la memoria
+const MAX_ZEROED_VALUES: usize = 1 << 24;
+
+pub fn load(path: &std::path::Path) -> Result<crate::mesh::Model, GltfError>
+{
+	let bytes = std::fs::read(path).map_err(GltfError::Io)?;
+
+	return parse(&bytes, path.parent().unwrap_or_else(|| std::path::Path::new(".")));
+}
+
+//NOTE(fpalacios): base_directory es donde se buscan los buffers y las imagenes que no vienen embebidos
+fn parse(bytes: &[u8], base_directory: &std::path::Path) -> Result<crate::mesh::Model, GltfError>
+{
+	let (json_text, glb_binary) = if bytes.starts_with(GLB_MAGIC)
+	{
+		parse_glb(bytes)?
+	}
+	else
+	{
+		(String::from_utf8(bytes.to_vec()).map_err(|_| invalid(String::from("El .gltf no es UTF-8")))?, None)
+	};
+
+	let document = crate::json::parse(&json_text).map_err(GltfError::Json)?;
+
+	let version = document.get("asset").and_then(|asset| asset.get("version")).and_then(|version| version.as_str()).unwrap_or("");
+	if !version.starts_with("2.")
+	{
+		return Err(GltfError::Unsupported(format!("Version de glTF {:?}, solo se soporta 2.x", version)));
+	}
+
+	let gltf = Gltf
+	{
+		buffers: load_buffers(&document, glb_binary, base_directory)?,
+		document,
+	};
+
+	return gltf.build_model(base_directory);
+}
+
+//NOTE(fpalacios): Header de 12 bytes y despues chunks con largo y tipo, el primero JSON y opcionalmente uno binario
+fn parse_glb(bytes: &[u8]) -> Result<(String, Option<Vec<u8>>), GltfError>
+{
+	let read_u32 = |offset: usize| -> Result<u32, GltfError>
+	{
+		let chunk = bytes.get(offset .. offset + 4).ok_or_else(|| GltfError::Glb(String::from("Archivo cortado")))?;
+		return Ok(u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]));
+	};
+
+	let version = read_u32(4)?;
+	if version != 2
+	{
+		return Err(GltfError::Glb(format!("Version de contenedor {} no soportada", version)));
+	}
+
+	let length = std::cmp::min(read_u32(8)? as usize, bytes.len());
+
+	let mut json   = None;
+	let mut binary = None;
+	let mut offset = 12;
+
+	while offset + 8 <= length
+	{
+		let chunk_length = read_u32(offset)? as usize;
+		let chunk_type   = read_u32(offset + 4)?;
+
+		let chunk = bytes.get(offset + 8 .. offset + 8 + chunk_length).ok_or_else(|| GltfError::Glb(String::from("Chunk cortado")))?;
+
+		match chunk_type
+		{
+			GLB_CHUNK_JSON if json.is_none() =>
+			{
+				json = Some(String::from_utf8(chunk.to_vec()).map_err(|_| GltfError::Glb(String::from("El JSON no es UTF-8")))?);
+			},
+			GLB_CHUNK_BIN if binary.is_none() => binary = Some(chunk.to_vec()),
+			//NOTE(fpalacios): Los chunks desconocidos se ignoran
+			_ => {},
+		}
+
+		//NOTE(fpalacios): Los chunks estan alineados a 4 bytes
+		offset += 8 + ((chunk_length + 3) & !3);
+	}
+
+	let json = json.ok_or_else(|| GltfError::Glb(String::from("Falta el chunk JSON")))?;
+
+	return Ok((json, binary));
+}
+
+fn load_buffers(document: &crate::json::Json, mut glb_binary: Option<Vec<u8>>, base_directory: &std::path::Path) -> Result<Vec<Vec<u8>>, GltfError>
+{
+	let mut buffers = Vec::new();
+
+	for (index, buffer) in array(document, "buffers").iter().enumerate()
+	{
+		let data = match buffer.get("uri").and_then(|uri| uri.as_str())
+		{
+			Some(uri) => read_uri(uri, base_directory)?,
+			//NOTE(fpalacios): Sin uri es el chunk binario del .glb, solo puede ser el primero
+			None if index == 0 => glb_binary.take().ok_or_else(|| invalid(String::from("El buffer 0 no tiene uri ni chunk binario")))?,
+			None => return Err(invalid(format!("El buffer {} no tiene uri", index))),
+		};
+
+		let byte_length = buffer.get("byteLength").and_then(|length| length.as_usize()).unwrap_or(data.len());
+		if data.len() < byte_length
+		{
+			return Err(invalid(format!("El buffer {} tiene {} bytes y deberia tener {}", index, data.len(), byte_length)));
+		}
+
+		buffers.push(data);
+	}
+
+	return Ok(buffers);
+}
+
+fn read_uri(uri: &str, base_directory: &std::path::Path) -> Result<Vec<u8>, GltfError>
+{
+	if let Some(data) = uri.strip_prefix("data:")
+	{
+		let (header, payload) = data.split_once(',').ok_or_else(|| invalid(String::from("Data URI sin ','")))?;
+		if !header.ends_with(";base64")
+		{
+			return Err(GltfError::Unsupported(String::from("Data URI que no es base64")));
+		}
+
+		return decode_base64(payload).ok_or_else(|| invalid(String::from("Base64 invalido en data URI")));
+	}
+
+	return std::fs::read(base_directory.join(percent_decode(uri))).map_err(GltfError::Io);
+}
+
+fn decode_base64(text: &str) -> Option<Vec<u8>>
+{
+	let mut output = Vec::with_capacity(text.len() * 3 / 4);
+	let mut accumulator: u32 = 0;
+	let mut bits = 0;
+
+	for byte in text.bytes()
+	{
+		let value = match byte
+		{
+			b'A' ..= b'Z' => byte - b'A',
+			b'a' ..= b'z' => byte - b'a' + 26,
+			b'0' ..= b'9' => byte - b'0' + 52,
+			b'+' | b'-'   => 62,
+			b'/' | b'_'   => 63,
+			b'='          => break,
+			b' ' | b'\n' | b'\r' | b'\t' => continue,
+			_             => return None,
+		};
+
+		accumulator = (accumulator << 6) | value as u32;
+		bits += 6;
+
+		if bits >= 8
+		{
+			bits -= 8;
+			output.push((accumulator >> bits) as u8);
+			accumulator &= (1 << bits) - 1;
+		}
+	}
+
+	return Some(output);
+}
+
+//NOTE(fpalacios): Las uri relativas pueden venir con espacios y otros caracteres escapados como %20
+fn percent_decode(uri: &str) -> String
+{
+	let bytes = uri.as_bytes();
+	let mut output = Vec::with_capacity(bytes.len());
+	let mut index = 0;
+
+	while index < bytes.len()
+	{
+		//NOTE(fpalacios): Se mira byte por byte porque despues del % puede venir cualquier caracter, no solo ASCII
+		let escaped = bytes.get(index + 1 .. index + 3)
+			.filter(|digits| bytes[index] == b'%' && digits.iter().all(|digit| digit.is_ascii_hexdigit()))
+			.and_then(|digits| u8::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok());
+
+		if let Some(value) = escaped
+		{
+			output.push(value);
+			index += 3;
+			continue;
+		}
+
+		output.push(bytes[index]);
+		index += 1;
+	}
+
+	return String::from_utf8_lossy(&output).into_owned();
+}
+
+//NOTE(fpalacios): Un arreglo del documento, vacio si no esta
+fn array<'json>(json: &'json crate::json::Json, key: &str) -> &'json [crate::json::Json]
+{
+	return json.get(key).and_then(|value| value.as_array()).unwrap_or(&[]);
+}
+
+fn get_usize(json: &crate::json::Json, key: &str) -> Option<usize>
+{
+	return json.get(key).and_then(|value| value.as_usize());
+}
+
+//NOTE(fpalacios): Los datos de un accessor pasados a f64 (alcanza para representar cualquier u32 sin perder nada)
+struct Accessor
+{
+	values    : Vec<f64>,
+	components: usize,
+}
+
+impl Accessor
+{
+	fn count(&self) -> usize
+	{
+		return self.values.len().checked_div(self.components).unwrap_or(0);
+	}
+
+	fn element(&self, index: usize) -> &[f64]
+	{
+		return &self.values[index * self.components .. (index + 1) * self.components];
+	}
+
+	fn to_f32(&self) -> Vec<f32>
+	{
+		return self.values.iter().map(|value| *value as f32).collect();
+	}
+}
+
+//NOTE(fpalacios): primitive indexa los atributos por vertice sin mirar, asi que se revisa antes que tengan la cantidad de
+//componentes que dice el spec y un elemento por cada POSITION
+fn check_attribute(name: &str, accessor: Accessor, components: &[usize], vertex_count: Option<usize>) -> Result<Accessor, GltfError>
+{
+	if !components.contains(&accessor.components)
+	{
+		return Err(invalid(format!("{} tiene {} componentes por elemento", name, accessor.components)));
+	}
+
+	if let Some(vertex_count) = vertex_count
+	{
+		if accessor.count() != vertex_count
+		{
+			return Err(invalid(format!("{} tiene {} elementos y POSITION {}", name, accessor.count(), vertex_count)));
+		}
+	}
+
+	return Ok(accessor);
+}
+
+struct Gltf
+{
+	document: crate::json::Json,
+	buffers : Vec<Vec<u8>>,
+}
+
+impl Gltf
+{
+	fn buffer_view(&self, index: usize) -> Result<(&[u8], Option<usize>), GltfError>
+	{
+		let view = array(&self.document, "bufferViews").get(index).ok_or_else(|| invalid(format!("No existe el bufferView {}", index)))?;
+
+		let buffer_index = get_usize(view, "buffer").ok_or_else(|| invalid(format!("El bufferView {} no tiene buffer", index)))?;
+		let buffer = self.buffers.get(buffer_index).ok_or_else(|| invalid(format!("No existe el buffer {}", buffer_index)))?;
+
+		let offset = get_usize(view, "byteOffset").unwrap_or(0);
+		let length = get_usize(view, "byteLength").ok_or_else(|| invalid(format!("El bufferView {} no tiene byteLength", index)))?;
+
+		let data = offset.checked_add(length).and_then(|end| buffer.get(offset .. end)).ok_or_else(|| invalid(format!("El bufferView {} se sale del buffer", index)))?;
+
+		return Ok((data, get_usize(view, "byteStride")));
+	}
+
+	fn read_components(
+		&self,
+		view_index    : usize,
+		byte_offset   : usize,
+		component_type: usize,
+		components    : usize,
+		count         : usize,
+		normalized    : bool
+	)
+	-> Result<Vec<f64>, GltfError>
+	{
+		let (data, stride) = self.buffer_view(view_index)?;
+
+		let component_size = match component_type
+		{
+			COMPONENT_I8  | COMPONENT_U8  => 1,
+			COMPONENT_I16 | COMPONENT_U16 => 2,
+			COMPONENT_U32 | COMPONENT_F32 => 4,
+			_ => return Err(invalid(format!("componentType {} desconocido", component_type))),
+		};
+
+		let element_size = components * component_size;
+		let stride = stride.unwrap_or(element_size);
+
+		//NOTE(fpalacios): Con checked para que un count gigante no de la vuelta y pase el control
+		let end = stride.checked_mul(count.saturating_sub(1))
+			.and_then(|end| end.checked_add(byte_offset))
+			.and_then(|end| end.checked_add(element_size));
+
+		if count > 0 && end.is_none_or(|end| end > data.len())
+		{
+			return Err(invalid(format!("Un accessor se sale del bufferView {}", view_index)));
+		}
+
+		let mut values = Vec::with_capacity(count * components);
+
+		for element in 0 .. count
+		{
+			for component in 0 .. components
+			{
+				let start = byte_offset + element * stride + component * component_size;
+				let bytes = &data[start .. start + component_size];
+
+				let value = match component_type
+				{
+					COMPONENT_I8  => (bytes[0] as i8) as f64,
+					COMPONENT_U8  => bytes[0] as f64,
+					COMPONENT_I16 => i16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+					COMPONENT_U16 => u16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+					COMPONENT_U32 => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+					_             => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+				};
+
+				//NOTE(fpalacios): Los enteros normalizados se pasan a [0, 1] o [-1, 1]
+				let value = match (normalized, component_type)
+				{
+					(true, COMPONENT_I8)  => (value / 127.0).max(-1.0),
+					(true, COMPONENT_U8)  => value / 255.0,
+					(true, COMPONENT_I16) => (value / 32767.0).max(-1.0),
+					(true, COMPONENT_U16) => value / 65535.0,
+					_                     => value,
+				};
+
+				values.push(value);
+			}
+		}
+
+		return Ok(values);
+	}
+
+	fn accessor(&self, index: usize) -> Result<Accessor, GltfError>
+	{
+		let accessor = array(&self.document, "accessors").get(index).ok_or_else(|| invalid(format!("No existe el accessor {}", index)))?;
+
+		let components = match accessor.get("type").and_then(|accessor_type| accessor_type.as_str())
+		{
+			Some("SCALAR") => 1,
+			Some("VEC2")   => 2,
+			Some("VEC3")   => 3,
+			Some("VEC4")   => 4,
+			Some("MAT2")   => 4,
+			Some("MAT3")   => 9,
+			Some("MAT4")   => 16,
+			other          => return Err(invalid(format!("Tipo de accessor {:?} desconocido", other))),
+		};
+
+		let count          = get_usize(accessor, "count").ok_or_else(|| invalid(format!("El accessor {} no tiene count", index)))?;
+		let component_type = get_usize(accessor, "componentType").ok_or_else(|| invalid(format!("El accessor {} no tiene componentType", index)))?;
+		let normalized     = accessor.get("normalized").and_then(|normalized| normalized.as_bool()).unwrap_or(false);
+
+		//NOTE(fpalacios): Sin bufferView el accessor arranca en ceros (se usa con sparse)
+		let mut values = match get_usize(accessor, "bufferView")
+		{
+			Some(view) => self.read_components(view, get_usize(accessor, "byteOffset").unwrap_or(0), component_type, components, count, normalized)?,
+			None       =>
+			{
+				let length = count.checked_mul(components).filter(|length| *length <= MAX_ZEROED_VALUES)
+					.ok_or_else(|| invalid(format!("El accessor {} sin bufferView tiene demasiados elementos ({})", index, count)))?;
+
+				vec![0.0; length]
+			},
+		};
+
+		if let Some(sparse) = accessor.get("sparse")
+		{
+			let sparse_count = get_usize(sparse, "count").unwrap_or(0);
+			let indices = sparse.get("indices").ok_or_else(|| invalid(String::from("sparse sin indices")))?;
+			let sparse_values = sparse.get("values").ok_or_else(|| invalid(String::from("sparse sin values")))?;
+
+			let indices = self.read_components(
+				get_usize(indices, "bufferView").ok_or_else(|| invalid(String::from("sparse.indices sin bufferView")))?,
+				get_usize(indices, "byteOffset").unwrap_or(0),
+				get_usize(indices, "componentType").unwrap_or(COMPONENT_U32),
+				1,
+				sparse_count,
+				false
+			)?;
+
+			let replacements = self.read_components(
+				get_usize(sparse_values, "bufferView").ok_or_else(|| invalid(String::from("sparse.values sin bufferView")))?,
+				get_usize(sparse_values, "byteOffset").unwrap_or(0),
+				component_type,
+				components,
+				sparse_count,
+				normalized
+			)?;
+
+			for (sparse_index, element) in indices.iter().enumerate()
+			{
+				let element = *element as usize;
+				if element >= count
+				{
+					return Err(invalid(format!("Indice sparse {} fuera del accessor {}", element, index)));
+				}
+
+				values[element * components .. (element + 1) * components]
+					.copy_from_slice(&replacements[sparse_index * components .. (sparse_index + 1) * components]);
+			}
+		}
+
+		return Ok(Accessor { values, components });
+	}
+
+	fn load_images(&self, base_directory: &std::path::Path) -> Result<Vec<std::rc::Rc<image::RgbaImage>>, GltfError>
+	{
+		let mut images = Vec::new();
+
+		for (index, image_json) in array(&self.document, "images").iter().enumerate()
+		{
+			let bytes = match (image_json.get("uri").and_then(|uri| uri.as_str()), get_usize(image_json, "bufferView"))
+			{
+				(Some(uri), _)     => read_uri(uri, base_directory)?,
+				(None, Some(view)) => self.buffer_view(view)?.0.to_vec(),
+				(None, None)       => return Err(invalid(format!("La imagen {} no tiene uri ni bufferView", index))),
+			};
+
+			images.push(std::rc::Rc::new(image::load_from_memory(&bytes).map_err(GltfError::Image)?.to_rgba8()));
+		}
+
+		return Ok(images);
+	}
+
+	//NOTE(fpalacios): Color base (sin la textura) y textura del material
+	fn material(&self, index: Option<usize>, images: &[std::rc::Rc<image::RgbaImage>]) -> ([f32; 4], Option<std::rc::Rc<image::RgbaImage>>)
+	{
+		let pbr = match index.and_then(|index| array(&self.document, "materials").get(index)).and_then(|material| material.get("pbrMetallicRoughness"))
+		{
+			Some(pbr) => pbr,
+			None      => return ([1.0, 1.0, 1.0, 1.0], None),
+		};
+
+		let factor = match pbr.get("baseColorFactor").and_then(|factor| factor.as_f64_array())
+		{
+			Some(factor) if factor.len() == 4 => [factor[0] as f32, factor[1] as f32, factor[2] as f32, factor[3] as f32],
+			_                                  => [1.0, 1.0, 1.0, 1.0],
+		};
+
+		let texture = pbr.get("baseColorTexture")
+			.and_then(|texture_info| get_usize(texture_info, "index"))
+			.and_then(|texture| array(&self.document, "textures").get(texture))
+			.and_then(|texture| get_usize(texture, "source"))
+			.and_then(|image| images.get(image))
+			.cloned();
+
+		return (factor, texture);
+	}
+
+	fn primitive(&self, primitive: &crate::json::Json, images: &[std::rc::Rc<image::RgbaImage>]) -> Result<Option<crate::mesh::Mesh>, GltfError>
+	{
+		let mode = get_usize(primitive, "mode").unwrap_or(MODE_TRIANGLES);
+		if mode != MODE_TRIANGLES && mode != MODE_TRIANGLE_STRIP && mode != MODE_TRIANGLE_FAN
+		{
+			//NOTE(fpalacios): Puntos y lineas no tienen sentido para las plantas, se saltean
+			return Ok(None);
+		}
+
+		let attributes = primitive.get("attributes").ok_or_else(|| invalid(String::from("Primitiva sin attributes")))?;
+		let attribute  = |name: &str, components: &[usize], vertex_count: Option<usize>| -> Result<Option<Accessor>, GltfError>
+		{
+			return match get_usize(attributes, name)
+			{
+				Some(index) => Ok(Some(check_attribute(name, self.accessor(index)?, components, vertex_count)?)),
+				None        => Ok(None),
+			};
+		};
+
+		let positions    = attribute("POSITION", &[3], None)?.ok_or_else(|| invalid(String::from("Primitiva sin POSITION")))?;
+		let vertex_count = positions.count();
+
+		let normals        = attribute("NORMAL",     &[3],    Some(vertex_count))?;
+		let texture_coords = attribute("TEXCOORD_0", &[2],    Some(vertex_count))?;
+		let colors         = attribute("COLOR_0",    &[3, 4], Some(vertex_count))?;
+		let joints         = attribute("JOINTS_0",   &[4],    Some(vertex_count))?;
+		let weights        = attribute("WEIGHTS_0",  &[4],    Some(vertex_count))?;
+
+		let (factor, texture) = self.material(get_usize(primitive, "material"), images);
+
+		let mut vertices = Vec::with_capacity(vertex_count);
+		for index in 0 .. vertex_count
+		{
+			let position = positions.element(index);
+			let normal   = normals.as_ref().map(|normals| normals.element(index));
+			let uv       = texture_coords.as_ref().map(|uvs| uvs.element(index));
+			let color    = colors.as_ref().map(|colors| colors.element(index));
+
+			vertices.push(
+				crate::renderer::Vertex3D
+				{
+					position      : [position[0] as f32, position[1] as f32, position[2] as f32],
+					normal        : normal.map(|normal| [normal[0] as f32, normal[1] as f32, normal[2] as f32]).unwrap_or([0.0, 0.0, 0.0]),
+					//NOTE(fpalacios): En glTF la v crece para abajo y las texturas se suben dadas vuelta
+					texture_coords: uv.map(|uv| [uv[0] as f32, 1.0 - uv[1] as f32]).unwrap_or([0.0, 0.0]),
+					color         : match color
+					{
+						Some(color) => [color[0] as f32 * factor[0], color[1] as f32 * factor[1], color[2] as f32 * factor[2]],
+						None        => [factor[0], factor[1], factor[2]],
+					},
+				}
+			);
+		}
+
+		let vertex_indices: Vec<u32> = match get_usize(primitive, "indices")
+		{
+			Some(indices) => self.accessor(indices)?.values.iter().map(|index| *index as u32).collect(),
+			None          => (0 .. vertex_count as u32).collect(),
+		};
+
+		if let Some(index) = vertex_indices.iter().find(|index| **index as usize >= vertex_count)
+		{
+			return Err(invalid(format!("Indice {} fuera de los {} vertices", index, vertex_count)));
+		}
+
+		let indices = match mode
+		{
+			MODE_TRIANGLE_STRIP => (0 .. vertex_indices.len().saturating_sub(2)).flat_map(|index|
+			{
+				//NOTE(fpalacios): Se alterna el orden para que todos los triangulos queden con la misma orientacion
+				if index % 2 == 0
+				{
+					vec![vertex_indices[index], vertex_indices[index + 1], vertex_indices[index + 2]]
+				}
+				else
+				{
+					vec![vertex_indices[index + 1], vertex_indices[index], vertex_indices[index + 2]]
+				}
+			}).collect(),
+			MODE_TRIANGLE_FAN => (1 .. vertex_indices.len().saturating_sub(1)).flat_map(|index|
+				vec![vertex_indices[0], vertex_indices[index], vertex_indices[index + 1]]
+			).collect(),
+			_ => vertex_indices,
+		};
+
+		let mut mesh = crate::mesh::Mesh::new(vertices, indices);
+		mesh.texture = texture;
+
+		if normals.is_none()
+		{
+			mesh.compute_normals();
+		}
+
+		if let (Some(joints), Some(weights)) = (joints, weights)
+		{
+			mesh.joints = (0 .. vertex_count).map(|index|
+			{
+				let joint = joints.element(index);
+				[joint[0] as u16, joint[1] as u16, joint[2] as u16, joint[3] as u16]
+			}).collect();
+
+			mesh.weights = (0 .. vertex_count).map(|index|
+			{
+				let weight = weights.element(index);
+				[weight[0] as f32, weight[1] as f32, weight[2] as f32, weight[3] as f32]
+			}).collect();
+		}
+
+		for target in array(primitive, "targets")
+		{
+			let read_deltas = |name: &str| -> Result<Vec<[f32; 3]>, GltfError>
+			{
+				return match get_usize(target, name)
+				{
+					Some(index) =>
+					{
+						let deltas = check_attribute(name, self.accessor(index)?, &[3], Some(vertex_count))?;
+						Ok((0 .. deltas.count()).map(|index| { let delta = deltas.element(index); [delta[0] as f32, delta[1] as f32, delta[2] as f32] }).collect())
+					},
+					None => Ok(Vec::new()),
+				};
+			};
+
+			mesh.morph_targets.push(
+				crate::mesh::MorphTarget
+				{
+					positions: read_deltas("POSITION")?,
+					normals  : read_deltas("NORMAL")?,
+				}
+			);
+		}
+
+		return Ok(Some(mesh));
+	}
+
+	fn build_model(&self, base_directory: &std::path::Path) -> Result<crate::mesh::Model, GltfError>
+	{
+		let images = self.load_images(base_directory)?;
+
+		//NOTE(fpalacios): Cada primitiva es una malla aparte porque puede tener otro material
+		let mut meshes = Vec::new();
+		for mesh_json in array(&self.document, "meshes")
+		{
+			let mut primitives = Vec::new();
+			for primitive in array(mesh_json, "primitives")
+			{
+				if let Some(mesh) = self.primitive(primitive, &images)?
+				{
+					primitives.push(std::rc::Rc::new(mesh));
+				}
+			}
+
+			let default_weights: Vec<f32> = mesh_json.get("weights").and_then(|weights| weights.as_f64_array()).unwrap_or_default()
+				.iter().map(|weight| *weight as f32).collect();
+
+			meshes.push((primitives, default_weights));
+		}
+
+		let nodes_json = array(&self.document, "nodes");
+		let mut nodes: Vec<crate::mesh::Node> = (0 .. nodes_json.len()).map(|_| crate::mesh::Node::identity(None)).collect();
+
+		for (index, node_json) in nodes_json.iter().enumerate()
+		{
+			for child in array(node_json, "children")
+			{
+				let child = child.as_usize().filter(|child| *child < nodes.len()).ok_or_else(|| invalid(format!("Hijo invalido en el nodo {}", index)))?;
+				nodes[child].parent = Some(index);
+			}
+
+			let node = &mut nodes[index];
+
+			if let Some(matrix) = node_json.get("matrix").and_then(|matrix| matrix.as_f64_array()).filter(|matrix| matrix.len() == 16)
+			{
+				//NOTE(fpalacios): Viene por columnas igual que nalgebra
+				node.matrix = Some(nalgebra::Matrix4::from_iterator(matrix.iter().map(|value| *value as f32)));
+			}
+
+			if let Some(translation) = node_json.get("translation").and_then(|value| value.as_f64_array()).filter(|value| value.len() == 3)
+			{
+				node.translation = nalgebra::Vector3::new(translation[0] as f32, translation[1] as f32, translation[2] as f32);
+			}
+
+			if let Some(rotation) = node_json.get("rotation").and_then(|value| value.as_f64_array()).filter(|value| value.len() == 4)
+			{
+				node.rotation = nalgebra::UnitQuaternion::from_quaternion(
+					nalgebra::Quaternion::new(rotation[3] as f32, rotation[0] as f32, rotation[1] as f32, rotation[2] as f32)
+				);
+			}
+
+			if let Some(scale) = node_json.get("scale").and_then(|value| value.as_f64_array()).filter(|value| value.len() == 3)
+			{
+				node.scale = nalgebra::Vector3::new(scale[0] as f32, scale[1] as f32, scale[2] as f32);
+			}
+
+			node.weights = match node_json.get("weights").and_then(|weights| weights.as_f64_array())
+			{
+				Some(weights) => weights.iter().map(|weight| *weight as f32).collect(),
+				None          => get_usize(node_json, "mesh").and_then(|mesh| meshes.get(mesh)).map(|(_, weights)| weights.clone()).unwrap_or_default(),
+			};
+		}
+
+		//NOTE(fpalacios): Solo se dibujan los nodos de la escena elegida, o todos si el archivo no tiene escenas
+		let scene = get_usize(&self.document, "scene").unwrap_or(0);
+		let mut visible: Vec<bool> = match array(&self.document, "scenes").get(scene)
+		{
+			Some(scene) =>
+			{
+				let mut visible = vec![false; nodes.len()];
+				let mut pending: Vec<usize> = array(scene, "nodes").iter().filter_map(|node| node.as_usize()).filter(|node| *node < nodes.len()).collect();
+
+				while let Some(node) = pending.pop()
+				{
+					if !visible[node]
+					{
+						visible[node] = true;
+						pending.extend(array(&nodes_json[node], "children").iter().filter_map(|child| child.as_usize()));
+					}
+				}
+
+				visible
+			},
+			None => vec![true; nodes.len()],
+		};
+		visible.resize(nodes.len(), false);
+
+		let mut parts = Vec::new();
+		for (index, node_json) in nodes_json.iter().enumerate()
+		{
+			if !visible[index]
+			{
+				continue;
+			}
+
+			if let Some((primitives, _)) = get_usize(node_json, "mesh").and_then(|mesh| meshes.get(mesh))
+			{
+				for mesh in primitives
+				{
+					parts.push(
+						crate::mesh::ModelPart
+						{
+							mesh: mesh.clone(),
+							node: index,
+							skin: get_usize(node_json, "skin"),
+						}
+					);
+				}
+			}
+		}
+
+		let mut skins = Vec::new();
+		for skin_json in array(&self.document, "skins")
+		{
+			let joints: Vec<usize> = array(skin_json, "joints").iter().filter_map(|joint| joint.as_usize()).filter(|joint| *joint < nodes.len()).collect();
+
+			let inverse_bind_matrices = match get_usize(skin_json, "inverseBindMatrices")
+			{
+				Some(accessor) =>
+				{
+					let matrices = self.accessor(accessor)?;
+					if matrices.components != 16
+					{
+						return Err(invalid(format!("inverseBindMatrices tiene {} componentes por elemento", matrices.components)));
+					}
+
+					(0 .. matrices.count()).map(|index| nalgebra::Matrix4::from_iterator(matrices.element(index).iter().map(|value| *value as f32))).collect()
+				},
+				None => vec![nalgebra::Matrix4::identity(); joints.len()],
+			};
+
+			skins.push(
+				crate::mesh::Skin
+				{
+					joints,
+					inverse_bind_matrices,
+				}
+			);
+		}
+
+		let animation = match array(&self.document, "animations").first()
+		{
+			Some(animation) => Some(self.animation(animation, nodes.len())?),
+			None            => None,
+		};
+
+		return Ok(crate::mesh::Model::new(nodes, parts, skins, animation));
+	}
+
+	fn animation(&self, animation: &crate::json::Json, node_count: usize) -> Result<crate::mesh::Animation, GltfError>
+	{
+		let samplers = array(animation, "samplers");
+		let mut channels = Vec::new();
+
+		for channel in array(animation, "channels")
+		{
+			let target = match channel.get("target")
+			{
+				Some(target) => target,
+				None         => continue,
+			};
+
+			//NOTE(fpalacios): Los canales sin nodo son de extensiones que no soportamos
+			let node = match get_usize(target, "node").filter(|node| *node < node_count)
+			{
+				Some(node) => node,
+				None       => continue,
+			};
+
+			//NOTE(fpalacios): Los componentes que tiene que tener cada valor del output, mesh::AnimationChannel los usa sin mirar
+			let (path, components) = match target.get("path").and_then(|path| path.as_str())
+			{
+				Some("translation") => (crate::mesh::AnimationPath::Translation, 3),
+				Some("rotation")    => (crate::mesh::AnimationPath::Rotation,    4),
+				Some("scale")       => (crate::mesh::AnimationPath::Scale,       3),
+				Some("weights")     => (crate::mesh::AnimationPath::Weights,     1),
+				_                   => continue,
+			};
+
+			let sampler = get_usize(channel, "sampler").and_then(|sampler| samplers.get(sampler))
+				.ok_or_else(|| invalid(String::from("Canal de animacion sin sampler")))?;
+
+			let interpolation = match sampler.get("interpolation").and_then(|interpolation| interpolation.as_str())
+			{
+				Some("STEP")        => crate::mesh::Interpolation::Step,
+				Some("CUBICSPLINE") => crate::mesh::Interpolation::CubicSpline,
+				_                   => crate::mesh::Interpolation::Linear,
+			};
+
+			let input  = self.accessor(get_usize(sampler, "input").ok_or_else(|| invalid(String::from("Sampler sin input")))?)?;
+			let output = self.accessor(get_usize(sampler, "output").ok_or_else(|| invalid(String::from("Sampler sin output")))?)?;
+
+			if input.components != 1
+			{
+				return Err(invalid(format!("El input del sampler tiene {} componentes por elemento", input.components)));
+			}
+
+			if output.components != components
+			{
+				return Err(invalid(format!("El output del sampler de {:?} tiene {} componentes por elemento", path, output.components)));
+			}
+
+			let times = input.to_f32();
+
+			if times.is_empty() || output.count() == 0
+			{
+				continue;
+			}
+
+			if times.iter().any(|time| !time.is_finite())
+			{
+				return Err(invalid(String::from("El input del sampler tiene tiempos que no son numeros finitos")));
+			}
+
+			//NOTE(fpalacios): Los pesos traen un valor por morph target en cada keyframe, el resto uno solo
+			let elements = if interpolation == crate::mesh::Interpolation::CubicSpline { 3 } else { 1 };
+			let matches  = match path
+			{
+				crate::mesh::AnimationPath::Weights => output.count() % (times.len() * elements) == 0,
+				_                                   => output.count() == times.len() * elements,
+			};
+
+			if !matches
+			{
+				return Err(invalid(format!("El output del sampler no coincide con los {} keyframes", times.len())));
+			}
+
+			let values = output.to_f32();
+
+			channels.push(
+				crate::mesh::AnimationChannel
+				{
+					node,
+					path,
+					interpolation,
+					times,
+					values,
+				}
+			);
+		}
+
+		let duration = channels.iter().filter_map(|channel| channel.times.last().cloned()).fold(0.0, f32::max);
+
+		return Ok(crate::mesh::Animation { channels, duration });
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	fn floats(values: &[f32]) -> Vec<u8>
+	{
+		return values.iter().flat_map(|value| value.to_le_bytes()).collect();
+	}
+
+	fn shorts(values: &[u16]) -> Vec<u8>
+	{
+		return values.iter().flat_map(|value| value.to_le_bytes()).collect();
+	}
+
+	//NOTE(fpalacios): El buffer que comparten todos los tests y sus bufferViews, uno por seccion en este orden:
+	//0 posiciones de un triangulo, 1 indices u16, 2 indice sparse u16, 3 valor sparse, 4 deltas de un morph target,
+	//5 tiempos de animacion, 6 traslaciones, 7 una inverseBindMatrix, 8 joints u16 y 9 weights
+	fn binary() -> (Vec<u8>, String)
+	{
+		let identity: Vec<f32> = (0 .. 16).map(|index| if index % 5 == 0 { 1.0 } else { 0.0 }).collect();
+
+		let sections = [
+			floats(&[0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0]),
+			shorts(&[0, 1, 2]),
+			shorts(&[1]),
+			floats(&[5.0, 5.0, 5.0]),
+			floats(&[0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0]),
+			floats(&[0.0, 1.0]),
+			floats(&[0.0, 0.0, 0.0, 2.0, 0.0, 0.0]),
+			floats(&identity),
+			shorts(&[0; 12]),
+			floats(&[1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0]),
+		];
+
+		let mut data  = Vec::new();
+		let mut views = Vec::new();
+		for section in sections
+		{
+			while !data.len().is_multiple_of(4)
+			{
+				data.push(0);
+			}
+
+			views.push(format!("{{\"buffer\":0,\"byteOffset\":{},\"byteLength\":{}}}", data.len(), section.len()));
+			data.extend_from_slice(&section);
+		}
+
+		return (data, format!("[{}]", views.join(",")));
+	}
+
+	//NOTE(fpalacios): Uno por bufferView de binary() hasta el 7, el 8 y el 9 con sparse (el 8 sobre las posiciones y el 9 sin
+	//bufferView), y el 10 y el 11 con los joints y weights. Cada test puede agregar mas despues del 11
+	const ACCESSORS: &str = r#"
+		{"bufferView": 0, "componentType": 5126, "type": "VEC3",   "count": 3},
+		{"bufferView": 1, "componentType": 5123, "type": "SCALAR", "count": 3},
+		{"bufferView": 2, "componentType": 5123, "type": "SCALAR", "count": 1},
+		{"bufferView": 3, "componentType": 5126, "type": "VEC3",   "count": 1},
+		{"bufferView": 4, "componentType": 5126, "type": "VEC3",   "count": 3},
+		{"bufferView": 5, "componentType": 5126, "type": "SCALAR", "count": 2},
+		{"bufferView": 6, "componentType": 5126, "type": "VEC3",   "count": 2},
+		{"bufferView": 7, "componentType": 5126, "type": "MAT4",   "count": 1},
+		{"bufferView": 0, "componentType": 5126, "type": "VEC3",   "count": 3,
+			"sparse": {"count": 1, "indices": {"bufferView": 2, "componentType": 5123}, "values": {"bufferView": 3}}},
+		{"componentType": 5126, "type": "VEC3", "count": 3,
+			"sparse": {"count": 1, "indices": {"bufferView": 2, "componentType": 5123}, "values": {"bufferView": 3}}},
+		{"bufferView": 8, "componentType": 5123, "type": "VEC4",   "count": 3},
+		{"bufferView": 9, "componentType": 5126, "type": "VEC4",   "count": 3}
+	"#;
+
+	fn chunk(chunk_type: u32, data: &[u8], padding: u8) -> Vec<u8>
+	{
+		let mut data = data.to_vec();
+		while !data.len().is_multiple_of(4)
+		{
+			data.push(padding);
+		}
+
+		let mut bytes = Vec::new();
+		bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+		bytes.extend_from_slice(&chunk_type.to_le_bytes());
+		bytes.extend_from_slice(&data);
+
+		return bytes;
+	}
+
+	fn glb(chunks: &[Vec<u8>]) -> Vec<u8>
+	{
+		let length: usize = 12 + chunks.iter().map(|chunk| chunk.len()).sum::<usize>();
+
+		let mut bytes = Vec::new();
+		bytes.extend_from_slice(super::GLB_MAGIC);
+		bytes.extend_from_slice(&2_u32.to_le_bytes());
+		bytes.extend_from_slice(&(length as u32).to_le_bytes());
+		for chunk in chunks
+		{
+			bytes.extend_from_slice(chunk);
+		}
+
+		return bytes;
+	}
+
+	//NOTE(fpalacios): Arma un .glb con el buffer de binary(), los accessors de ACCESSORS seguidos de los que se pasen y lo
+	//que se pase como resto del documento
+	fn document(accessors: &[&str], rest: &str) -> Vec<u8>
+	{
+		let (data, views) = binary();
+		let accessors: String = accessors.iter().map(|accessor| format!(", {}", accessor)).collect();
+		let json = format!(
+			r#"{{"asset": {{"version": "2.0"}}, "buffers": [{{"byteLength": {}}}], "bufferViews": {}, "accessors": [{}{}], {}}}"#,
+			data.len(),
+			views,
+			ACCESSORS,
+			accessors,
+			rest
+		);
+
+		return glb(&[chunk(super::GLB_CHUNK_JSON, json.as_bytes(), b' '), chunk(super::GLB_CHUNK_BIN, &data, 0)]);
+	}
+
+	fn load(accessors: &[&str], rest: &str) -> Result<crate::mesh::Model, super::GltfError>
+	{
+		return super::parse(&document(accessors, rest), std::path::Path::new("."));
+	}
+
+	fn triangle(position: usize) -> String
+	{
+		return format!(r#""meshes": [{{"primitives": [{{"attributes": {{"POSITION": {}}}, "indices": 1}}]}}], "nodes": [{{"mesh": 0}}]"#, position);
+	}
+
+	fn positions(model: &crate::mesh::Model) -> Vec<[f32; 3]>
+	{
+		return model.parts[0].mesh.vertices.iter().map(|vertex| vertex.position).collect();
+	}
+
+	fn assert_close(actual: [f32; 3], expected: [f32; 3])
+	{
+		assert!(actual.iter().zip(expected.iter()).all(|(actual, expected)| (actual - expected).abs() < 1.0e-5), "{:?} no es {:?}", actual, expected);
+	}
+
+	#[test]
+	fn glb_chunks()
+	{
+		let model = load(&[], &triangle(0)).unwrap();
+		assert_eq!(model.parts.len(), 1);
+		assert_eq!(positions(&model), vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+		assert_eq!(model.parts[0].mesh.indices, vec![0, 1, 2]);
+
+		//NOTE(fpalacios): Un chunk de tipo desconocido entre el JSON y el binario se ignora
+		let bytes = document(&[], &triangle(0));
+		let json_length = u32::from_le_bytes([bytes[12], bytes[13], bytes[14], bytes[15]]) as usize;
+		let (json, binary) = bytes[12 ..].split_at(8 + json_length);
+		let with_unknown = glb(&[json.to_vec(), chunk(0x12345678, b"nada", 0), binary.to_vec()]);
+		assert_eq!(positions(&super::parse(&with_unknown, std::path::Path::new(".")).unwrap()).len(), 3);
+
+		let cut = &bytes[.. bytes.len() - 4];
+		assert!(matches!(super::parse(cut, std::path::Path::new(".")), Err(super::GltfError::Glb(_))));
+
+		let mut version_1 = bytes.clone();
+		version_1[4] = 1;
+		assert!(matches!(super::parse(&version_1, std::path::Path::new(".")), Err(super::GltfError::Glb(_))));
+
+		let without_json = glb(&[binary.to_vec()]);
+		assert!(matches!(super::parse(&without_json, std::path::Path::new(".")), Err(super::GltfError::Glb(_))));
+	}
+
+	#[test]
+	fn sparse_accessors()
+	{
+		assert_eq!(positions(&load(&[], &triangle(8)).unwrap()), vec![[0.0, 0.0, 0.0], [5.0, 5.0, 5.0], [0.0, 1.0, 0.0]]);
+		assert_eq!(positions(&load(&[], &triangle(9)).unwrap()), vec![[0.0, 0.0, 0.0], [5.0, 5.0, 5.0], [0.0, 0.0, 0.0]]);
+
+		//NOTE(fpalacios): El indice sparse 1 no entra en un accessor de un elemento
+		let out_of_range = r#"{"componentType": 5126, "type": "VEC3", "count": 1,
+			"sparse": {"count": 1, "indices": {"bufferView": 2, "componentType": 5123}, "values": {"bufferView": 3}}}"#;
+		assert!(matches!(load(&[out_of_range], &triangle(12)), Err(super::GltfError::Invalid(_))));
+
+		//NOTE(fpalacios): Sin bufferView un count enorme no se llena de ceros
+		let huge = r#"{"componentType": 5126, "type": "VEC3", "count": 1000000000000}"#;
+		assert!(matches!(load(&[huge], &triangle(12)), Err(super::GltfError::Invalid(_))));
+	}
+
+	#[test]
+	fn node_hierarchy()
+	{
+		let model = load(
+			&[],
+			r#""meshes": [{"primitives": [{"attributes": {"POSITION": 0}}]}],
+			"nodes": [
+				{"children": [1], "translation": [1, 0, 0]},
+				{"mesh": 0, "translation": [0, 2, 0], "scale": [2, 2, 2]},
+				{"mesh": 0}
+			],
+			"scene": 0,
+			"scenes": [{"nodes": [0]}]"#
+		).unwrap();
+
+		assert_eq!(model.nodes[0].parent, None);
+		assert_eq!(model.nodes[1].parent, Some(0));
+		assert_eq!(model.nodes[2].parent, None);
+
+		//NOTE(fpalacios): El nodo 2 no esta en la escena y no se dibuja
+		assert_eq!(model.parts.len(), 1);
+		assert_eq!(model.parts[0].node, 1);
+
+		let expected = model.normalization
+			* nalgebra::Matrix4::new_translation(&nalgebra::Vector3::new(1.0, 2.0, 0.0))
+			* nalgebra::Matrix4::new_scaling(2.0);
+		assert!((model.pose(0.0)[0].transform - expected).abs().max() < 1.0e-5);
+	}
+
+	#[test]
+	fn skins_and_morph_targets()
+	{
+		let model = load(
+			&[],
+			r#""meshes": [{
+				"primitives": [{"attributes": {"POSITION": 0, "JOINTS_0": 10, "WEIGHTS_0": 11}, "indices": 1, "targets": [{"POSITION": 4}]}],
+				"weights": [0.5]
+			}],
+			"nodes": [{"mesh": 0, "skin": 0}, {}],
+			"skins": [{"joints": [1], "inverseBindMatrices": 7}],
+			"animations": [{
+				"samplers": [{"input": 5, "output": 6}],
+				"channels": [{"sampler": 0, "target": {"node": 1, "path": "translation"}}]
+			}]"#
+		).unwrap();
+
+		let mesh = &model.parts[0].mesh;
+		assert_eq!(mesh.morph_targets.len(), 1);
+		assert_eq!(mesh.joints, vec![[0, 0, 0, 0]; 3]);
+		assert_eq!(model.nodes[0].weights, vec![0.5]);
+		assert_eq!(model.animation.as_ref().unwrap().duration, 1.0);
+
+		//NOTE(fpalacios): A mitad de la animacion el hueso esta en x = 1 y el morph target suma la mitad de su z
+		let pose = model.pose(0.5);
+		let vertices = pose[0].vertices.as_ref().unwrap();
+		assert_close(vertices[0].position, [1.0, 0.0, 0.5]);
+		assert_close(vertices[1].position, [2.0, 0.0, 0.5]);
+		assert_close(vertices[2].position, [1.0, 1.0, 0.5]);
+	}
+
+	#[test]
+	fn malformed_input()
+	{
+		let invalid = |accessors: &[&str], rest: &str|
+		{
+			assert!(matches!(load(accessors, rest), Err(super::GltfError::Invalid(_))), "{} deberia ser invalido", rest);
+		};
+
+		let animation = |path: &str, output: usize| format!(
+			r#""nodes": [{{}}], "animations": [{{"samplers": [{{"input": 5, "output": {}}}], "channels": [{{"sampler": 0, "target": {{"node": 0, "path": "{}"}}}}]}}]"#,
+			output,
+			path
+		);
+
+		//NOTE(fpalacios): Las rotaciones son de 4 componentes y las traslaciones de 3
+		invalid(&[], &animation("rotation", 6));
+		invalid(&[], &animation("translation", 7));
+		invalid(&[], &animation("translation", 5));
+		assert!(load(&[], &animation("translation", 6)).is_ok());
+
+		invalid(&[], r#""nodes": [{"mesh": 0, "skin": 0}], "skins": [{"joints": [0], "inverseBindMatrices": 0}]"#);
+		invalid(&[], r#""nodes": [{"children": [3]}]"#);
+		invalid(&[r#"{"bufferView": 0, "componentType": 5126, "type": "VEC3", "count": 4}"#], &triangle(12));
+		invalid(&[r#"{"bufferView": 0, "componentType": 5126, "type": "VEC3", "count": 1000000000000}"#], &triangle(12));
+		invalid(&[r#"{"bufferView": 1, "componentType": 5123, "type": "SCALAR", "count": 3}"#], &triangle(12));
+		invalid(&[], &triangle(12));
+
+		assert!(matches!(super::parse(b"{\"asset\": ", std::path::Path::new(".")), Err(super::GltfError::Json(_))));
+		assert!(matches!(super::parse(b"{\"asset\": {\"version\": \"1.0\"}}", std::path::Path::new(".")), Err(super::GltfError::Unsupported(_))));
+	}
+
+	#[test]
+	fn percent_decode()
+	{
+		assert_eq!(super::percent_decode("hoja%20verde.png"), "hoja verde.png");
+		assert_eq!(super::percent_decode("%C3%B1and%C3%BA"), "\u{f1}and\u{fa}");
+		assert_eq!(super::percent_decode("hoja%a\u{e9}.png"), "hoja%a\u{e9}.png");
+		assert_eq!(super::percent_decode("%\u{e9}\u{e9}"), "%\u{e9}\u{e9}");
+		assert_eq!(super::percent_decode("100%"), "100%");
+		assert_eq!(super::percent_decode("%zz%2"), "%zz%2");
+	}
+}
@@ -0,0 +1,652 @@
+//NOTE(fpalacios): Socket de control para manejar el jardin desde scripts, atajos del window manager o barras de estado. El
+//protocolo es una linea de JSON por pedido y una por respuesta:
+//-> { "command": "water", "plant": "bonsai" }
+//<- { "ok": true, "watered": 2 }
+//<- { "ok": false, "error": "No hay ninguna planta bonsai sembrada" }
+pub const SOCKET_FILE: &str = "jardincho.sock";
+
+//NOTE(fpalacios): Cuanto espera el cliente la respuesta, la captura de pantalla puede tardar un poco
+const CLIENT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+//NOTE(fpalacios): Para que un cliente que no lee no trabe el loop principal
+const REPLY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ControlCommand
+{
+	//NOTE(fpalacios): None riega todo el jardin
+	Water(Option<String>),
+	AddPlant(String),
+	//NOTE(fpalacios): None arranca la ultima planta que se sembro
+	RemovePlant(Option<String>),
+	SetWeather(crate::garden::Weather),
+	//NOTE(fpalacios): None cambia de pausado a andando y al reves
+	Pause(Option<bool>),
+	//NOTE(fpalacios): Ruta absoluta, el cliente y el jardin pueden tener distinto directorio de trabajo
+	Screenshot(std::path::PathBuf),
+	Status,
+	Quit,
+}
+
+impl ControlCommand
+{
+	pub fn from_json(json: &crate::json::Json) -> Result<ControlCommand, String>
+	{
+		let command = json.get("command")
+			.and_then(crate::json::Json::as_str)
+			.ok_or_else(|| String::from("Falta \"command\""))?;
+
+		let string = |key: &str| -> Result<Option<String>, String>
+		{
+			return match json.get(key)
+			{
+				None        => Ok(None),
+				Some(value) => value.as_str()
+					.map(|value| Some(String::from(value)))
+					.ok_or_else(|| format!("\"{}\" tiene que ser un texto", key)),
+			};
+		};
+
+		let required = |key: &str| -> Result<String, String>
+		{
+			return string(key)?.ok_or_else(|| format!("{} necesita \"{}\"", command, key));
+		};
+
+		return match command
+		{
+			"water"        => Ok(ControlCommand::Water(string("plant")?)),
+			"add-plant"    => Ok(ControlCommand::AddPlant(required("plant")?)),
+			"remove-plant" => Ok(ControlCommand::RemovePlant(string("plant")?)),
+			"set-weather"  =>
+			{
+				let weather = required("weather")?;
+				crate::garden::Weather::parse(&weather)
+					.map(ControlCommand::SetWeather)
+					.ok_or_else(|| format!("\"weather\" tiene que ser uno de {}, no {}", crate::garden::Weather::NAMES.join(", "), weather))
+			},
+			"pause" => match json.get("paused")
+			{
+				None         => Ok(ControlCommand::Pause(None)),
+				Some(paused) => paused.as_bool()
+					.map(|paused| ControlCommand::Pause(Some(paused)))
+					.ok_or_else(|| String::from("\"paused\" tiene que ser true o false")),
+			},
+			"screenshot" =>
+			{
+				let path = std::path::PathBuf::from(required("path")?);
+				if !path.is_absolute()
+				{
+					return Err(String::from("\"path\" tiene que ser una ruta absoluta"));
+				}
+				Ok(ControlCommand::Screenshot(path))
+			},
+			"status" => Ok(ControlCommand::Status),
+			"quit"   => Ok(ControlCommand::Quit),
+			_ => Err(format!(
+				"No existe el comando {}, los que hay son: water, add-plant, remove-plant, set-weather, pause, screenshot, status y quit",
+				command
+			)),
+		};
+	}
+
+	pub fn to_json(&self) -> crate::json::Json
+	{
+		let (command, mut members) = match self
+		{
+			ControlCommand::Water(plant)       => ("water", optional_string("plant", plant)),
+			ControlCommand::AddPlant(plant)    => ("add-plant", vec![(String::from("plant"), crate::json::Json::String(plant.clone()))]),
+			ControlCommand::RemovePlant(plant) => ("remove-plant", optional_string("plant", plant)),
+			ControlCommand::SetWeather(weather) =>
+			{
+				("set-weather", vec![(String::from("weather"), crate::json::Json::String(String::from(weather.name())))])
+			},
+			ControlCommand::Pause(paused) =>
+			{
+				("pause", paused.iter().map(|paused| (String::from("paused"), crate::json::Json::Bool(*paused))).collect())
+			},
+			ControlCommand::Screenshot(path) =>
+			{
+				("screenshot", vec![(String::from("path"), crate::json::Json::String(path.to_string_lossy().into_owned()))])
+			},
+			ControlCommand::Status => ("status", Vec::new()),
+			ControlCommand::Quit   => ("quit", Vec::new()),
+		};
+
+		members.insert(0, (String::from("command"), crate::json::Json::String(String::from(command))));
+
+		return crate::json::Json::Object(members);
+	}
+
+	//NOTE(fpalacios): Lo que viene despues de "jardincho ctl"
+	pub fn from_args(args: &[String]) -> Result<ControlCommand, String>
+	{
+		let (command, args) = args.split_first()
+			.ok_or_else(|| String::from("ctl necesita un comando: water, add-plant, remove-plant, set-weather, pause, screenshot, status o quit"))?;
+
+		let argument = match args
+		{
+			[]         => None,
+			[argument] => Some(argument.clone()),
+			_          => return Err(format!("Sobran argumentos para ctl {}", command)),
+		};
+
+		let mut json = vec![(String::from("command"), crate::json::Json::String(command.clone()))];
+
+		match (command.as_str(), argument)
+		{
+			("water", Some(plant)) | ("add-plant", Some(plant)) | ("remove-plant", Some(plant)) =>
+			{
+				json.push((String::from("plant"), crate::json::Json::String(plant)));
+			},
+			("set-weather", Some(weather)) => json.push((String::from("weather"), crate::json::Json::String(weather))),
+			("pause", Some(paused)) =>
+			{
+				let paused = match paused.as_str()
+				{
+					"on"  => true,
+					"off" => false,
+					_     => return Err(format!("ctl pause acepta on u off, no {}", paused)),
+				};
+				json.push((String::from("paused"), crate::json::Json::Bool(paused)));
+			},
+			("screenshot", Some(path)) =>
+			{
+				let path = std::env::current_dir().map(|directory| directory.join(&path)).unwrap_or_else(|_| std::path::PathBuf::from(path));
+				json.push((String::from("path"), crate::json::Json::String(path.to_string_lossy().into_owned())));
+			},
+			("water", None) | ("remove-plant", None) | ("pause", None) | ("status", None) | ("quit", None) => {},
+			(_, Some(argument)) => return Err(format!("ctl {} no lleva argumentos, sobra {}", command, argument)),
+			(_, None) => {},
+		}
+
+		return ControlCommand::from_json(&crate::json::Json::Object(json));
+	}
+}
+
+fn optional_string(key: &str, value: &Option<String>) -> Vec<(String, crate::json::Json)>
+{
+	return value.iter().map(|value| (String::from(key), crate::json::Json::String(value.clone()))).collect();
+}
+
+//NOTE(fpalacios): "ok" va primero para que se lea facil desde una barra de estado
+pub fn response(result: Result<Vec<(String, crate::json::Json)>, String>) -> crate::json::Json
+{
+	return match result
+	{
+		Ok(mut members) =>
+		{
+			members.insert(0, (String::from("ok"), crate::json::Json::Bool(true)));
+			crate::json::Json::Object(members)
+		},
+		Err(message) => crate::json::Json::Object(vec![
+			(String::from("ok"),    crate::json::Json::Bool(false)),
+			(String::from("error"), crate::json::Json::String(message)),
+		]),
+	};
+}
+
+//NOTE(fpalacios): Un pedido que llego por el socket, el loop principal lo contesta con reply
+pub struct ControlRequest
+{
+	pub command: ControlCommand,
+	stream     : std::os::unix::net::UnixStream,
+}
+
+impl ControlRequest
+{
+	pub fn reply(&mut self, response: &crate::json::Json)
+	{
+		write_line(&self.stream, response);
+	}
+}
+
+fn write_line(mut stream: &std::os::unix::net::UnixStream, json: &crate::json::Json)
+{
+	//NOTE(fpalacios): Si el cliente ya se fue no importa
+	let _ = std::io::Write::write_all(&mut stream, format!("{}\n", json).as_bytes());
+}
+
+//NOTE(fpalacios): $XDG_RUNTIME_DIR/jardincho.sock. Sin XDG_RUNTIME_DIR va a un directorio del usuario dentro del temporal,
+//que solo el puede abrir. Un nombre fijo directo en /tmp lo podria crear antes cualquiera y atender los pedidos
+pub fn socket_path() -> Result<std::path::PathBuf, String>
+{
+	if let Some(runtime_directory) = std::env::var_os("XDG_RUNTIME_DIR").filter(|value| !value.is_empty())
+	{
+		return Ok(std::path::Path::new(&runtime_directory).join(SOCKET_FILE));
+	}
+
+	let directory = std::env::temp_dir().join(format!("jardincho-{}", unsafe { libc::getuid() }));
+	private_directory(&directory)?;
+
+	return Ok(directory.join(SOCKET_FILE));
+}
+
+//NOTE(fpalacios): Lo crea con 0700 si no existe. Si ya existe tiene que ser un directorio de verdad, no un link, del usuario
+//y sin permisos para nadie mas
+fn private_directory(directory: &std::path::Path) -> Result<(), String>
+{
+	match std::os::unix::fs::DirBuilderExt::mode(&mut std::fs::DirBuilder::new(), 0o700).create(directory)
+	{
+		Ok(())                                                        => {},
+		Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {},
+		Err(err) => return Err(format!("No se pudo crear el directorio del socket de control {}: {}", directory.display(), err)),
+	}
+
+	let metadata = std::fs::symlink_metadata(directory)
+		.map_err(|err| format!("No se pudo revisar el directorio del socket de control {}: {}", directory.display(), err))?;
+
+	let uid  = std::os::unix::fs::MetadataExt::uid(&metadata);
+	let mode = std::os::unix::fs::MetadataExt::mode(&metadata);
+
+	if !metadata.file_type().is_dir() || uid != unsafe { libc::getuid() } || mode & 0o077 != 0
+	{
+		return Err(format!(
+			"{} no es un directorio privado del usuario, no se usa para el socket de control. Borralo o defini XDG_RUNTIME_DIR",
+			directory.display()
+		));
+	}
+
+	return Ok(());
+}
+
+//NOTE(fpalacios): Si el archivo ya existe pero nadie contesta es de un jardincho que se cerro mal y se borra
+pub fn bind() -> Result<(std::os::unix::net::UnixListener, std::path::PathBuf), String>
+{
+	let path = socket_path()?;
+
+	if path.exists()
+	{
+		if std::os::unix::net::UnixStream::connect(&path).is_ok()
+		{
+			return Err(format!("Ya hay otro jardincho escuchando en {}, no se abre el socket de control", path.display()));
+		}
+
+		let _ = std::fs::remove_file(&path);
+	}
+
+	let listener = std::os::unix::net::UnixListener::bind(&path)
+		.map_err(|err| format!("No se pudo abrir el socket de control {}: {}", path.display(), err))?;
+
+	//NOTE(fpalacios): El directorio ya es solo del usuario, esto es por si XDG_RUNTIME_DIR no lo es. Si no se puede, se cierra
+	if let Err(err) = std::fs::set_permissions(&path, std::os::unix::fs::PermissionsExt::from_mode(0o600))
+	{
+		let _ = std::fs::remove_file(&path);
+		return Err(format!("No se pudieron cambiar los permisos del socket de control {}: {}", path.display(), err));
+	}
+
+	return Ok((listener, path));
+}
+
+//NOTE(fpalacios): Un pedido no puede ser mas largo que esto. Si un cliente manda mas sin cortar la linea se le contesta
+//con un error y se cierra la conexion, asi no llena la memoria
+const MAX_LINE: usize = 64 * 1024;
+
+//NOTE(fpalacios): Una conexion abierta y lo que llego despues del ultimo salto de linea
+struct Connection
+{
+	stream : std::os::unix::net::UnixStream,
+	pending: Vec<u8>,
+}
+
+//NOTE(fpalacios): Atiende todas las conexiones desde un solo thread con poll, asi un cliente que no cierra no bloquea a los
+//demas. Los pedidos se le pasan al loop principal, wake lo despierta y devuelve false si el loop ya termino
+pub fn serve(
+	listener: std::os::unix::net::UnixListener,
+	sender  : std::sync::mpsc::Sender<ControlRequest>,
+	wake    : impl Fn() -> bool
+)
+{
+	if let Err(err) = listener.set_nonblocking(true)
+	{
+		println!("Error en el socket de control {:?}", err);
+		return;
+	}
+
+	let mut connections: Vec<Connection> = Vec::new();
+
+	loop
+	{
+		let mut fds = vec![std::os::unix::io::AsRawFd::as_raw_fd(&listener)];
+		fds.extend(connections.iter().map(|connection| std::os::unix::io::AsRawFd::as_raw_fd(&connection.stream)));
+
+		let ready = match crate::platform::poll::wait_readable(&fds, None)
+		{
+			Ok(ready) => ready,
+			Err(err)  =>
+			{
+				println!("Error en el socket de control {:?}", err);
+				return;
+			}
+		};
+
+		//NOTE(fpalacios): De atras para adelante, asi sacar una conexion no corre los indices de las que faltan
+		for index in (0 .. connections.len()).rev()
+		{
+			if !ready.contains(&fds[index + 1])
+			{
+				continue;
+			}
+
+			let read = match read_requests(&mut connections[index], &sender)
+			{
+				Some(read) => read,
+				None       => return,
+			};
+
+			if !read.open
+			{
+				connections.swap_remove(index);
+			}
+
+			if read.sent && !wake()
+			{
+				return;
+			}
+		}
+
+		if ready.contains(&fds[0])
+		{
+			loop
+			{
+				match listener.accept()
+				{
+					Ok((stream, _)) =>
+					{
+						let _ = stream.set_write_timeout(Some(REPLY_TIMEOUT));
+						connections.push(Connection { stream, pending: Vec::new() });
+					},
+					Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => break,
+					Err(err) =>
+					{
+						println!("Error en el socket de control {:?}", err);
+						break;
+					},
+				}
+			}
+		}
+	}
+}
+
+struct ReadResult
+{
+	//NOTE(fpalacios): Mando algun pedido al loop principal, hay que despertarlo
+	sent: bool,
+	open: bool,
+}
+
+//NOTE(fpalacios): poll ya dijo que hay algo para leer, asi que read no bloquea aunque el socket sea bloqueante. Se deja
+//bloqueante porque las respuestas del loop principal se escriben con write_all. None si el loop principal ya termino
+fn read_requests(connection: &mut Connection, sender: &std::sync::mpsc::Sender<ControlRequest>) -> Option<ReadResult>
+{
+	let mut buffer = [0u8; 4096];
+
+	let read = match std::io::Read::read(&mut connection.stream, &mut buffer)
+	{
+		Ok(read)                                                   => read,
+		Err(err) if err.kind() == std::io::ErrorKind::Interrupted => return Some(ReadResult { sent: false, open: true }),
+		Err(_)                                                     => 0,
+	};
+
+	connection.pending.extend_from_slice(&buffer[.. read]);
+
+	let mut sent = false;
+	while let Some(end) = connection.pending.iter().position(|byte| *byte == b'\n')
+	{
+		let line: Vec<u8> = connection.pending.drain(..= end).collect();
+
+		let command = std::str::from_utf8(&line)
+			.map_err(|_| String::from("El pedido no es UTF-8"))
+			.and_then(parse_request);
+
+		let command = match command
+		{
+			Ok(Some(command)) => command,
+			Ok(None)          => continue,
+			//NOTE(fpalacios): Los pedidos mal armados se contestan aca sin molestar al loop
+			Err(message) =>
+			{
+				write_line(&connection.stream, &response(Err(message)));
+				continue;
+			}
+		};
+
+		let writer = match connection.stream.try_clone()
+		{
+			Ok(writer) => writer,
+			Err(_)     => return Some(ReadResult { sent, open: false }),
+		};
+
+		if sender.send(ControlRequest { command, stream: writer }).is_err()
+		{
+			return None;
+		}
+
+		sent = true;
+	}
+
+	if connection.pending.len() > MAX_LINE
+	{
+		write_line(&connection.stream, &response(Err(format!("El pedido es mas largo que {} bytes", MAX_LINE))));
+		return Some(ReadResult { sent, open: false });
+	}
+
+	return Some(ReadResult { sent, open: read > 0 });
+}
+
+//NOTE(fpalacios): None si la linea esta vacia
+fn parse_request(line: &str) -> Result<Option<ControlCommand>, String>
+{
+	if line.trim().is_empty()
+	{
+		return Ok(None);
+	}
+
+	return crate::json::parse(line)
+		.map_err(|err| format!("JSON invalido en la posicion {}: {}", err.position, err.message))
+		.and_then(|json| ControlCommand::from_json(&json))
+		.map(Some);
+}
+
+//NOTE(fpalacios): "jardincho ctl", manda un pedido y devuelve la respuesta como vino
+pub fn send(command: &ControlCommand) -> Result<crate::json::Json, String>
+{
+	let path = socket_path()?;
+
+	let mut stream = std::os::unix::net::UnixStream::connect(&path)
+		.map_err(|err| format!("No hay ningun jardincho corriendo en {} ({})", path.display(), err))?;
+
+	let _ = stream.set_read_timeout(Some(CLIENT_TIMEOUT));
+
+	std::io::Write::write_all(&mut stream, format!("{}\n", command.to_json()).as_bytes())
+		.map_err(|err| format!("No se pudo mandar el pedido: {}", err))?;
+
+	let mut line = String::new();
+	std::io::BufRead::read_line(&mut std::io::BufReader::new(&stream), &mut line)
+		.map_err(|err| format!("No llego la respuesta: {}", err))?;
+
+	if line.trim().is_empty()
+	{
+		return Err(String::from("El jardin cerro la conexion sin contestar"));
+	}
+
+	return crate::json::parse(&line).map_err(|err| format!("Respuesta invalida en la posicion {}: {}", err.position, err.message));
+}
+
+#[cfg(test)]
+mod tests
+{
+	fn args(args: &[&str]) -> Vec<String>
+	{
+		return args.iter().map(|arg| String::from(*arg)).collect();
+	}
+
+	fn commands() -> Vec<super::ControlCommand>
+	{
+		return vec![
+			super::ControlCommand::Water(None),
+			super::ControlCommand::Water(Some(String::from("bonsai"))),
+			super::ControlCommand::AddPlant(String::from("helecho \"grande\"")),
+			super::ControlCommand::RemovePlant(None),
+			super::ControlCommand::RemovePlant(Some(String::from("cactus"))),
+			super::ControlCommand::SetWeather(crate::garden::Weather::Clear),
+			super::ControlCommand::SetWeather(crate::garden::Weather::Cloudy),
+			super::ControlCommand::SetWeather(crate::garden::Weather::Rain),
+			super::ControlCommand::Pause(None),
+			super::ControlCommand::Pause(Some(true)),
+			super::ControlCommand::Pause(Some(false)),
+			super::ControlCommand::Screenshot(std::path::PathBuf::from("/tmp/jardin ñ.png")),
+			super::ControlCommand::Status,
+			super::ControlCommand::Quit,
+		];
+	}
+
+	#[test]
+	fn json_round_trip()
+	{
+		for command in commands()
+		{
+			let json = command.to_json();
+			assert_eq!(super::ControlCommand::from_json(&json), Ok(command.clone()));
+
+			//NOTE(fpalacios): Lo mismo que viaja por el socket
+			let line = crate::json::parse(&json.to_string()).unwrap();
+			assert_eq!(super::ControlCommand::from_json(&line), Ok(command));
+		}
+	}
+
+	#[test]
+	fn from_args()
+	{
+		let directory = std::env::current_dir().unwrap();
+
+		assert_eq!(super::ControlCommand::from_args(&args(&["water"])), Ok(super::ControlCommand::Water(None)));
+		assert_eq!(super::ControlCommand::from_args(&args(&["water", "bonsai"])), Ok(super::ControlCommand::Water(Some(String::from("bonsai")))));
+		assert_eq!(super::ControlCommand::from_args(&args(&["add-plant", "bonsai"])), Ok(super::ControlCommand::AddPlant(String::from("bonsai"))));
+		assert_eq!(super::ControlCommand::from_args(&args(&["remove-plant"])), Ok(super::ControlCommand::RemovePlant(None)));
+		assert_eq!(super::ControlCommand::from_args(&args(&["set-weather", "rain"])), Ok(super::ControlCommand::SetWeather(crate::garden::Weather::Rain)));
+		assert_eq!(super::ControlCommand::from_args(&args(&["pause"])), Ok(super::ControlCommand::Pause(None)));
+		assert_eq!(super::ControlCommand::from_args(&args(&["pause", "on"])), Ok(super::ControlCommand::Pause(Some(true))));
+		assert_eq!(super::ControlCommand::from_args(&args(&["pause", "off"])), Ok(super::ControlCommand::Pause(Some(false))));
+		assert_eq!(super::ControlCommand::from_args(&args(&["screenshot", "jardin.png"])), Ok(super::ControlCommand::Screenshot(directory.join("jardin.png"))));
+		assert_eq!(super::ControlCommand::from_args(&args(&["screenshot", "/tmp/jardin.png"])), Ok(super::ControlCommand::Screenshot(std::path::PathBuf::from("/tmp/jardin.png"))));
+		assert_eq!(super::ControlCommand::from_args(&args(&["status"])), Ok(super::ControlCommand::Status));
+		assert_eq!(super::ControlCommand::from_args(&args(&["quit"])), Ok(super::ControlCommand::Quit));
+
+		//NOTE(fpalacios): Lo que arma from_args tiene que volver igual por el socket
+		for command in [["water", "bonsai"], ["set-weather", "cloudy"], ["pause", "on"], ["screenshot", "jardin.png"]]
+		{
+			let command = super::ControlCommand::from_args(&args(&command)).unwrap();
+			assert_eq!(super::ControlCommand::from_json(&command.to_json()), Ok(command));
+		}
+	}
+
+	#[test]
+	fn errors()
+	{
+		let error = |args: &[&str]| super::ControlCommand::from_args(&self::args(args)).unwrap_err();
+
+		assert!(error(&[]).starts_with("ctl necesita un comando"));
+		assert_eq!(error(&["add-plant"]), "add-plant necesita \"plant\"");
+		assert_eq!(error(&["water", "a", "b"]), "Sobran argumentos para ctl water");
+		assert_eq!(error(&["pause", "si"]), "ctl pause acepta on u off, no si");
+		assert_eq!(error(&["status", "ya"]), "ctl status no lleva argumentos, sobra ya");
+		assert_eq!(error(&["set-weather", "nieve"]), "\"weather\" tiene que ser uno de clear, cloudy, rain, no nieve");
+		assert!(error(&["regar"]).starts_with("No existe el comando regar"));
+
+		let from_json = |source: &str| super::ControlCommand::from_json(&crate::json::parse(source).unwrap()).unwrap_err();
+
+		assert_eq!(from_json("{}"), "Falta \"command\"");
+		assert_eq!(from_json(r#"{ "command": "water", "plant": 3 }"#), "\"plant\" tiene que ser un texto");
+		assert_eq!(from_json(r#"{ "command": "pause", "paused": "on" }"#), "\"paused\" tiene que ser true o false");
+		assert_eq!(from_json(r#"{ "command": "screenshot", "path": "jardin.png" }"#), "\"path\" tiene que ser una ruta absoluta");
+	}
+
+	#[test]
+	fn private_directory()
+	{
+		let parent = std::env::temp_dir().join(format!("jardincho-ipc-{}", std::process::id()));
+		let _ = std::fs::remove_dir_all(&parent);
+		std::fs::create_dir_all(&parent).unwrap();
+
+		let directory = parent.join("socket");
+		assert_eq!(super::private_directory(&directory), Ok(()));
+
+		let mode = std::os::unix::fs::MetadataExt::mode(&std::fs::metadata(&directory).unwrap());
+		assert_eq!(mode & 0o777, 0o700);
+
+		//NOTE(fpalacios): La segunda vez ya existe y se usa como esta
+		assert_eq!(super::private_directory(&directory), Ok(()));
+
+		std::fs::set_permissions(&directory, std::os::unix::fs::PermissionsExt::from_mode(0o755)).unwrap();
+		assert!(super::private_directory(&directory).is_err());
+
+		let link = parent.join("link");
+		std::fs::set_permissions(&directory, std::os::unix::fs::PermissionsExt::from_mode(0o700)).unwrap();
+		std::os::unix::fs::symlink(&directory, &link).unwrap();
+		assert!(super::private_directory(&link).is_err());
+
+		let file = parent.join("file");
+		std::fs::write(&file, "").unwrap();
+		assert!(super::private_directory(&file).is_err());
+
+		std::fs::remove_dir_all(&parent).unwrap();
+	}
+
+	fn read_line(stream: &std::os::unix::net::UnixStream) -> String
+	{
+		let mut line = String::new();
+		std::io::BufRead::read_line(&mut std::io::BufReader::new(stream), &mut line).unwrap();
+		return line;
+	}
+
+	#[test]
+	fn serves_every_connection_from_one_thread()
+	{
+		let directory = std::env::temp_dir().join(format!("jardincho-serve-{}", std::process::id()));
+		let _ = std::fs::remove_dir_all(&directory);
+		std::fs::create_dir_all(&directory).unwrap();
+
+		let path     = directory.join(super::SOCKET_FILE);
+		let listener = std::os::unix::net::UnixListener::bind(&path).unwrap();
+
+		let (sender, receiver) = std::sync::mpsc::channel();
+		std::thread::spawn(move || super::serve(listener, sender, || true));
+
+		let connect = ||
+		{
+			let stream = std::os::unix::net::UnixStream::connect(&path).unwrap();
+			stream.set_read_timeout(Some(std::time::Duration::from_secs(5))).unwrap();
+			return stream;
+		};
+
+		//NOTE(fpalacios): Uno que deja una linea a medias no traba a los demas
+		let mut idle = connect();
+		std::io::Write::write_all(&mut idle, b"{ \"command\": ").unwrap();
+
+		let mut client = connect();
+		std::io::Write::write_all(&mut client, b"{ \"command\": \"pause\" }\n\n{ \"command\": \"water\" }\n").unwrap();
+
+		let mut request = receiver.recv_timeout(std::time::Duration::from_secs(5)).unwrap();
+		assert_eq!(request.command, super::ControlCommand::Pause(None));
+		request.reply(&super::response(Ok(Vec::new())));
+		assert_eq!(read_line(&client), "{\"ok\":true}\n");
+
+		let request = receiver.recv_timeout(std::time::Duration::from_secs(5)).unwrap();
+		assert_eq!(request.command, super::ControlCommand::Water(None));
+
+		//NOTE(fpalacios): Los pedidos invalidos se contestan sin pasar por el loop
+		std::io::Write::write_all(&mut client, b"{\n").unwrap();
+		assert!(read_line(&client).contains("JSON invalido"));
+
+		let mut long = connect();
+		std::io::Write::write_all(&mut long, &vec![b' '; super::MAX_LINE + 1]).unwrap();
+		assert!(read_line(&long).contains("mas largo"));
+		assert_eq!(read_line(&long), "");
+
+		std::io::Write::write_all(&mut idle, b"\"quit\" }\n").unwrap();
+		let request = receiver.recv_timeout(std::time::Duration::from_secs(5)).unwrap();
+		assert_eq!(request.command, super::ControlCommand::Quit);
+
+		std::fs::remove_dir_all(&directory).unwrap();
+	}
+}
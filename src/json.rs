@@ -0,0 +1,513 @@
+//NOTE(fpalacios): Parser y serializador de JSON chiquito, alcanza para glTF y para el protocolo de control
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json
+{
+	Null,
+	Bool(bool),
+	Number(f64),
+	String(String),
+	Array(Vec<Json>),
+	//NOTE(fpalacios): Se mantiene el orden de las claves como vinieron
+	Object(Vec<(String, Json)>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonError
+{
+	pub position: usize,
+	pub message : String,
+}
+
+impl Json
+{
+	pub fn get(&self, key: &str) -> Option<&Json>
+	{
+		return match self
+		{
+			Json::Object(members) => members.iter().find(|(name, _)| name == key).map(|(_, value)| value),
+			_                     => None,
+		};
+	}
+
+	pub fn index(&self, index: usize) -> Option<&Json>
+	{
+		return match self
+		{
+			Json::Array(values) => values.get(index),
+			_                   => None,
+		};
+	}
+
+	pub fn as_f64(&self) -> Option<f64>
+	{
+		return match self
+		{
+			Json::Number(number) => Some(*number),
+			_                    => None,
+		};
+	}
+
+	pub fn as_usize(&self) -> Option<usize>
+	{
+		let number = self.as_f64()?;
+		if number < 0.0 || number.fract() != 0.0
+		{
+			return None;
+		}
+
+		return Some(number as usize);
+	}
+
+	pub fn as_bool(&self) -> Option<bool>
+	{
+		return match self
+		{
+			Json::Bool(value) => Some(*value),
+			_                 => None,
+		};
+	}
+
+	pub fn as_str(&self) -> Option<&str>
+	{
+		return match self
+		{
+			Json::String(value) => Some(value),
+			_                   => None,
+		};
+	}
+
+	pub fn as_array(&self) -> Option<&[Json]>
+	{
+		return match self
+		{
+			Json::Array(values) => Some(values),
+			_                   => None,
+		};
+	}
+
+	pub fn as_object(&self) -> Option<&[(String, Json)]>
+	{
+		return match self
+		{
+			Json::Object(members) => Some(members),
+			_                     => None,
+		};
+	}
+
+	//NOTE(fpalacios): Un arreglo de numeros, None si algun elemento no es numero
+	pub fn as_f64_array(&self) -> Option<Vec<f64>>
+	{
+		return self.as_array()?.iter().map(|value| value.as_f64()).collect();
+	}
+
+	fn write(&self, output: &mut String)
+	{
+		match self
+		{
+			Json::Null          => output.push_str("null"),
+			Json::Bool(value)   => output.push_str(if *value { "true" } else { "false" }),
+			Json::Number(value) =>
+			{
+				//NOTE(fpalacios): JSON no tiene NaN ni infinito
+				if value.is_finite()
+				{
+					output.push_str(&format!("{}", value));
+				}
+				else
+				{
+					output.push_str("null");
+				}
+			},
+			Json::String(value) => write_string(value, output),
+			Json::Array(values) =>
+			{
+				output.push('[');
+				for (index, value) in values.iter().enumerate()
+				{
+					if index > 0
+					{
+						output.push(',');
+					}
+					value.write(output);
+				}
+				output.push(']');
+			},
+			Json::Object(members) =>
+			{
+				output.push('{');
+				for (index, (name, value)) in members.iter().enumerate()
+				{
+					if index > 0
+					{
+						output.push(',');
+					}
+					write_string(name, output);
+					output.push(':');
+					value.write(output);
+				}
+				output.push('}');
+			},
+		}
+	}
+}
+
+impl std::fmt::Display for Json
+{
+	fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result
+	{
+		let mut output = String::new();
+		self.write(&mut output);
+		return formatter.write_str(&output);
+	}
+}
+
+fn write_string(value: &str, output: &mut String)
+{
+	output.push('"');
+	for character in value.chars()
+	{
+		match character
+		{
+			'"'  => output.push_str("\\\""),
+			'\\' => output.push_str("\\\\"),
+			'\n' => output.push_str("\\n"),
+			'\r' => output.push_str("\\r"),
+			'\t' => output.push_str("\\t"),
+			character if (character as u32) < 0x20 => output.push_str(&format!("\\u{:04x}", character as u32)),
+			character => output.push(character),
+		}
+	}
+	output.push('"');
+}
+
+//NOTE(fpalacios): Los arreglos y objetos se leen recursivamente, con un tope para que un archivo con miles de [ seguidos
+//devuelva un error en vez de llenar el stack
+const MAX_DEPTH: usize = 128;
+
+pub fn parse(source: &str) -> Result<Json, JsonError>
+{
+	let mut parser = Parser
+	{
+		bytes   : source.as_bytes(),
+		position: 0,
+		depth   : 0,
+	};
+
+	let value = parser.parse_value()?;
+
+	parser.skip_whitespace();
+	if parser.position != parser.bytes.len()
+	{
+		return Err(parser.error("Sobra texto despues del valor"));
+	}
+
+	return Ok(value);
+}
+
+struct Parser<'source>
+{
+	bytes   : &'source [u8],
+	position: usize,
+	depth   : usize,
+}
+
+impl<'source> Parser<'source>
+{
+	fn error(&self, message: &str) -> JsonError
+	{
+		return JsonError
+		{
+			position: self.position,
+			message : String::from(message),
+		};
+	}
+
+	fn skip_whitespace(&mut self)
+	{
+		while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') = self.bytes.get(self.position)
+		{
+			self.position += 1;
+		}
+	}
+
+	fn expect_literal(&mut self, literal: &str, value: Json) -> Result<Json, JsonError>
+	{
+		if self.bytes[self.position ..].starts_with(literal.as_bytes())
+		{
+			self.position += literal.len();
+			return Ok(value);
+		}
+
+		return Err(self.error("Valor invalido"));
+	}
+
+	fn parse_value(&mut self) -> Result<Json, JsonError>
+	{
+		self.skip_whitespace();
+
+		return match self.bytes.get(self.position)
+		{
+			Some(b'n')                       => self.expect_literal("null", Json::Null),
+			Some(b't')                       => self.expect_literal("true", Json::Bool(true)),
+			Some(b'f')                       => self.expect_literal("false", Json::Bool(false)),
+			Some(b'"')                       => Ok(Json::String(self.parse_string()?)),
+			Some(b'[') | Some(b'{')          => self.parse_nested(),
+			Some(b'-') | Some(b'0' ..= b'9') => self.parse_number(),
+			Some(_)                          => Err(self.error("Valor invalido")),
+			None                             => Err(self.error("Se termino el texto")),
+		};
+	}
+
+	fn parse_nested(&mut self) -> Result<Json, JsonError>
+	{
+		if self.depth == MAX_DEPTH
+		{
+			return Err(self.error("Demasiados arreglos u objetos anidados"));
+		}
+
+		self.depth += 1;
+		let value = if self.bytes[self.position] == b'[' { self.parse_array() } else { self.parse_object() };
+		self.depth -= 1;
+
+		return value;
+	}
+
+	fn parse_number(&mut self) -> Result<Json, JsonError>
+	{
+		let start = self.position;
+
+		while let Some(b'-') | Some(b'+') | Some(b'.') | Some(b'e') | Some(b'E') | Some(b'0' ..= b'9') = self.bytes.get(self.position)
+		{
+			self.position += 1;
+		}
+
+		let text = std::str::from_utf8(&self.bytes[start .. self.position]).unwrap();
+
+		return text.parse::<f64>().map(Json::Number).map_err(|_| JsonError { position: start, message: String::from("Numero invalido") });
+	}
+
+	fn parse_hex4(&mut self) -> Result<u32, JsonError>
+	{
+		let digits = self.bytes.get(self.position .. self.position + 4).ok_or_else(|| self.error("Escape \\u incompleto"))?;
+		let text = std::str::from_utf8(digits).map_err(|_| self.error("Escape \\u invalido"))?;
+		let value = u32::from_str_radix(text, 16).map_err(|_| self.error("Escape \\u invalido"))?;
+
+		self.position += 4;
+
+		return Ok(value);
+	}
+
+	fn parse_string(&mut self) -> Result<String, JsonError>
+	{
+		//NOTE(fpalacios): Salta la comilla de apertura
+		self.position += 1;
+
+		let mut bytes = Vec::new();
+
+		loop
+		{
+			let byte = *self.bytes.get(self.position).ok_or_else(|| self.error("String sin cerrar"))?;
+			self.position += 1;
+
+			match byte
+			{
+				b'"'  => break,
+				b'\\' =>
+				{
+					let escape = *self.bytes.get(self.position).ok_or_else(|| self.error("String sin cerrar"))?;
+					self.position += 1;
+
+					let character = match escape
+					{
+						b'"'  => '"',
+						b'\\' => '\\',
+						b'/'  => '/',
+						b'b'  => '\u{8}',
+						b'f'  => '\u{c}',
+						b'n'  => '\n',
+						b'r'  => '\r',
+						b't'  => '\t',
+						b'u'  =>
+						{
+							let mut code = self.parse_hex4()?;
+
+							//NOTE(fpalacios): Los caracteres fuera del plano basico vienen como par de surrogates
+							if (0xD800 .. 0xDC00).contains(&code) && self.bytes[self.position ..].starts_with(b"\\u")
+							{
+								self.position += 2;
+								let low = self.parse_hex4()?;
+								code = 0x10000 + ((code - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF);
+							}
+
+							std::char::from_u32(code).unwrap_or('\u{FFFD}')
+						},
+						_ => return Err(self.error("Escape invalido")),
+					};
+
+					let mut buffer = [0_u8; 4];
+					bytes.extend_from_slice(character.encode_utf8(&mut buffer).as_bytes());
+				},
+				byte => bytes.push(byte),
+			}
+		}
+
+		return String::from_utf8(bytes).map_err(|_| self.error("String con UTF-8 invalido"));
+	}
+
+	fn parse_array(&mut self) -> Result<Json, JsonError>
+	{
+		self.position += 1;
+		let mut values = Vec::new();
+
+		self.skip_whitespace();
+		if self.bytes.get(self.position) == Some(&b']')
+		{
+			self.position += 1;
+			return Ok(Json::Array(values));
+		}
+
+		loop
+		{
+			values.push(self.parse_value()?);
+
+			self.skip_whitespace();
+			match self.bytes.get(self.position)
+			{
+				Some(b',') => self.position += 1,
+				Some(b']') =>
+				{
+					self.position += 1;
+					return Ok(Json::Array(values));
+				},
+				_ => return Err(self.error("Se esperaba ',' o ']'")),
+			}
+		}
+	}
+
+	fn parse_object(&mut self) -> Result<Json, JsonError>
+	{
+		self.position += 1;
+		let mut members = Vec::new();
+
+		self.skip_whitespace();
+		if self.bytes.get(self.position) == Some(&b'}')
+		{
+			self.position += 1;
+			return Ok(Json::Object(members));
+		}
+
+		loop
+		{
+			self.skip_whitespace();
+			if self.bytes.get(self.position) != Some(&b'"')
+			{
+				return Err(self.error("Se esperaba el nombre de la clave"));
+			}
+			let name = self.parse_string()?;
+
+			self.skip_whitespace();
+			if self.bytes.get(self.position) != Some(&b':')
+			{
+				return Err(self.error("Se esperaba ':'"));
+			}
+			self.position += 1;
+
+			members.push((name, self.parse_value()?));
+
+			self.skip_whitespace();
+			match self.bytes.get(self.position)
+			{
+				Some(b',') => self.position += 1,
+				Some(b'}') =>
+				{
+					self.position += 1;
+					return Ok(Json::Object(members));
+				},
+				_ => return Err(self.error("Se esperaba ',' o '}'")),
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	fn object(members: Vec<(&str, super::Json)>) -> super::Json
+	{
+		return super::Json::Object(members.into_iter().map(|(name, value)| (String::from(name), value)).collect());
+	}
+
+	#[test]
+	fn values()
+	{
+		assert_eq!(
+			super::parse(" { \"a\": [1, -2.5, 1e3, true, false, null], \"b\": {}, \"c\": [], \"a\": \"otra vez\" } "),
+			Ok(object(vec![
+				("a", super::Json::Array(vec![
+					super::Json::Number(1.0),
+					super::Json::Number(-2.5),
+					super::Json::Number(1000.0),
+					super::Json::Bool(true),
+					super::Json::Bool(false),
+					super::Json::Null,
+				])),
+				("b", object(vec![])),
+				("c", super::Json::Array(vec![])),
+				//NOTE(fpalacios): Las claves repetidas se guardan las dos, get devuelve la primera
+				("a", super::Json::String(String::from("otra vez"))),
+			]))
+		);
+	}
+
+	#[test]
+	fn escapes()
+	{
+		assert_eq!(
+			super::parse(r#""comillas \" barra \\ \/ \b\f\n\r\t enie ñ rana 🐸""#),
+			Ok(super::Json::String(String::from("comillas \" barra \\ / \u{8}\u{c}\n\r\t enie ñ rana \u{1F438}")))
+		);
+
+		//NOTE(fpalacios): Un surrogate sin pareja no es un caracter valido
+		assert_eq!(super::parse(r#""\ud83d""#), Ok(super::Json::String(String::from("\u{FFFD}"))));
+
+		assert!(super::parse(r#""\x""#).is_err());
+		assert!(super::parse(r#""\u00""#).is_err());
+		assert!(super::parse(r#""\u00zz""#).is_err());
+	}
+
+	#[test]
+	fn errors()
+	{
+		assert_eq!(super::parse("[1, 2").unwrap_err().position, 5);
+		assert_eq!(super::parse("{\"a\" 1}").unwrap_err().position, 5);
+		assert_eq!(super::parse("{a: 1}").unwrap_err().position, 1);
+		assert_eq!(super::parse("[1] 2").unwrap_err().position, 4);
+		assert_eq!(super::parse("nul").unwrap_err().position, 0);
+		assert_eq!(super::parse("1.2.3").unwrap_err().position, 0);
+		assert_eq!(super::parse("\"sin cerrar").unwrap_err().position, 11);
+		assert_eq!(super::parse("").unwrap_err().position, 0);
+
+		let deep = format!("{}{}", "[".repeat(super::MAX_DEPTH), "]".repeat(super::MAX_DEPTH));
+		assert!(super::parse(&deep).is_ok());
+
+		let too_deep = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+		assert_eq!(super::parse(&too_deep).unwrap_err().position, super::MAX_DEPTH);
+	}
+
+	#[test]
+	fn round_trip()
+	{
+		let json = object(vec![
+			("texto",  super::Json::String(String::from("\"hola\"\n\t\u{1} ñ"))),
+			("numero", super::Json::Number(0.25)),
+			("lista",  super::Json::Array(vec![super::Json::Null, super::Json::Bool(true), object(vec![])])),
+		]);
+
+		let text = json.to_string();
+		assert_eq!(text, "{\"texto\":\"\\\"hola\\\"\\n\\t\\u0001 ñ\",\"numero\":0.25,\"lista\":[null,true,{}]}");
+		assert_eq!(super::parse(&text), Ok(json));
+
+		assert_eq!(super::Json::Number(f64::NAN).to_string(), "null");
+		assert_eq!(super::Json::Number(f64::INFINITY).to_string(), "null");
+	}
+}
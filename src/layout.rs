@@ -0,0 +1,240 @@
+#[derive(Debug, Clone, PartialEq)]
+pub enum MonitorSelection
+{
+	Primary,
+	All,
+	Named(String),
+}
+
+impl MonitorSelection
+{
+	pub fn parse(value: &str) -> MonitorSelection
+	{
+		return match value
+		{
+			"primary" => MonitorSelection::Primary,
+			"all"     => MonitorSelection::All,
+			name      => MonitorSelection::Named(name.to_owned()),
+		};
+	}
+
+	pub fn select(&self, monitors: &[crate::platform::xcb::Monitor]) -> Vec<crate::platform::xcb::Monitor>
+	{
+		let selected: Vec<crate::platform::xcb::Monitor> = match self
+		{
+			MonitorSelection::All => monitors.to_vec(),
+			MonitorSelection::Primary => monitors.iter().filter(|monitor| monitor.primary).cloned().collect(),
+			MonitorSelection::Named(name) => monitors.iter().filter(|monitor| &monitor.name == name).cloned().collect(),
+		};
+
+		//NOTE(fpalacios): Si no hay monitor primario o el nombre no existe (ej: se desenchufó) usamos el primero que haya
+		if selected.is_empty()
+		{
+			return monitors.iter().take(1).cloned().collect();
+		}
+
+		return selected;
+	}
+}
+
+//NOTE(fpalacios): Un monitor que ocupa toda la pantalla, para cuando RandR falla o no informa ninguno (ej: un server sin
+//la extension)
+pub fn whole_screen(width: u16, height: u16) -> crate::platform::xcb::Monitor
+{
+	return crate::platform::xcb::Monitor
+	{
+		output : 0,
+		name   : String::from("screen"),
+		primary: true,
+		x      : 0,
+		y      : 0,
+		width,
+		height,
+	};
+}
+
+//NOTE(fpalacios): Donde van las plantas dentro de cada monitor. 0.0 es a la izquierda o abajo y 1.0 a la derecha o arriba
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Anchor
+{
+	pub horizontal: f32,
+	pub vertical  : f32,
+}
+
+impl Anchor
+{
+	pub const CENTER: Anchor = Anchor { horizontal: 0.5, vertical: 0.5 };
+
+	pub const NAMES: &'static [&'static str] =
+	&[
+		"center", "top", "bottom", "left", "right", "top-left", "top-right", "bottom-left", "bottom-right",
+	];
+
+	pub fn parse(value: &str) -> Option<Anchor>
+	{
+		let (horizontal, vertical) = match value
+		{
+			"center"       => (0.5, 0.5),
+			"top"          => (0.5, 1.0),
+			"bottom"       => (0.5, 0.0),
+			"left"         => (0.0, 0.5),
+			"right"        => (1.0, 0.5),
+			"top-left"     => (0.0, 1.0),
+			"top-right"    => (1.0, 1.0),
+			"bottom-left"  => (0.0, 0.0),
+			"bottom-right" => (1.0, 0.0),
+			_              => return None,
+		};
+
+		return Some(Anchor { horizontal, vertical });
+	}
+
+	//NOTE(fpalacios): Esquina de abajo a la izquierda de un rectangulo de ese tamaño pegado al ancla dentro del area
+	pub fn place(&self, area: &crate::world::Area, width: f32, height: f32) -> (f32, f32)
+	{
+		return (
+			area.x + (area.width  - width)  * self.horizontal,
+			area.y + (area.height - height) * self.vertical,
+		);
+	}
+}
+
+//NOTE(fpalacios): Rectangulo que encierra a todos los monitores, es la geometria que tiene que tener la ventana
+pub fn bounds(monitors: &[crate::platform::xcb::Monitor]) -> Option<(i16, i16, u16, u16)>
+{
+	let first = monitors.first()?;
+
+	let mut left   = first.x as i32;
+	let mut top    = first.y as i32;
+	let mut right  = first.x as i32 + first.width as i32;
+	let mut bottom = first.y as i32 + first.height as i32;
+
+	for monitor in monitors
+	{
+		left   = left.min(monitor.x as i32);
+		top    = top.min(monitor.y as i32);
+		right  = right.max(monitor.x as i32 + monitor.width as i32);
+		bottom = bottom.max(monitor.y as i32 + monitor.height as i32);
+	}
+
+	return Some((left as i16, top as i16, (right - left) as u16, (bottom - top) as u16));
+}
+
+//NOTE(fpalacios): Pasa la geometria de cada monitor (en pixeles) a coordenadas normalizadas dentro de la ventana
+pub fn areas(monitors: &[crate::platform::xcb::Monitor], (x, y, width, height): (i16, i16, u16, u16)) -> Vec<crate::world::Area>
+{
+	return monitors.iter().map(|monitor|
+	{
+		let area_width  = monitor.width  as f32 / width  as f32 * 2.0;
+		let area_height = monitor.height as f32 / height as f32 * 2.0;
+
+		crate::world::Area
+		{
+			x     : (monitor.x as i32 - x as i32) as f32 / width as f32 * 2.0 - 1.0,
+			//NOTE(fpalacios): En X11 el eje y crece hacia abajo y en OpenGL hacia arriba
+			y     : 1.0 - (monitor.y as i32 - y as i32) as f32 / height as f32 * 2.0 - area_height,
+			width : area_width,
+			height: area_height,
+		}
+	}).collect();
+}
+
+#[cfg(test)]
+mod tests
+{
+	fn monitor(name: &str, primary: bool, (x, y, width, height): (i16, i16, u16, u16)) -> crate::platform::xcb::Monitor
+	{
+		return crate::platform::xcb::Monitor
+		{
+			output: 0,
+			name  : String::from(name),
+			primary,
+			x,
+			y,
+			width,
+			height,
+		};
+	}
+
+	fn names(monitors: &[crate::platform::xcb::Monitor]) -> Vec<&str>
+	{
+		return monitors.iter().map(|monitor| monitor.name.as_str()).collect();
+	}
+
+	#[test]
+	fn select()
+	{
+		let monitors = vec![
+			monitor("DP-1",   false, (0,    0, 1920, 1080)),
+			monitor("HDMI-1", true,  (1920, 0, 2560, 1440)),
+		];
+
+		assert_eq!(names(&super::MonitorSelection::Primary.select(&monitors)), vec!["HDMI-1"]);
+		assert_eq!(names(&super::MonitorSelection::All.select(&monitors)), vec!["DP-1", "HDMI-1"]);
+		assert_eq!(names(&super::MonitorSelection::parse("DP-1").select(&monitors)), vec!["DP-1"]);
+
+		//NOTE(fpalacios): Sin primario o con un nombre que no esta se usa el primero
+		assert_eq!(names(&super::MonitorSelection::parse("VGA-1").select(&monitors)), vec!["DP-1"]);
+		assert_eq!(names(&super::MonitorSelection::Primary.select(&monitors[.. 1])), vec!["DP-1"]);
+		assert!(super::MonitorSelection::All.select(&[]).is_empty());
+	}
+
+	#[test]
+	fn bounds()
+	{
+		assert_eq!(super::bounds(&[]), None);
+		assert_eq!(super::bounds(&[monitor("a", true, (0, 0, 1920, 1080))]), Some((0, 0, 1920, 1080)));
+
+		//NOTE(fpalacios): Uno a la izquierda del primario y mas bajo, el rectangulo tiene que cubrir a los dos
+		let monitors = [
+			monitor("a", false, (-1280, 200, 1280, 1024)),
+			monitor("b", true,  (0,     0,   1920, 1080)),
+		];
+		assert_eq!(super::bounds(&monitors), Some((-1280, 0, 3200, 1224)));
+	}
+
+	#[test]
+	fn areas()
+	{
+		let single = [monitor("a", true, (0, 0, 1920, 1080))];
+		assert_eq!(
+			super::areas(&single, (0, 0, 1920, 1080)),
+			vec![crate::world::Area { x: -1.0, y: -1.0, width: 2.0, height: 2.0 }]
+		);
+
+		//NOTE(fpalacios): b esta abajo de a en X11, asi que en coordenadas de OpenGL queda en la mitad de abajo
+		let stacked = [
+			monitor("a", true,  (100, 50,  800, 600)),
+			monitor("b", false, (100, 650, 800, 600)),
+		];
+		assert_eq!(
+			super::areas(&stacked, super::bounds(&stacked).unwrap()),
+			vec![
+				crate::world::Area { x: -1.0, y: 0.0,  width: 2.0, height: 1.0 },
+				crate::world::Area { x: -1.0, y: -1.0, width: 2.0, height: 1.0 },
+			]
+		);
+
+		let side_by_side = [
+			monitor("a", true,  (0,    0, 1000, 1000)),
+			monitor("b", false, (1000, 0, 3000, 500)),
+		];
+		assert_eq!(
+			super::areas(&side_by_side, super::bounds(&side_by_side).unwrap()),
+			vec![
+				crate::world::Area { x: -1.0, y: -1.0, width: 0.5, height: 2.0 },
+				crate::world::Area { x: -0.5, y: 0.0,  width: 1.5, height: 1.0 },
+			]
+		);
+	}
+
+	#[test]
+	fn whole_screen()
+	{
+		let screen = super::whole_screen(1366, 768);
+
+		assert!(screen.primary);
+		assert_eq!(screen.geometry(), (0, 0, 1366, 768));
+		assert_eq!(names(&super::MonitorSelection::parse("HDMI-1").select(&[screen])), vec!["screen"]);
+	}
+}
@@ -0,0 +1,26 @@
+#![allow(clippy::needless_return)]
+
+//NOTE(fpalacios): Todo menos el loop de la ventana, asi los tests de integracion en tests/ pueden usar los modulos
+pub mod world;
+pub mod assets;
+pub mod camera;
+pub mod cli;
+pub mod color;
+pub mod config;
+pub mod frame_scheduler;
+pub mod game_loop;
+pub mod garden;
+pub mod gltf;
+pub mod ipc;
+pub mod json;
+pub mod layout;
+pub mod mesh;
+pub mod offscreen;
+pub mod platform;
+pub mod postprocess;
+pub mod recording_renderer;
+pub mod renderer;
+pub mod shaders;
+pub mod software_renderer;
+pub mod state;
+pub mod visibility;
@@ -1,61 +1,109 @@
-pub mod world;
-pub mod color;
-pub mod platform;
-pub mod renderer;
+#![allow(clippy::needless_return)]
+
+//NOTE(fpalacios): Los modulos viven en lib.rs. Se traen a la raiz para que el binario los siga nombrando con crate::
+use jardincho::{assets, cli, config, frame_scheduler, game_loop, ipc, json, layout, mesh, platform, renderer, shaders, software_renderer, state, visibility, world};
 
 use glium::glutin::platform::unix::WindowExtUnix;
 
-fn setup_xcb_window(xcb_conn: &xcb::Connection, window_context: &glium::glutin::window::Window)
+//NOTE(fpalacios): Se vuelve a llamar cuando cambia window.mode en la configuracion, por eso tambien saca los estados del
+//modo anterior
+fn setup_xcb_window(
+	ewmh     : &crate::platform::ewmh::Ewmh,
+	screen_id: crate::platform::xcb::ScreenID,
+	window_id: crate::platform::xcb::WindowID,
+	mode     : crate::config::WindowMode
+)
 {
-	let xcb_client = crate::platform::xcb::Client::new(&xcb_conn);
-
-	let xcb_screen = crate::platform::xcb::Screen::from_id(
-		&xcb_client,
-		window_context.xlib_screen_id().unwrap() as crate::platform::xcb::ScreenID
-	).unwrap();
+	let xcb_screen = crate::platform::xcb::Screen::from_id(ewmh.client, screen_id).unwrap();
 
 	let xcb_window = crate::platform::xcb::Window
 	{
 		screen: &xcb_screen,
-		id    : window_context.xlib_window().unwrap() as crate::platform::xcb::WindowID,
+		id    : window_id,
 	};
 
-	xcb_window.set_property(
-		&crate::platform::xcb::Property
-		{
-			key  : xcb_client.find_atom_id_by_name("_NET_WM_WINDOW_TYPE").unwrap(),
-			value: crate::platform::xcb::PropertyValue::Atom(xcb_client.find_atom_id_by_name("_NET_WM_WINDOW_TYPE_UTILITY").unwrap())
-		}
-	);
-	xcb_client.send_message(
-		&xcb_screen.root_window(),
-		crate::platform::xcb::Event::ClientMessageEvent
-		{
-			window: xcb_window.id,
-			event_type: xcb_client.find_atom_id_by_name("_NET_WM_STATE").unwrap(),
-			data:
-			[
-				1,
-				//NOTE(fpalacios): Se usa _NET_WM_STATE_BELOW para que esté abajo de todas las ventanas pero arriba del escritorio real
-				xcb_client.find_atom_id_by_name("_NET_WM_STATE_BELOW").unwrap(),
-
-				//NOTE(fpalacios): Se usa _NET_WM_STATE_FULLSCREEEN para que use todo el tamaño de la pantalla
-				xcb_client.find_atom_id_by_name("_NET_WM_STATE_FULLSCREEN").unwrap(),
-				0,
-				0,
-			],
-		}
-	);
+	ewmh.set_wm_name(&xcb_window, "Jardincho");
+
+	//NOTE(fpalacios): No se usa _NET_WM_STATE_FULLSCREEN porque el window manager elige el monitor, el tamaño lo pone layout_garden
+	let states: &[crate::platform::ewmh::WindowState] = match mode
+	{
+		crate::config::WindowMode::Desktop =>
+		&[
+			//NOTE(fpalacios): Se usa _NET_WM_STATE_BELOW para que esté abajo de todas las ventanas pero arriba del escritorio real
+			crate::platform::ewmh::WindowState::Below,
+			crate::platform::ewmh::WindowState::Sticky,
+
+			//NOTE(fpalacios): Para que no aparezca en la barra de tareas, el alt-tab ni el paginador de escritorios
+			crate::platform::ewmh::WindowState::SkipTaskbar,
+			crate::platform::ewmh::WindowState::SkipPager,
+		],
+		crate::config::WindowMode::Overlay =>
+		&[
+			crate::platform::ewmh::WindowState::Above,
+			crate::platform::ewmh::WindowState::Sticky,
+			crate::platform::ewmh::WindowState::SkipTaskbar,
+			crate::platform::ewmh::WindowState::SkipPager,
+		],
+		crate::config::WindowMode::Window => &[],
+	};
+
+	let stale_states: Vec<crate::platform::ewmh::WindowState> =
+	[
+		crate::platform::ewmh::WindowState::Below,
+		crate::platform::ewmh::WindowState::Above,
+		crate::platform::ewmh::WindowState::Sticky,
+		crate::platform::ewmh::WindowState::SkipTaskbar,
+		crate::platform::ewmh::WindowState::SkipPager,
+	].iter().filter(|state| !states.contains(state)).cloned().collect();
+
+	ewmh.remove_state(&xcb_window, &stale_states);
+
+	//NOTE(fpalacios): Muchos window managers leen el tipo solo al mapear la ventana, cambiarlo en vivo puede no hacer nada
+	if mode == crate::config::WindowMode::Window
+	{
+		ewmh.set_window_type(&xcb_window, crate::platform::ewmh::WindowType::Normal);
+		return;
+	}
+
+	ewmh.set_window_type(&xcb_window, crate::platform::ewmh::WindowType::Utility);
+	ewmh.set_desktop(&xcb_window, crate::platform::ewmh::Desktop::All);
+	ewmh.add_state(&xcb_window, states);
+}
+
+//NOTE(fpalacios): Eventos propios que se le mandan al loop de glutin desde otros threads para despertarlo
+#[derive(Debug, Clone, Copy)]
+pub enum GardenEvent
+{
+	MonitorsChanged,
+	Exposed,
+	VisibilityChanged(crate::platform::xcb::Visibility),
+	WindowStateChanged,
+	//NOTE(fpalacios): ConfigureNotify de la ventana, la movieron o le cambiaron el tamaño
+	WindowConfigured,
+	ScreenSaverChanged(bool),
+	ShadersChanged,
+	AssetsChanged,
+	ConfigChanged,
+	//NOTE(fpalacios): El pedido en si viene por el canal, GardenEvent tiene que ser Copy
+	ControlRequest,
 }
 
-fn setup_window(event_loop: &glium::glutin::event_loop::EventLoop<()>) -> glium::Display
+fn setup_window(
+	event_loop: &glium::glutin::event_loop::EventLoop<GardenEvent>,
+	mode      : crate::config::WindowMode
+)
+-> Result<glium::Display, glium::backend::glutin::DisplayCreationError>
 {
 	let wb = glium::glutin::window::WindowBuilder::new()
-		.with_transparent(true);
+		.with_transparent(true)
+		.with_decorations(false);
 
-	let cb = glium::glutin::ContextBuilder::new();
+	//NOTE(fpalacios): La escena se mezcla en una textura sRGB con su propio depth buffer (ver PostProcessor). A la ventana
+	//solo llega el pase de salida, que ya escribe codificado, asi que el framebuffer no tiene que ser sRGB
+	let cb = glium::glutin::ContextBuilder::new()
+		.with_srgb(false);
 
-	let display = glium::Display::new(wb, cb, &event_loop).unwrap();
+	let display = glium::Display::new(wb, cb, event_loop)?;
 	{
 		let gl_window = display.gl_window();
 		let window_context = gl_window.window();
@@ -64,50 +112,908 @@ fn setup_window(event_loop: &glium::glutin::event_loop::EventLoop<()>) -> glium:
 		if let Some(xcb_conn) = window_context.xcb_connection()
 		{
 			let xcb_conn = unsafe { xcb::Connection::from_raw_conn(xcb_conn as *mut xcb::ffi::xcb_connection_t) };
-			setup_xcb_window(&xcb_conn, window_context);
+			{
+				let xcb_client = crate::platform::xcb::Client::new(&xcb_conn);
+				setup_xcb_window(
+					&crate::platform::ewmh::Ewmh::new(&xcb_client),
+					window_context.xlib_screen_id().unwrap() as crate::platform::xcb::ScreenID,
+					window_context.xlib_window().unwrap() as crate::platform::xcb::WindowID,
+					mode
+				);
+			}
 
 			//NOTE(fpalacios): Infame hack para que no se llame al destructor de xcb::Connection y le mate la conexion que en realidad no es nuestra :V
 			xcb_conn.into_raw_conn();
 		}
 	}
 
-	return display;
+	return Ok(display);
+}
+
+//NOTE(fpalacios): Ventana ARGB creada a mano con xcb para el backend por software, cuando no hay GLX
+fn create_software_window(
+	ewmh     : &crate::platform::ewmh::Ewmh,
+	screen_id: crate::platform::xcb::ScreenID,
+	mode     : crate::config::WindowMode
+)
+-> Result<(crate::platform::xcb::WindowID, crate::platform::xcb::GraphicsContextID), crate::platform::xcb::Error>
+{
+	let xcb_screen = crate::platform::xcb::Screen::from_id(ewmh.client, screen_id).unwrap();
+	let root_window = xcb_screen.root_window();
+
+	let visual_id = xcb_screen.find_argb_visual().ok_or(crate::platform::xcb::Error{ error_code: 0 })?;
+	let colormap = crate::platform::xcb::ColorMap::create(&root_window, visual_id);
+
+	//NOTE(fpalacios): Con un visual distinto al del padre hay que pasar colormap, fondo y borde si no el server tira BadMatch
+	let xcb_window = root_window.create_child_window(
+		(0, 0, 1, 1),
+		32,
+		Some(&colormap),
+		Some(0),
+		Some(0),
+		Some(visual_id)
+	)?;
+
+	let graphics_context = crate::platform::xcb::GraphicsContext::generate(&xcb_window, 0, 0);
+
+	setup_xcb_window(ewmh, screen_id, xcb_window.id, mode);
+	xcb_window.map();
+
+	return Ok((xcb_window.id, graphics_context.id()));
+}
+
+pub enum Backend
+{
+	Glium
+	{
+		display : glium::Display,
+		//NOTE(fpalacios): En una caja para que Backend no ocupe lo que ocupa el renderer de OpenGL tambien en software
+		renderer: Box<crate::renderer::GliumRenderer>,
+	},
+	Software
+	{
+		renderer           : crate::software_renderer::SoftwareRenderer,
+		window_id          : crate::platform::xcb::WindowID,
+		graphics_context_id: crate::platform::xcb::GraphicsContextID,
+	},
+}
+
+impl Backend
+{
+	pub fn resize(&mut self, width: u32, height: u32)
+	{
+		if let Backend::Software { renderer, .. } = self
+		{
+			renderer.resize(width, height);
+		}
+	}
+
+	pub fn draw(&mut self, xcb_conn: &xcb::Connection, world: &crate::world::World, interpolation: f32)
+	{
+		match self
+		{
+			Backend::Glium { renderer, .. } =>
+			{
+				world.draw(renderer.as_mut(), interpolation);
+			},
+			Backend::Software { renderer, window_id, graphics_context_id } =>
+			{
+				world.draw(renderer, interpolation);
+
+				let xcb_client = crate::platform::xcb::Client::new(xcb_conn);
+				let graphics_context = crate::platform::xcb::GraphicsContext::from_id(&xcb_client, *graphics_context_id);
+				renderer.present(&graphics_context, *window_id);
+			},
+		}
+	}
+
+	pub fn release_assets(&mut self, assets: &mut crate::assets::AssetCache)
+	{
+		match self
+		{
+			Backend::Glium { renderer, .. }    => assets.release_stale(renderer.as_mut()),
+			Backend::Software { renderer, .. } => assets.release_stale(renderer),
+		}
+	}
+
+	pub fn release_models(&mut self, models: &[std::rc::Rc<crate::mesh::Model>])
+	{
+		match self
+		{
+			Backend::Glium { renderer, .. }    => release_model_meshes(renderer.as_mut(), models),
+			Backend::Software { renderer, .. } => release_model_meshes(renderer, models),
+		}
+	}
+
+	pub fn apply_theme(&mut self, theme: &crate::config::Theme)
+	{
+		//NOTE(fpalacios): En software no hay post procesado
+		if let Backend::Glium { renderer, .. } = self
+		{
+			renderer.apply_theme(theme);
+		}
+	}
+
+	//NOTE(fpalacios): En OpenGL se dibuja de nuevo en una textura del mismo tamaño, el framebuffer de la ventana puede no
+	//tener alpha
+	pub fn screenshot(&mut self, world: &crate::world::World, path: &std::path::Path) -> Result<(), String>
+	{
+		let image = match self
+		{
+			Backend::Glium { renderer, .. }    => renderer.capture(|renderer| world.draw(renderer, 0.0))?,
+			Backend::Software { renderer, .. } => renderer.to_image(),
+		};
+
+		return image.save(path).map_err(|err| format!("No se pudo guardar {}: {}", path.display(), err));
+	}
+}
+
+fn release_model_meshes(renderer: &mut impl crate::renderer::Renderer, models: &[std::rc::Rc<crate::mesh::Model>])
+{
+	for part in models.iter().flat_map(|model| model.parts.iter())
+	{
+		renderer.release_mesh(&part.mesh);
+
+		if let Some(texture) = &part.mesh.texture
+		{
+			renderer.release_texture(texture);
+		}
+	}
+}
+
+
+//NOTE(fpalacios): Acomoda la ventana sobre los monitores elegidos y pone una planta en cada uno
+fn layout_garden(
+	xcb_conn         : &xcb::Connection,
+	screen_id        : crate::platform::xcb::ScreenID,
+	window_id        : crate::platform::xcb::WindowID,
+	monitor_selection: &crate::layout::MonitorSelection,
+	world            : &mut crate::world::World
+)
+-> Option<(i16, i16, u16, u16)>
+{
+	let xcb_client = crate::platform::xcb::Client::new(xcb_conn);
+	let xcb_screen = crate::platform::xcb::Screen::from_id(&xcb_client, screen_id).unwrap();
+
+	let whole_screen = crate::layout::whole_screen(xcb_screen.xcb_screen.width_in_pixels(), xcb_screen.xcb_screen.height_in_pixels());
+
+	let monitors = match xcb_screen.monitors()
+	{
+		Ok(monitors) if !monitors.is_empty() => monitors,
+		Ok(_) =>
+		{
+			println!("RandR no informa ningun monitor, se usa la pantalla entera");
+			vec![whole_screen]
+		},
+		Err(err) =>
+		{
+			println!("Error al obtener los monitores {:?}, se usa la pantalla entera", err);
+			vec![whole_screen]
+		}
+	};
+
+	let selected_monitors = monitor_selection.select(&monitors);
+	let bounds = crate::layout::bounds(&selected_monitors)?;
+
+	let xcb_window = crate::platform::xcb::Window
+	{
+		screen: &xcb_screen,
+		id    : window_id,
+	};
+	xcb_window.configure(bounds);
+
+	world.layout(&crate::layout::areas(&selected_monitors, bounds));
+
+	return Some(bounds);
+}
+
+//NOTE(fpalacios): Algunos window managers mueven o agrandan la ventana despues del configure de layout_garden (al mapearla,
+//al cambiar de escritorio). Devuelve true si la tuvo que volver a poner en su lugar
+fn restore_window_geometry(
+	xcb_client: &crate::platform::xcb::Client,
+	screen_id : crate::platform::xcb::ScreenID,
+	window_id : crate::platform::xcb::WindowID,
+	bounds    : (i16, i16, u16, u16)
+)
+-> bool
+{
+	let xcb_screen = crate::platform::xcb::Screen::from_id(xcb_client, screen_id).unwrap();
+	let xcb_window = crate::platform::xcb::Window
+	{
+		screen: &xcb_screen,
+		id    : window_id,
+	};
+
+	let (x, y, width, height) = bounds;
+	let (_, _, current_width, current_height) = xcb_window.geometry();
+
+	if xcb_window.root_position() == Some((x, y)) && (current_width, current_height) == (width, height)
+	{
+		return false;
+	}
+
+	xcb_window.configure(bounds);
+	return true;
+}
+
+//NOTE(fpalacios): Thread con su propia conexion que duerme hasta que pasa algo en el escritorio (cambian los monitores,
+//tapan la ventana, se bloquea la pantalla) y despierta al loop principal
+fn spawn_desktop_watcher(
+	screen_id: crate::platform::xcb::ScreenID,
+	window_id: crate::platform::xcb::WindowID,
+	proxy    : glium::glutin::event_loop::EventLoopProxy<GardenEvent>
+)
+{
+	std::thread::spawn(move ||
+	{
+		let (xcb_conn, _) = match xcb::Connection::connect(None)
+		{
+			Ok(connection) => connection,
+			Err(err) =>
+			{
+				println!("No se pueden escuchar los eventos del escritorio {:?}", err);
+				return;
+			}
+		};
+
+		let xcb_client = crate::platform::xcb::Client::new(&xcb_conn);
+		let xcb_screen = crate::platform::xcb::Screen::from_id(&xcb_client, screen_id).unwrap();
+		let xcb_window = crate::platform::xcb::Window
+		{
+			screen: &xcb_screen,
+			id    : window_id,
+		};
+
+		if let Err(err) = xcb_screen.select_monitor_changes()
+		{
+			println!("No se pueden escuchar los cambios de monitores {:?}", err);
+		}
+
+		if let Err(err) = xcb_screen.select_screen_saver_changes()
+		{
+			println!("No se puede escuchar el protector de pantalla {:?}", err);
+		}
+
+		if let Err(err) = xcb_window.select_events(
+			xcb::EVENT_MASK_VISIBILITY_CHANGE |
+			xcb::EVENT_MASK_EXPOSURE          |
+			xcb::EVENT_MASK_PROPERTY_CHANGE   |
+			xcb::EVENT_MASK_STRUCTURE_NOTIFY
+		)
+		{
+			println!("No se puede escuchar la visibilidad de la ventana {:?}", err);
+		}
+
+		let net_wm_state = xcb_client.find_atom_id_by_name("_NET_WM_STATE").unwrap();
+
+		while let Some(xcb_event) = xcb_client.wait_for_event()
+		{
+			let garden_event = match xcb_event
+			{
+				crate::platform::xcb::Event::RandrEvent(_) => GardenEvent::MonitorsChanged,
+				crate::platform::xcb::Event::ExposedEvent(expose) if expose.count == 0 => GardenEvent::Exposed,
+				crate::platform::xcb::Event::VisibilityEvent(visibility) => GardenEvent::VisibilityChanged(visibility.visibility),
+				crate::platform::xcb::Event::PropertyEvent(property) if property.atom == net_wm_state => GardenEvent::WindowStateChanged,
+				crate::platform::xcb::Event::ConfigureEvent(configure) if configure.window == window_id => GardenEvent::WindowConfigured,
+				crate::platform::xcb::Event::ScreenSaverEvent(screen_saver) => GardenEvent::ScreenSaverChanged(screen_saver.active),
+				_ => continue,
+			};
+
+			if proxy.send_event(garden_event).is_err()
+			{
+				return;
+			}
+		}
+	});
+}
+
+//NOTE(fpalacios): Manda event cada vez que cambia algo en los directorios o sus subdirectorios. Junta los cambios que llegan
+//seguidos para no mandar uno por cada archivo que toca el editor al guardar
+fn spawn_directory_watcher(
+	directories: Vec<std::path::PathBuf>,
+	event      : GardenEvent,
+	proxy      : glium::glutin::event_loop::EventLoopProxy<GardenEvent>
+)
+{
+	std::thread::spawn(move ||
+	{
+		let inotify = match crate::platform::inotify::Inotify::new()
+		{
+			Ok(inotify) => inotify,
+			Err(err) =>
+			{
+				println!("No se pueden escuchar los cambios en {:?} {:?}", directories, err);
+				return;
+			}
+		};
+
+		let mask = libc::IN_CLOSE_WRITE | libc::IN_MOVED_TO | libc::IN_CREATE | libc::IN_DELETE;
+		let mut watched_directories = std::collections::HashMap::new();
+
+		for watched_directory in directories.iter().flat_map(|directory| crate::platform::inotify::directories(directory))
+		{
+			match inotify.add_watch(&watched_directory, mask)
+			{
+				Ok(watch) => { watched_directories.insert(watch, watched_directory); },
+				Err(err)  => println!("No se puede mirar {} {:?}", watched_directory.display(), err),
+			}
+		}
+
+		loop
+		{
+			let mut events = match inotify.read_events()
+			{
+				Ok(events) => events,
+				Err(err) =>
+				{
+					println!("Error al leer los cambios en {:?} {:?}", directories, err);
+					return;
+				}
+			};
+
+			while let Ok(ready) = crate::platform::poll::wait_readable(
+				&[std::os::unix::io::AsRawFd::as_raw_fd(&inotify)],
+				Some(std::time::Duration::from_millis(100))
+			)
+			{
+				if ready.is_empty()
+				{
+					break;
+				}
+
+				match inotify.read_events()
+				{
+					Ok(more_events) => events.extend(more_events),
+					Err(_)          => break,
+				}
+			}
+
+			//NOTE(fpalacios): Los directorios nuevos tambien hay que mirarlos
+			for event in &events
+			{
+				if event.mask & libc::IN_ISDIR != 0 && event.mask & (libc::IN_CREATE | libc::IN_MOVED_TO) != 0
+				{
+					if let (Some(parent), Some(name)) = (watched_directories.get(&event.watch).cloned(), &event.name)
+					{
+						let new_directory = parent.join(name);
+						if let Ok(watch) = inotify.add_watch(&new_directory, mask)
+						{
+							watched_directories.insert(watch, new_directory);
+						}
+					}
+				}
+			}
+
+			if proxy.send_event(event).is_err()
+			{
+				return;
+			}
+		}
+	});
+}
+
+//NOTE(fpalacios): Un thread para todas las conexiones, ver ipc::serve
+fn spawn_control_server(
+	listener: std::os::unix::net::UnixListener,
+	sender  : std::sync::mpsc::Sender<crate::ipc::ControlRequest>,
+	proxy   : glium::glutin::event_loop::EventLoopProxy<GardenEvent>
+)
+{
+	std::thread::spawn(move || crate::ipc::serve(listener, sender, || proxy.send_event(GardenEvent::ControlRequest).is_ok()));
+}
+
+//NOTE(fpalacios): Todos menos quit, que tiene que cortar el loop. Devuelve los campos de la respuesta
+fn handle_control_command(
+	command: crate::ipc::ControlCommand,
+	world  : &mut crate::world::World,
+	backend: &mut Backend,
+	config : &crate::config::Config
+)
+-> Result<Vec<(String, crate::json::Json)>, String>
+{
+	return match command
+	{
+		crate::ipc::ControlCommand::Water(plant) =>
+		{
+			let watered = world.water(plant.as_deref());
+			if watered == 0
+			{
+				return Err(match plant
+				{
+					Some(plant) => format!("No hay ninguna planta {} sembrada", plant),
+					None        => String::from("El jardin esta vacio"),
+				});
+			}
+
+			Ok(vec![(String::from("watered"), crate::json::Json::Number(watered as f64))])
+		},
+		crate::ipc::ControlCommand::AddPlant(plant) =>
+		{
+			world.add_plant(&plant)?;
+			Ok(vec![(String::from("plants"), crate::json::Json::Number(world.garden().len() as f64))])
+		},
+		crate::ipc::ControlCommand::RemovePlant(plant) =>
+		{
+			world.remove_plant(plant.as_deref())?;
+			Ok(vec![(String::from("plants"), crate::json::Json::Number(world.garden().len() as f64))])
+		},
+		crate::ipc::ControlCommand::SetWeather(weather) =>
+		{
+			world.set_weather(weather);
+			Ok(vec![(String::from("weather"), crate::json::Json::String(String::from(weather.name())))])
+		},
+		crate::ipc::ControlCommand::Pause(paused) =>
+		{
+			let paused = paused.unwrap_or(!world.is_paused());
+			world.set_paused(paused);
+			Ok(vec![(String::from("paused"), crate::json::Json::Bool(paused))])
+		},
+		crate::ipc::ControlCommand::Screenshot(path) =>
+		{
+			backend.screenshot(world, &path)?;
+			Ok(vec![(String::from("path"), crate::json::Json::String(path.to_string_lossy().into_owned()))])
+		},
+		crate::ipc::ControlCommand::Status =>
+		{
+			let plants = world.garden().iter()
+				.map(|plant| crate::json::Json::Object(vec![
+					(String::from("name"),  crate::json::Json::String(plant.name.clone())),
+					(String::from("water"), crate::json::Json::Number(plant.water as f64)),
+				]))
+				.collect();
+
+			let species = world.species_names().into_iter().map(|name| crate::json::Json::String(String::from(name))).collect();
+
+			Ok(vec![
+				(String::from("paused"),  crate::json::Json::Bool(world.is_paused())),
+				(String::from("weather"), crate::json::Json::String(String::from(world.weather().name()))),
+				(String::from("fps"),     crate::json::Json::Number(config.fps as f64)),
+				(String::from("plants"),  crate::json::Json::Array(plants)),
+				(String::from("species"), crate::json::Json::Array(species)),
+			])
+		},
+		crate::ipc::ControlCommand::Quit => Ok(Vec::new()),
+	};
+}
+
+fn save_garden(world: &crate::world::World)
+{
+	if let Err(err) = world.state().save()
+	{
+		println!("{}", err);
+	}
+}
+
+//NOTE(fpalacios): El window manager marca con _NET_WM_STATE_HIDDEN las ventanas minimizadas o en otro escritorio
+fn is_window_hidden(
+	ewmh     : &crate::platform::ewmh::Ewmh,
+	screen_id: crate::platform::xcb::ScreenID,
+	window_id: crate::platform::xcb::WindowID
+)
+-> bool
+{
+	let xcb_screen = crate::platform::xcb::Screen::from_id(ewmh.client, screen_id).unwrap();
+	let xcb_window = crate::platform::xcb::Window
+	{
+		screen: &xcb_screen,
+		id    : window_id,
+	};
+
+	return ewmh.states(&xcb_window).contains(&crate::platform::ewmh::WindowState::Hidden);
 }
 
 fn main()
 {
-	let event_loop = glium::glutin::event_loop::EventLoop::new();
-	let display = setup_window(&event_loop);
+	let args: Vec<String> = std::env::args().skip(1).collect();
+
+	let command = match crate::cli::parse(&args)
+	{
+		Ok(command) => command,
+		Err(err) =>
+		{
+			println!("{}\n\n{}", err, crate::cli::USAGE);
+			std::process::exit(2);
+		}
+	};
+
+	let result = match command
+	{
+		crate::cli::Command::Run(options)        => run(options),
+		crate::cli::Command::Render(options)     => crate::cli::render(&options),
+		crate::cli::Command::ListMonitors        => crate::cli::list_monitors(),
+		crate::cli::Command::Validate { config } => crate::cli::validate(config.as_deref()),
+		crate::cli::Command::Control(command)    => crate::cli::control(&command),
+		crate::cli::Command::Reset               => crate::cli::reset(),
+		crate::cli::Command::Help                =>
+		{
+			println!("{}", crate::cli::USAGE);
+			Ok(())
+		},
+	};
+
+	if let Err(err) = result
+	{
+		println!("{}", err);
+		std::process::exit(1);
+	}
+}
+
+fn run(options: crate::cli::RunOptions) -> Result<(), String>
+{
+	let (config_path, mut config) = crate::cli::load_config(options.config.as_deref())?;
+	options.apply(&mut config);
+
+	let event_loop = glium::glutin::event_loop::EventLoop::<GardenEvent>::with_user_event();
 
-	let mut world = crate::world::World::new(&display);
+	let mut assets = crate::assets::AssetCache::new(crate::assets::AssetPaths::from_env()).map_err(|err| err.to_string())?;
 
-	let mut last_frame_time = std::time::Instant::now();
-	let mut frame_timer = std::time::Duration::from_nanos(0);
-	let frame_delay = std::time::Duration::from_millis(1000 / 60);
+	let mut world = crate::world::World::new(&mut assets, &config).map_err(|err| format!("Error al cargar las plantas\n{}", err))?;
+
+	//NOTE(fpalacios): Si el estado guardado esta roto se arranca con el jardin de la configuracion
+	match crate::state::GardenState::load()
+	{
+		Ok(Some(state)) => world.restore(&state),
+		Ok(None)        => {},
+		Err(err)        => println!("{}\nSe arranca con el jardin de la configuracion", err),
+	}
+
+	let memory = assets.memory();
+	println!("Assets cargados: {} texturas ({:.1} MB)", memory.textures, memory.bytes as f64 / (1024.0 * 1024.0));
+
+	//NOTE(fpalacios): Conexion propia para acomodar la ventana, la de glutin es de winit
+	let (xcb_conn, default_screen_id) = xcb::Connection::connect(None).unwrap();
+
+	//NOTE(fpalacios): event_loop.run no vuelve nunca, asi que la conexion vive lo mismo que el proceso. Se suelta para que
+	//el loop se pueda llevar un solo Ewmh y no pida los atoms de nuevo con cada _NET_WM_STATE
+	let xcb_conn: &'static xcb::Connection = Box::leak(Box::new(xcb_conn));
+	let xcb_client: &'static crate::platform::xcb::Client = Box::leak(Box::new(crate::platform::xcb::Client::new(xcb_conn)));
+	let ewmh = crate::platform::ewmh::Ewmh::new(xcb_client);
+
+	let force_software = std::env::var("JARDINCHO_SOFTWARE").map(|value| value == "1").unwrap_or(false);
+
+	let display = if force_software
+	{
+		None
+	}
+	else
+	{
+		match setup_window(&event_loop, config.window.mode)
+		{
+			Ok(display) => Some(display),
+			Err(err) =>
+			{
+				println!("No se pudo crear el contexto de OpenGL, se usa el backend por software {:?}", err);
+				None
+			}
+		}
+	};
+
+	let shader_source = crate::shaders::ShaderSource::from_env();
+
+	let glium_backend = display.and_then(|display|
+		match crate::renderer::GliumRenderer::new(&display, shader_source.clone())
+		{
+			Ok(renderer) =>
+			{
+				Some((display, renderer))
+			},
+			Err(err) =>
+			{
+				println!("No se pudieron compilar los shaders, se usa el backend por software\n{}", err);
+				None
+			}
+		}
+	);
+
+	let (mut backend, window_id, screen_id) = match glium_backend
+	{
+		Some((display, renderer)) =>
+		{
+			let (window_id, screen_id) =
+			{
+				let gl_window = display.gl_window();
+				(
+					gl_window.window().xlib_window().unwrap() as crate::platform::xcb::WindowID,
+					gl_window.window().xlib_screen_id().unwrap() as crate::platform::xcb::ScreenID,
+				)
+			};
+
+			(Backend::Glium { display, renderer: Box::new(renderer) }, window_id, screen_id)
+		},
+		None =>
+		{
+			let (window_id, graphics_context_id) = create_software_window(&ewmh, default_screen_id, config.window.mode).unwrap();
+			let backend = Backend::Software
+			{
+				renderer: crate::software_renderer::SoftwareRenderer::new(1, 1),
+				window_id,
+				graphics_context_id,
+			};
+			(backend, window_id, default_screen_id)
+		},
+	};
+
+	backend.apply_theme(&config.theme);
+
+	spawn_desktop_watcher(screen_id, window_id, event_loop.create_proxy());
+
+	if let crate::shaders::ShaderSource::Directory(directory) = &shader_source
+	{
+		spawn_directory_watcher(vec![directory.clone()], GardenEvent::ShadersChanged, event_loop.create_proxy());
+	}
+
+	//NOTE(fpalacios): Los PNG se recargan siempre, asi se puede ver el dibujo nuevo en el escritorio apenas se guarda
+	let asset_directories = assets.paths().existing_directories();
+	if !asset_directories.is_empty()
+	{
+		spawn_directory_watcher(asset_directories, GardenEvent::AssetsChanged, event_loop.create_proxy());
+	}
+
+	//NOTE(fpalacios): Se mira el directorio y no el archivo porque los editores lo reemplazan al guardar. Si el directorio
+	//no existe hay que reiniciar despues de crearlo
+	if let Some(config_directory) = config_path.as_deref().and_then(std::path::Path::parent).filter(|directory| directory.is_dir())
+	{
+		spawn_directory_watcher(vec![config_directory.to_path_buf()], GardenEvent::ConfigChanged, event_loop.create_proxy());
+	}
+
+	let mut window_bounds = layout_garden(xcb_conn, screen_id, window_id, &config.window.monitor, &mut world);
+	if let Some((_, _, width, height)) = window_bounds
+	{
+		backend.resize(width as u32, height as u32);
+	}
+
+	//NOTE(fpalacios): Sin socket el jardin anda igual, solo no se puede manejar con "jardincho ctl"
+	let (control_sender, control_receiver) = std::sync::mpsc::channel();
+	let socket_path = match crate::ipc::bind()
+	{
+		Ok((listener, socket_path)) =>
+		{
+			spawn_control_server(listener, control_sender, event_loop.create_proxy());
+			Some(socket_path)
+		},
+		Err(err) =>
+		{
+			println!("{}", err);
+			None
+		}
+	};
+
+	let mut frame_scheduler = crate::frame_scheduler::FrameScheduler::new(config.fps);
+	let mut game_loop = crate::game_loop::GameLoop::new(
+		std::time::Duration::from_secs(1) / 60,
+		std::time::Duration::from_millis(250)
+	);
+	let mut needs_redraw = true;
+	let mut desktop_state = crate::visibility::DesktopState::new();
+	let mut last_geometry_restore: Option<std::time::Instant> = None;
 
 	event_loop.run( move |event, _, control_flow|
 	{
-		*control_flow = match event
+		match event
 		{
 			glium::glutin::event::Event::WindowEvent
 			{
 				event: glium::glutin::event::WindowEvent::CloseRequested,
 				..
-			} => glium::glutin::event_loop::ControlFlow::Exit,
-			_ => glium::glutin::event_loop::ControlFlow::Poll,
-		};
+			} =>
+			{
+				*control_flow = glium::glutin::event_loop::ControlFlow::Exit;
+				return;
+			},
+			glium::glutin::event::Event::UserEvent(GardenEvent::MonitorsChanged) =>
+			{
+				window_bounds = layout_garden(xcb_conn, screen_id, window_id, &config.window.monitor, &mut world);
+				if let Some((_, _, width, height)) = window_bounds
+				{
+					backend.resize(width as u32, height as u32);
+				}
+				needs_redraw = true;
+			},
+			glium::glutin::event::Event::UserEvent(GardenEvent::Exposed) |
+			glium::glutin::event::Event::RedrawRequested(_) =>
+			{
+				needs_redraw = true;
+			},
+			glium::glutin::event::Event::UserEvent(GardenEvent::VisibilityChanged(visibility)) =>
+			{
+				desktop_state.visibility = visibility;
+				needs_redraw = true;
+			},
+			glium::glutin::event::Event::UserEvent(GardenEvent::WindowStateChanged) =>
+			{
+				desktop_state.hidden = is_window_hidden(&ewmh, screen_id, window_id);
+				needs_redraw = true;
+			},
+			glium::glutin::event::Event::UserEvent(GardenEvent::WindowConfigured) =>
+			{
+				//NOTE(fpalacios): Como mucho una vez por segundo, asi con un window manager que insiste en otra geometria no se
+				//entra en un loop
+				let now = std::time::Instant::now();
+				let can_restore = last_geometry_restore.is_none_or(|last| now.duration_since(last) >= std::time::Duration::from_secs(1));
+
+				if let Some(bounds) = window_bounds.filter(|_| can_restore)
+				{
+					if restore_window_geometry(xcb_client, screen_id, window_id, bounds)
+					{
+						last_geometry_restore = Some(now);
+					}
+				}
+			},
+			glium::glutin::event::Event::UserEvent(GardenEvent::ScreenSaverChanged(active)) =>
+			{
+				desktop_state.screen_saver_active = active;
+				needs_redraw = true;
+			},
+			glium::glutin::event::Event::UserEvent(GardenEvent::ShadersChanged) =>
+			{
+				if let Backend::Glium { renderer, .. } = &mut backend
+				{
+					renderer.reload_shaders();
+				}
+				needs_redraw = true;
+			},
+			glium::glutin::event::Event::UserEvent(GardenEvent::AssetsChanged) =>
+			{
+				let reloaded = assets.reload_changed();
+				let evicted  = assets.evict_unused();
+				backend.release_assets(&mut assets);
+
+				if reloaded > 0 || evicted > 0
+				{
+					let memory = assets.memory();
+					println!(
+						"Assets: {} recargadas, {} desalojadas, quedan {} texturas ({:.1} MB)",
+						reloaded,
+						evicted,
+						memory.textures,
+						memory.bytes as f64 / (1024.0 * 1024.0)
+					);
+				}
+				needs_redraw = true;
+			},
+			glium::glutin::event::Event::UserEvent(GardenEvent::ConfigChanged) =>
+			{
+				let path = match &config_path
+				{
+					Some(path) => path,
+					None       => return,
+				};
+
+				//NOTE(fpalacios): Si el archivo nuevo tiene errores se avisa y se sigue con la configuracion de antes
+				let mut new_config = match crate::config::Config::load(path)
+				{
+					Ok(new_config) => new_config,
+					Err(err) =>
+					{
+						println!("{}\nSe sigue usando la configuracion anterior", err);
+						return;
+					}
+				};
+				options.apply(&mut new_config);
+
+				match world.configure(&mut assets, &new_config)
+				{
+					Ok(unused_models) => backend.release_models(&unused_models),
+					Err(err) =>
+					{
+						println!("Error al cargar las plantas\n{}\nSe sigue usando la configuracion anterior", err);
+						return;
+					}
+				}
 
-		let current_frame_time = std::time::Instant::now();
-		frame_timer += current_frame_time - last_frame_time;
+				assets.evict_unused();
+				backend.release_assets(&mut assets);
 
-		if frame_timer >= frame_delay
+				if new_config.window.mode != config.window.mode
+				{
+					setup_xcb_window(&ewmh, screen_id, window_id, new_config.window.mode);
+				}
+
+				if new_config.window.monitor != config.window.monitor
+				{
+					window_bounds = layout_garden(xcb_conn, screen_id, window_id, &new_config.window.monitor, &mut world);
+					if let Some((_, _, width, height)) = window_bounds
+					{
+						backend.resize(width as u32, height as u32);
+					}
+				}
+
+				if new_config.fps != config.fps
+				{
+					frame_scheduler = crate::frame_scheduler::FrameScheduler::new(new_config.fps);
+				}
+
+				//NOTE(fpalacios): Siempre, porque puede haber cambiado el JSON del post procesado y no la configuracion
+				backend.apply_theme(&new_config.theme);
+
+				config = new_config;
+				println!("Configuracion recargada");
+				needs_redraw = true;
+			},
+			glium::glutin::event::Event::UserEvent(GardenEvent::ControlRequest) =>
+			{
+				while let Ok(mut request) = control_receiver.try_recv()
+				{
+					//NOTE(fpalacios): Exit no se puede pisar despues, el guardado y el borrado del socket van en LoopDestroyed
+					if request.command == crate::ipc::ControlCommand::Quit
+					{
+						request.reply(&crate::ipc::response(Ok(Vec::new())));
+						*control_flow = glium::glutin::event_loop::ControlFlow::Exit;
+						return;
+					}
+
+					let result = handle_control_command(request.command.clone(), &mut world, &mut backend, &config);
+
+					if result.is_ok() && request.command != crate::ipc::ControlCommand::Status
+					{
+						save_garden(&world);
+					}
+
+					request.reply(&crate::ipc::response(result));
+				}
+				needs_redraw = true;
+			},
+			glium::glutin::event::Event::LoopDestroyed =>
+			{
+				save_garden(&world);
+
+				if let Some(socket_path) = &socket_path
+				{
+					let _ = std::fs::remove_file(socket_path);
+				}
+				return;
+			},
+			glium::glutin::event::Event::MainEventsCleared => {},
+			_ => return,
+		};
+
+		//NOTE(fpalacios): Se actualiza y dibuja una sola vez por vuelta del loop, despues de procesar todos los eventos
+		if let glium::glutin::event::Event::MainEventsCleared = event
 		{
-			world.update(&frame_timer);
-			world.draw(&display);
+			let now = std::time::Instant::now();
 
-			frame_timer = std::time::Duration::from_nanos(0);
-		}
+			{
+				let xcb_screen = crate::platform::xcb::Screen::from_id(xcb_client, screen_id).unwrap();
+				needs_redraw |= desktop_state.check(now, &xcb_screen);
+			}
+
+			//NOTE(fpalacios): La simulacion avanza siempre con el reloj, aunque no se dibuje
+			let step = game_loop.advance(now);
+			if !step.skipped.is_zero()
+			{
+				needs_redraw |= world.catch_up(&step.skipped);
+			}
+
+			for _ in 0 .. step.ticks
+			{
+				needs_redraw |= world.update(&game_loop.tick);
+			}
 
-		last_frame_time = current_frame_time;
+			let render_mode = desktop_state.render_mode();
+
+			//NOTE(fpalacios): Si esta suspendido needs_redraw queda pendiente para cuando se vuelva a ver
+			if render_mode != crate::visibility::RenderMode::Suspended && (needs_redraw || world.is_animating())
+			{
+				backend.draw(xcb_conn, &world, step.interpolation);
+				frame_scheduler.frame_drawn(now);
+				needs_redraw = false;
+			}
+
+			let next_frame = frame_scheduler.next_deadline(now, &world, &game_loop, render_mode);
+
+			//NOTE(fpalacios): Suspendido no hace falta revisar la bateria, lo despierta el evento que lo haga volver a verse
+			let deadline = match (next_frame, render_mode)
+			{
+				(_, crate::visibility::RenderMode::Suspended) => None,
+				(Some(next_frame), _) => Some(std::cmp::min(next_frame, desktop_state.next_check())),
+				(None, _)             => Some(desktop_state.next_check()),
+			};
+
+			*control_flow = match deadline
+			{
+				Some(deadline) => glium::glutin::event_loop::ControlFlow::WaitUntil(deadline),
+				None           => glium::glutin::event_loop::ControlFlow::Wait,
+			};
+		}
 	});
-}
\ No newline at end of file
+}
@@ -0,0 +1,720 @@
+//NOTE(fpalacios): Malla en memoria, igual que las imagenes de los sprites cada backend la sube o la usa como quiera.
+//Cada malla tiene un solo material
+pub struct Mesh
+{
+	pub vertices     : Vec<crate::renderer::Vertex3D>,
+	pub indices      : Vec<u32>,
+	pub texture      : Option<std::rc::Rc<image::RgbaImage>>,
+	//NOTE(fpalacios): Hasta 4 huesos por vertice con su peso, vacios si la malla no tiene esqueleto
+	pub joints       : Vec<[u16; 4]>,
+	pub weights      : Vec<[f32; 4]>,
+	pub morph_targets: Vec<MorphTarget>,
+}
+
+//NOTE(fpalacios): Desplazamientos por vertice que se suman a la malla segun el peso del target
+pub struct MorphTarget
+{
+	pub positions: Vec<[f32; 3]>,
+	pub normals  : Vec<[f32; 3]>,
+}
+
+#[derive(Debug)]
+pub enum MeshError
+{
+	Io(std::io::Error),
+	UnsupportedFormat(String),
+	Parse
+	{
+		line   : usize,
+		message: String,
+	},
+	Gltf(crate::gltf::GltfError),
+	Empty,
+}
+
+//NOTE(fpalacios): Color que se usa cuando el modelo no trae ninguno
+const DEFAULT_COLOR: [f32; 3] = [0.55, 0.7, 0.4];
+
+impl Mesh
+{
+	pub fn new(vertices: Vec<crate::renderer::Vertex3D>, indices: Vec<u32>) -> Mesh
+	{
+		return Mesh
+		{
+			vertices,
+			indices,
+			texture      : None,
+			joints       : Vec::new(),
+			weights      : Vec::new(),
+			morph_targets: Vec::new(),
+		};
+	}
+
+	//NOTE(fpalacios): Las mallas con esqueleto o morph targets cambian los vertices en cada frame
+	pub fn is_deformable(&self) -> bool
+	{
+		return !self.joints.is_empty() || !self.morph_targets.is_empty();
+	}
+
+	//NOTE(fpalacios): Aplica los morph targets y despues el esqueleto (skinning en la CPU). joint_matrices ya tiene que
+	//tener aplicada la inversa de la bind pose
+	pub fn deform(&self, morph_weights: &[f32], joint_matrices: Option<&[nalgebra::Matrix4<f32>]>) -> Vec<crate::renderer::Vertex3D>
+	{
+		let mut vertices = self.vertices.clone();
+
+		for (morph_target, weight) in self.morph_targets.iter().zip(morph_weights)
+		{
+			if *weight == 0.0
+			{
+				continue;
+			}
+
+			for (vertex, delta) in vertices.iter_mut().zip(&morph_target.positions)
+			{
+				for (position, delta) in vertex.position.iter_mut().zip(delta)
+				{
+					*position += delta * weight;
+				}
+			}
+
+			for (vertex, delta) in vertices.iter_mut().zip(&morph_target.normals)
+			{
+				for (normal, delta) in vertex.normal.iter_mut().zip(delta)
+				{
+					*normal += delta * weight;
+				}
+			}
+		}
+
+		if let Some(joint_matrices) = joint_matrices
+		{
+			for (index, vertex) in vertices.iter_mut().enumerate()
+			{
+				let (joints, weights) = match (self.joints.get(index), self.weights.get(index))
+				{
+					(Some(joints), Some(weights)) => (joints, weights),
+					_                             => continue,
+				};
+
+				let mut skin_matrix = nalgebra::Matrix4::zeros();
+				for influence in 0 .. 4
+				{
+					if let Some(joint_matrix) = joint_matrices.get(joints[influence] as usize)
+					{
+						skin_matrix += joint_matrix * weights[influence];
+					}
+				}
+
+				let position = skin_matrix.transform_point(&nalgebra::Point3::from(vertex.position));
+				let normal   = skin_matrix.transform_vector(&nalgebra::Vector3::from(vertex.normal));
+
+				vertex.position = [position.x, position.y, position.z];
+				vertex.normal   = normal.try_normalize(1.0e-8).unwrap_or_else(nalgebra::Vector3::y).into();
+			}
+		}
+
+		return vertices;
+	}
+
+	//NOTE(fpalacios): Normales suaves promediando las de las caras que comparten cada vertice
+	pub fn compute_normals(&mut self)
+	{
+		for vertex in &mut self.vertices
+		{
+			vertex.normal = [0.0, 0.0, 0.0];
+		}
+
+		for triangle in self.indices.chunks_exact(3)
+		{
+			let a = nalgebra::Vector3::from(self.vertices[triangle[0] as usize].position);
+			let b = nalgebra::Vector3::from(self.vertices[triangle[1] as usize].position);
+			let c = nalgebra::Vector3::from(self.vertices[triangle[2] as usize].position);
+
+			//NOTE(fpalacios): Sin normalizar, asi las caras mas grandes pesan mas
+			let face_normal = (b - a).cross(&(c - a));
+
+			for index in triangle
+			{
+				let normal = &mut self.vertices[*index as usize].normal;
+				normal[0] += face_normal.x;
+				normal[1] += face_normal.y;
+				normal[2] += face_normal.z;
+			}
+		}
+
+		for vertex in &mut self.vertices
+		{
+			let normal = nalgebra::Vector3::from(vertex.normal);
+			vertex.normal = normal.try_normalize(1.0e-8).unwrap_or_else(nalgebra::Vector3::y).into();
+		}
+	}
+}
+
+//NOTE(fpalacios): Elige el formato por la extension
+pub fn load(path: &std::path::Path) -> Result<Model, MeshError>
+{
+	let extension = path.extension().and_then(|extension| extension.to_str()).unwrap_or("").to_lowercase();
+
+	let model = match extension.as_str()
+	{
+		"obj"          => Model::from_mesh(load_obj(&std::fs::read_to_string(path).map_err(MeshError::Io)?)?),
+		"gltf" | "glb" => crate::gltf::load(path).map_err(MeshError::Gltf)?,
+		_              => return Err(MeshError::UnsupportedFormat(extension)),
+	};
+
+	if model.parts.iter().all(|part| part.mesh.indices.is_empty())
+	{
+		return Err(MeshError::Empty);
+	}
+
+	return Ok(model);
+}
+
+//NOTE(fpalacios): Wavefront OBJ. Se usan v, vt, vn y f (triangulando los poligonos en abanico), el resto (materiales,
+//grupos) se ignora
+pub fn load_obj(source: &str) -> Result<Mesh, MeshError>
+{
+	let mut positions     : Vec<[f32; 3]> = Vec::new();
+	let mut texture_coords: Vec<[f32; 2]> = Vec::new();
+	let mut normals       : Vec<[f32; 3]> = Vec::new();
+
+	let mut mesh = Mesh::new(Vec::new(), Vec::new());
+
+	//NOTE(fpalacios): La misma combinacion de posicion, coordenada de textura y normal se reusa para no duplicar vertices
+	let mut vertex_indices: std::collections::HashMap<(usize, Option<usize>, Option<usize>), u32> = std::collections::HashMap::new();
+	let mut has_all_normals = true;
+
+	for (line_index, line) in source.lines().enumerate()
+	{
+		let line_number = line_index + 1;
+		let parse_error = |message: &str| MeshError::Parse { line: line_number, message: String::from(message) };
+
+		let mut tokens = line.split_whitespace();
+
+		match tokens.next()
+		{
+			Some("vt") =>
+			{
+				let values: Vec<f32> = tokens.take(2).map(|token| token.parse::<f32>()).collect::<Result<_, _>>()
+					.map_err(|_| parse_error("Numero invalido"))?;
+
+				//NOTE(fpalacios): La v es opcional y vale 0
+				texture_coords.push([values.first().cloned().unwrap_or(0.0), values.get(1).cloned().unwrap_or(0.0)]);
+			},
+			Some(kind @ "v") | Some(kind @ "vn") =>
+			{
+				let values: Vec<f32> = tokens.take(3).map(|token| token.parse::<f32>()).collect::<Result<_, _>>()
+					.map_err(|_| parse_error("Numero invalido"))?;
+
+				if values.len() != 3
+				{
+					return Err(parse_error("Se esperaban 3 coordenadas"));
+				}
+
+				if kind == "vn"
+				{
+					normals.push([values[0], values[1], values[2]]);
+				}
+				else
+				{
+					positions.push([values[0], values[1], values[2]]);
+				}
+			},
+			Some("f") =>
+			{
+				let mut face = Vec::new();
+
+				for token in tokens
+				{
+					let mut parts = token.split('/');
+
+					let position = resolve_obj_index(parts.next(), positions.len()).ok_or_else(|| parse_error("Indice de vertice invalido"))?;
+					let texture_coord = match parts.next()
+					{
+						Some(texture_coord) if !texture_coord.is_empty() => Some(resolve_obj_index(Some(texture_coord), texture_coords.len()).ok_or_else(|| parse_error("Indice de textura invalido"))?),
+						_                                                => None,
+					};
+					let normal = match parts.next()
+					{
+						Some(normal) if !normal.is_empty() => Some(resolve_obj_index(Some(normal), normals.len()).ok_or_else(|| parse_error("Indice de normal invalido"))?),
+						_                                  => None,
+					};
+
+					has_all_normals &= normal.is_some();
+
+					let vertex_index = match vertex_indices.get(&(position, texture_coord, normal))
+					{
+						Some(vertex_index) => *vertex_index,
+						None =>
+						{
+							let vertex_index = mesh.vertices.len() as u32;
+							mesh.vertices.push(
+								crate::renderer::Vertex3D
+								{
+									position      : positions[position],
+									normal        : normal.map(|normal| normals[normal]).unwrap_or([0.0, 0.0, 0.0]),
+									texture_coords: texture_coord.map(|texture_coord| texture_coords[texture_coord]).unwrap_or([0.0, 0.0]),
+									color         : DEFAULT_COLOR,
+								}
+							);
+							vertex_indices.insert((position, texture_coord, normal), vertex_index);
+							vertex_index
+						}
+					};
+
+					face.push(vertex_index);
+				}
+
+				if face.len() < 3
+				{
+					return Err(parse_error("Una cara necesita al menos 3 vertices"));
+				}
+
+				for index in 1 .. face.len() - 1
+				{
+					mesh.indices.extend_from_slice(&[face[0], face[index], face[index + 1]]);
+				}
+			},
+			_ => {},
+		}
+	}
+
+	if !has_all_normals
+	{
+		mesh.compute_normals();
+	}
+
+	return Ok(mesh);
+}
+
+//NOTE(fpalacios): Los indices de OBJ empiezan en 1 y los negativos cuentan desde el ultimo
+fn resolve_obj_index(token: Option<&str>, count: usize) -> Option<usize>
+{
+	let index: i64 = token?.parse().ok()?;
+
+	let resolved = if index < 0 { count as i64 + index } else { index - 1 };
+
+	if resolved < 0 || resolved >= count as i64
+	{
+		return None;
+	}
+
+	return Some(resolved as usize);
+}
+
+pub struct Node
+{
+	pub parent     : Option<usize>,
+	pub translation: nalgebra::Vector3<f32>,
+	pub rotation   : nalgebra::UnitQuaternion<f32>,
+	pub scale      : nalgebra::Vector3<f32>,
+	//NOTE(fpalacios): Si el nodo trae la matriz armada se usa tal cual y no se puede animar
+	pub matrix     : Option<nalgebra::Matrix4<f32>>,
+	//NOTE(fpalacios): Pesos de los morph targets de la malla del nodo
+	pub weights    : Vec<f32>,
+}
+
+impl Node
+{
+	pub fn identity(parent: Option<usize>) -> Node
+	{
+		return Node
+		{
+			parent,
+			translation: nalgebra::Vector3::zeros(),
+			rotation   : nalgebra::UnitQuaternion::identity(),
+			scale      : nalgebra::Vector3::new(1.0, 1.0, 1.0),
+			matrix     : None,
+			weights    : Vec::new(),
+		};
+	}
+}
+
+pub struct ModelPart
+{
+	pub mesh: std::rc::Rc<Mesh>,
+	pub node: usize,
+	pub skin: Option<usize>,
+}
+
+pub struct Skin
+{
+	pub joints               : Vec<usize>,
+	pub inverse_bind_matrices: Vec<nalgebra::Matrix4<f32>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnimationPath
+{
+	Translation,
+	Rotation,
+	Scale,
+	Weights,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Interpolation
+{
+	Step,
+	Linear,
+	//NOTE(fpalacios): Cada keyframe trae tangente de entrada, valor y tangente de salida
+	CubicSpline,
+}
+
+pub struct AnimationChannel
+{
+	pub node         : usize,
+	pub path         : AnimationPath,
+	pub interpolation: Interpolation,
+	pub times        : Vec<f32>,
+	pub values       : Vec<f32>,
+}
+
+impl AnimationChannel
+{
+	fn sample(&self, time: f32) -> Vec<f32>
+	{
+		let keyframes = self.times.len();
+		if keyframes == 0
+		{
+			return Vec::new();
+		}
+
+		let elements   = if self.interpolation == Interpolation::CubicSpline { 3 } else { 1 };
+		let components = self.values.len() / (keyframes * elements);
+
+		//NOTE(fpalacios): Con spline el valor esta en el medio, entre las dos tangentes
+		let value = |keyframe: usize, element: usize| -> &[f32]
+		{
+			let start = (keyframe * elements + element) * components;
+			return &self.values[start .. start + components];
+		};
+		let value_element = if self.interpolation == Interpolation::CubicSpline { 1 } else { 0 };
+
+		if time <= self.times[0]
+		{
+			return value(0, value_element).to_vec();
+		}
+		if time >= self.times[keyframes - 1]
+		{
+			return value(keyframes - 1, value_element).to_vec();
+		}
+
+		let next     = self.times.iter().position(|keyframe_time| *keyframe_time > time).unwrap_or(keyframes - 1);
+		let previous = next - 1;
+		let duration = self.times[next] - self.times[previous];
+		let factor   = if duration > 0.0 { (time - self.times[previous]) / duration } else { 0.0 };
+
+		let mut sampled = match self.interpolation
+		{
+			Interpolation::Step => value(previous, 0).to_vec(),
+			Interpolation::Linear if self.path == AnimationPath::Rotation =>
+			{
+				let from = quaternion(value(previous, 0));
+				let to   = quaternion(value(next, 0));
+
+				let rotation = from.try_slerp(&to, factor, 1.0e-6).unwrap_or_else(|| from.nlerp(&to, factor));
+				vec![rotation.i, rotation.j, rotation.k, rotation.w]
+			},
+			Interpolation::Linear =>
+			{
+				value(previous, 0).iter().zip(value(next, 0)).map(|(from, to)| from + (to - from) * factor).collect()
+			},
+			Interpolation::CubicSpline =>
+			{
+				let factor2 = factor * factor;
+				let factor3 = factor2 * factor;
+
+				(0 .. components).map(|component|
+				{
+					let previous_value = value(previous, 1)[component];
+					let out_tangent    = value(previous, 2)[component] * duration;
+					let next_value     = value(next, 1)[component];
+					let in_tangent     = value(next, 0)[component] * duration;
+
+					(2.0 * factor3 - 3.0 * factor2 + 1.0) * previous_value
+						+ (factor3 - 2.0 * factor2 + factor) * out_tangent
+						+ (-2.0 * factor3 + 3.0 * factor2) * next_value
+						+ (factor3 - factor2) * in_tangent
+				}).collect()
+			},
+		};
+
+		if self.path == AnimationPath::Rotation && self.interpolation == Interpolation::CubicSpline
+		{
+			let rotation = quaternion(&sampled);
+			sampled = vec![rotation.i, rotation.j, rotation.k, rotation.w];
+		}
+
+		return sampled;
+	}
+}
+
+//NOTE(fpalacios): Los cuaterniones vienen como x, y, z, w
+fn quaternion(values: &[f32]) -> nalgebra::UnitQuaternion<f32>
+{
+	return nalgebra::UnitQuaternion::from_quaternion(nalgebra::Quaternion::new(values[3], values[0], values[1], values[2]));
+}
+
+pub struct Animation
+{
+	pub channels: Vec<AnimationChannel>,
+	//NOTE(fpalacios): En segundos, la animacion se repite
+	pub duration: f32,
+}
+
+//NOTE(fpalacios): Lo que hay que dibujar de cada parte del modelo en un momento de la animacion
+pub struct PartPose<'model>
+{
+	pub mesh     : &'model std::rc::Rc<Mesh>,
+	pub transform: nalgebra::Matrix4<f32>,
+	//NOTE(fpalacios): Solo para las mallas deformables, los vertices ya deformados
+	pub vertices : Option<Vec<crate::renderer::Vertex3D>>,
+}
+
+//NOTE(fpalacios): Jerarquia de nodos con mallas, esqueletos y a lo sumo una animacion
+pub struct Model
+{
+	pub nodes        : Vec<Node>,
+	pub parts        : Vec<ModelPart>,
+	pub skins        : Vec<Skin>,
+	pub animation    : Option<Animation>,
+	//NOTE(fpalacios): Centra el modelo en el origen y lo escala para que el lado mas largo vaya de -1 a 1, asi la camara
+	//no depende de las unidades en que lo haya exportado cada uno
+	pub normalization: nalgebra::Matrix4<f32>,
+
+	//NOTE(fpalacios): Indices de los nodos con los padres antes que los hijos
+	node_order: Vec<usize>,
+}
+
+impl Model
+{
+	pub fn new(nodes: Vec<Node>, parts: Vec<ModelPart>, skins: Vec<Skin>, animation: Option<Animation>) -> Model
+	{
+		let mut node_order = Vec::new();
+		let mut pending: Vec<usize> = (0 .. nodes.len()).filter(|index| nodes[*index].parent.is_none()).collect();
+
+		while let Some(index) = pending.pop()
+		{
+			node_order.push(index);
+			pending.extend((0 .. nodes.len()).filter(|child| nodes[*child].parent == Some(index)));
+		}
+
+		let mut model = Model
+		{
+			nodes,
+			parts,
+			skins,
+			animation,
+			normalization: nalgebra::Matrix4::identity(),
+			node_order,
+		};
+
+		model.normalization = model.compute_normalization();
+
+		return model;
+	}
+
+	pub fn from_mesh(mesh: Mesh) -> Model
+	{
+		return Model::new(
+			vec![Node::identity(None)],
+			vec![
+				ModelPart
+				{
+					mesh: std::rc::Rc::new(mesh),
+					node: 0,
+					skin: None,
+				}
+			],
+			Vec::new(),
+			None
+		);
+	}
+
+	fn compute_normalization(&self) -> nalgebra::Matrix4<f32>
+	{
+		let mut min = nalgebra::Vector3::repeat(f32::MAX);
+		let mut max = nalgebra::Vector3::repeat(f32::MIN);
+
+		for part in self.pose_raw(0.0)
+		{
+			let vertices = part.vertices.as_ref().unwrap_or(&part.mesh.vertices);
+			for vertex in vertices
+			{
+				let position = part.transform.transform_point(&nalgebra::Point3::from(vertex.position));
+				min = min.inf(&position.coords);
+				max = max.sup(&position.coords);
+			}
+		}
+
+		if min.x > max.x
+		{
+			return nalgebra::Matrix4::identity();
+		}
+
+		let center = (min + max) / 2.0;
+		let size   = (max - min).max();
+		let scale  = if size > 0.0 { 2.0 / size } else { 1.0 };
+
+		return nalgebra::Matrix4::new_scaling(scale) * nalgebra::Matrix4::new_translation(&-center);
+	}
+
+	//NOTE(fpalacios): time en segundos, se da la vuelta al terminar la animacion
+	pub fn pose(&self, time: f32) -> Vec<PartPose<'_>>
+	{
+		let mut poses = self.pose_raw(time);
+
+		for pose in &mut poses
+		{
+			pose.transform = self.normalization * pose.transform;
+		}
+
+		return poses;
+	}
+
+	fn pose_raw(&self, time: f32) -> Vec<PartPose<'_>>
+	{
+		let mut translations: Vec<nalgebra::Vector3<f32>>       = self.nodes.iter().map(|node| node.translation).collect();
+		let mut rotations   : Vec<nalgebra::UnitQuaternion<f32>> = self.nodes.iter().map(|node| node.rotation).collect();
+		let mut scales      : Vec<nalgebra::Vector3<f32>>       = self.nodes.iter().map(|node| node.scale).collect();
+		let mut weights     : Vec<Vec<f32>>                     = self.nodes.iter().map(|node| node.weights.clone()).collect();
+
+		if let Some(animation) = &self.animation
+		{
+			let time = if animation.duration > 0.0 { time % animation.duration } else { 0.0 };
+
+			for channel in &animation.channels
+			{
+				let value = channel.sample(time);
+
+				match (channel.path, value.len())
+				{
+					(AnimationPath::Translation, 3) => translations[channel.node] = nalgebra::Vector3::new(value[0], value[1], value[2]),
+					(AnimationPath::Rotation,    4) => rotations[channel.node]    = quaternion(&value),
+					(AnimationPath::Scale,       3) => scales[channel.node]       = nalgebra::Vector3::new(value[0], value[1], value[2]),
+					(AnimationPath::Weights,     _) => weights[channel.node]      = value,
+					_                               => {},
+				}
+			}
+		}
+
+		let mut global_matrices = vec![nalgebra::Matrix4::identity(); self.nodes.len()];
+
+		for index in &self.node_order
+		{
+			let node = &self.nodes[*index];
+
+			let local = match node.matrix
+			{
+				Some(matrix) => matrix,
+				None =>
+				{
+					nalgebra::Matrix4::new_translation(&translations[*index])
+						* rotations[*index].to_homogeneous()
+						* nalgebra::Matrix4::new_nonuniform_scaling(&scales[*index])
+				},
+			};
+
+			global_matrices[*index] = match node.parent
+			{
+				Some(parent) => global_matrices[parent] * local,
+				None         => local,
+			};
+		}
+
+		return self.parts.iter().map(|part|
+		{
+			let node_weights = &weights[part.node];
+
+			match part.skin.and_then(|skin| self.skins.get(skin))
+			{
+				//NOTE(fpalacios): En las mallas con esqueleto la transformacion del nodo no se usa, solo la de los huesos
+				Some(skin) =>
+				{
+					let joint_matrices: Vec<nalgebra::Matrix4<f32>> = skin.joints.iter().enumerate().map(|(joint, node)|
+						global_matrices[*node] * skin.inverse_bind_matrices.get(joint).cloned().unwrap_or_else(nalgebra::Matrix4::identity)
+					).collect();
+
+					PartPose
+					{
+						mesh     : &part.mesh,
+						transform: nalgebra::Matrix4::identity(),
+						vertices : Some(part.mesh.deform(node_weights, Some(&joint_matrices))),
+					}
+				},
+				None =>
+				{
+					PartPose
+					{
+						mesh     : &part.mesh,
+						transform: global_matrices[part.node],
+						vertices : if part.mesh.morph_targets.is_empty() { None } else { Some(part.mesh.deform(node_weights, None)) },
+					}
+				},
+			}
+		}).collect();
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	fn parse_error_line(source: &str) -> usize
+	{
+		return match super::load_obj(source)
+		{
+			Err(super::MeshError::Parse { line, .. }) => line,
+			Err(err)                                   => panic!("{:?} no es un error de sintaxis", err),
+			Ok(_)                                      => panic!("{:?} no deberia cargar", source),
+		};
+	}
+
+	#[test]
+	fn obj_faces()
+	{
+		//NOTE(fpalacios): Un cuadrado con indices negativos, triangulado en abanico. Los vertices con la misma posicion,
+		//coordenada de textura y normal se reusan
+		let mesh = super::load_obj(
+			"# cuadrado\n\
+			 v 0 0 0\n\
+			 v 1 0 0\n\
+			 v 1 1 0\n\
+			 v 0 1 0\n\
+			 vt 0 0\n\
+			 vt 1\n\
+			 vn 0 0 1\n\
+			 o ignorado\n\
+			 f -4/1/1 -3/2/1 -2/1/1 -1/1/1\n\
+			 f 1/1/1 3/1/1 4/1/1\n"
+		).unwrap();
+
+		assert_eq!(mesh.vertices.len(), 4);
+		assert_eq!(mesh.indices, vec![0, 1, 2, 0, 2, 3, 0, 2, 3]);
+		assert_eq!(mesh.vertices[1].position, [1.0, 0.0, 0.0]);
+		assert_eq!(mesh.vertices[1].texture_coords, [1.0, 0.0]);
+		assert_eq!(mesh.vertices[1].normal, [0.0, 0.0, 1.0]);
+
+		//NOTE(fpalacios): Sin vn las normales se calculan con las caras
+		let mesh = super::load_obj("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\nf 1//  2 3\n").unwrap();
+		assert_eq!(mesh.vertices.len(), 3);
+		assert!(mesh.vertices.iter().all(|vertex| vertex.normal == [0.0, 0.0, 1.0]));
+	}
+
+	#[test]
+	fn obj_errors()
+	{
+		let triangle = "v 0 0 0\nv 1 0 0\nv 0 1 0\n";
+
+		assert_eq!(parse_error_line("v 0 0\n"), 1);
+		assert_eq!(parse_error_line("v 0 0 x\n"), 1);
+		assert_eq!(parse_error_line("vt x\n"), 1);
+		assert_eq!(parse_error_line(&format!("{}f 1 2\n", triangle)), 4);
+		assert_eq!(parse_error_line(&format!("{}f 1 2 4\n", triangle)), 4);
+		assert_eq!(parse_error_line(&format!("{}f 0 1 2\n", triangle)), 4);
+		assert_eq!(parse_error_line(&format!("{}f -4 1 2\n", triangle)), 4);
+		assert_eq!(parse_error_line(&format!("{}f 1/1 2/1 3/1\n", triangle)), 4);
+		assert_eq!(parse_error_line(&format!("{}f 1//1 2//1 3//1\n", triangle)), 4);
+		assert_eq!(parse_error_line(&format!("{}f 1 2 99999999999999999999\n", triangle)), 4);
+	}
+}
@@ -0,0 +1,59 @@
+//NOTE(fpalacios): Dibuja el mundo en un framebuffer fuera de pantalla usando OSMesa (el rasterizador por software de Mesa),
+//no necesita ni ventana ni X ni GPU. Sirve para generar previews y para comparar contra imagenes de referencia
+pub struct OffscreenTarget
+{
+	pub facade  : glium::HeadlessRenderer,
+	pub renderer: crate::renderer::GliumRenderer,
+	pub width   : u32,
+	pub height  : u32,
+}
+
+#[derive(Debug)]
+pub enum OffscreenError
+{
+	Context(String),
+	Render(String),
+	Save(image::ImageError),
+}
+
+impl OffscreenTarget
+{
+	pub fn new(width: u32, height: u32) -> Result<OffscreenTarget, OffscreenError>
+	{
+		let context = glium::glutin::platform::unix::HeadlessContextExt::build_osmesa(
+			glium::glutin::ContextBuilder::new(),
+			glium::glutin::dpi::PhysicalSize::new(width, height)
+		).map_err(|err| OffscreenError::Context(format!("{:?}", err)))?;
+
+		let context = unsafe { context.make_current() }.map_err(|(_, err)| OffscreenError::Context(format!("{:?}", err)))?;
+
+		let facade = glium::HeadlessRenderer::new(context).map_err(|err| OffscreenError::Context(format!("{:?}", err)))?;
+
+		let renderer = crate::renderer::GliumRenderer::offscreen(&facade, crate::shaders::ShaderSource::from_env(), width, height)
+			.map_err(OffscreenError::Render)?;
+
+		return Ok(
+			OffscreenTarget
+			{
+				facade,
+				renderer,
+				width,
+				height,
+			}
+		);
+	}
+
+	pub fn render(&mut self, world: &crate::world::World, interpolation: f32) -> Result<image::RgbaImage, OffscreenError>
+	{
+		world.draw(&mut self.renderer, interpolation);
+
+		return self.renderer.read_image()
+			.ok_or_else(|| OffscreenError::Render(String::from("El tamaño de la imagen leida no coincide")));
+	}
+
+	pub fn render_png(&mut self, world: &crate::world::World, interpolation: f32, path: &std::path::Path) -> Result<(), OffscreenError>
+	{
+		let image = self.render(world, interpolation)?;
+		return image.save(path).map_err(OffscreenError::Save);
+	}
+}
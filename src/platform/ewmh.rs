@@ -0,0 +1,241 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WindowType
+{
+	Desktop,
+	Dock,
+	Toolbar,
+	Menu,
+	Utility,
+	Splash,
+	Dialog,
+	Normal,
+}
+
+impl WindowType
+{
+	pub fn atom_name(&self) -> &'static str
+	{
+		return match self
+		{
+			WindowType::Desktop => "_NET_WM_WINDOW_TYPE_DESKTOP",
+			WindowType::Dock    => "_NET_WM_WINDOW_TYPE_DOCK",
+			WindowType::Toolbar => "_NET_WM_WINDOW_TYPE_TOOLBAR",
+			WindowType::Menu    => "_NET_WM_WINDOW_TYPE_MENU",
+			WindowType::Utility => "_NET_WM_WINDOW_TYPE_UTILITY",
+			WindowType::Splash  => "_NET_WM_WINDOW_TYPE_SPLASH",
+			WindowType::Dialog  => "_NET_WM_WINDOW_TYPE_DIALOG",
+			WindowType::Normal  => "_NET_WM_WINDOW_TYPE_NORMAL",
+		};
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WindowState
+{
+	Modal,
+	Sticky,
+	MaximizedVert,
+	MaximizedHorz,
+	Shaded,
+	SkipTaskbar,
+	SkipPager,
+	Hidden,
+	Fullscreen,
+	Above,
+	Below,
+	DemandsAttention,
+}
+
+impl WindowState
+{
+	pub fn atom_name(&self) -> &'static str
+	{
+		return match self
+		{
+			WindowState::Modal            => "_NET_WM_STATE_MODAL",
+			WindowState::Sticky           => "_NET_WM_STATE_STICKY",
+			WindowState::MaximizedVert    => "_NET_WM_STATE_MAXIMIZED_VERT",
+			WindowState::MaximizedHorz    => "_NET_WM_STATE_MAXIMIZED_HORZ",
+			WindowState::Shaded           => "_NET_WM_STATE_SHADED",
+			WindowState::SkipTaskbar      => "_NET_WM_STATE_SKIP_TASKBAR",
+			WindowState::SkipPager        => "_NET_WM_STATE_SKIP_PAGER",
+			WindowState::Hidden           => "_NET_WM_STATE_HIDDEN",
+			WindowState::Fullscreen       => "_NET_WM_STATE_FULLSCREEN",
+			WindowState::Above            => "_NET_WM_STATE_ABOVE",
+			WindowState::Below            => "_NET_WM_STATE_BELOW",
+			WindowState::DemandsAttention => "_NET_WM_STATE_DEMANDS_ATTENTION",
+		};
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Desktop
+{
+	All,
+	Index(u32),
+}
+
+impl Desktop
+{
+	pub fn to_cardinal(&self) -> u32
+	{
+		return match self
+		{
+			//NOTE(fpalacios): 0xFFFFFFFF hace que la ventana aparezca en todos los escritorios virtuales
+			Desktop::All          => 0xFFFFFFFF,
+			Desktop::Index(index) => *index,
+		};
+	}
+}
+
+//NOTE(fpalacios): Capa tipada sobre platform::xcb para hablar con el window manager, guarda los atoms para no pedirlos cada vez
+pub struct Ewmh<'client, 'conn>
+{
+	pub client: &'client crate::platform::xcb::Client<'conn>,
+	atoms     : std::cell::RefCell<std::collections::HashMap<&'static str, crate::platform::xcb::AtomID>>,
+}
+
+impl<'client, 'conn> Ewmh<'client, 'conn>
+{
+	pub fn new(client: &'client crate::platform::xcb::Client<'conn>) -> Ewmh<'client, 'conn>
+	{
+		return Ewmh
+		{
+			client,
+			atoms: std::cell::RefCell::new(std::collections::HashMap::new()),
+		};
+	}
+
+	pub fn atom(&self, name: &'static str) -> crate::platform::xcb::AtomID
+	{
+		if let Some(atom_id) = self.atoms.borrow().get(name)
+		{
+			return *atom_id;
+		}
+
+		let atom_id = self.client.find_atom_id_by_name(name).unwrap();
+		self.atoms.borrow_mut().insert(name, atom_id);
+
+		return atom_id;
+	}
+
+	pub fn set_window_type(&self, window: &crate::platform::xcb::Window, window_type: WindowType)
+	{
+		window.set_property(
+			&crate::platform::xcb::Property
+			{
+				key  : self.atom("_NET_WM_WINDOW_TYPE"),
+				value: crate::platform::xcb::PropertyValue::Atom(self.atom(window_type.atom_name())),
+			}
+		);
+	}
+
+	pub fn add_state(&self, window: &crate::platform::xcb::Window, states: &[WindowState])
+	{
+		self.change_state(window, crate::platform::xcb::WmStateAction::Add, states);
+	}
+
+	pub fn remove_state(&self, window: &crate::platform::xcb::Window, states: &[WindowState])
+	{
+		self.change_state(window, crate::platform::xcb::WmStateAction::Remove, states);
+	}
+
+	pub fn toggle_state(&self, window: &crate::platform::xcb::Window, states: &[WindowState])
+	{
+		self.change_state(window, crate::platform::xcb::WmStateAction::Toggle, states);
+	}
+
+	fn change_state(&self, window: &crate::platform::xcb::Window, action: crate::platform::xcb::WmStateAction, states: &[WindowState])
+	{
+		let state_atoms: Vec<crate::platform::xcb::AtomID> = states.iter().map(|state| self.atom(state.atom_name())).collect();
+		self.client.send_wm_states(&window.screen.root_window(), window.id, action, &state_atoms);
+	}
+
+	pub fn states(&self, window: &crate::platform::xcb::Window) -> Vec<WindowState>
+	{
+		let all_states =
+		[
+			WindowState::Modal, WindowState::Sticky, WindowState::MaximizedVert, WindowState::MaximizedHorz,
+			WindowState::Shaded, WindowState::SkipTaskbar, WindowState::SkipPager, WindowState::Hidden,
+			WindowState::Fullscreen, WindowState::Above, WindowState::Below, WindowState::DemandsAttention,
+		];
+
+		let state_atoms = match window.get_property(self.atom("_NET_WM_STATE"))
+		{
+			Ok(property) => property.value.as_atom_list().unwrap_or_default(),
+			Err(_)       => return Vec::new(),
+		};
+
+		return all_states.iter().filter(|state| state_atoms.contains(&self.atom(state.atom_name()))).cloned().collect();
+	}
+
+	pub fn set_desktop(&self, window: &crate::platform::xcb::Window, desktop: Desktop)
+	{
+		let net_wm_desktop = self.atom("_NET_WM_DESKTOP");
+
+		window.set_property(
+			&crate::platform::xcb::Property
+			{
+				key  : net_wm_desktop,
+				value: crate::platform::xcb::PropertyValue::U32(desktop.to_cardinal()),
+			}
+		);
+
+		//NOTE(fpalacios): Si la ventana ya esta mapeada el window manager ignora la propiedad, hay que mandarle el mensaje
+		self.client.send_message(
+			&window.screen.root_window(),
+			crate::platform::xcb::Event::ClientMessageEvent(
+				crate::platform::xcb::ClientMessageEvent
+				{
+					window    : window.id,
+					event_type: net_wm_desktop,
+					data      : [desktop.to_cardinal(), 1, 0, 0, 0],
+				}
+			)
+		);
+	}
+
+	pub fn set_wm_name(&self, window: &crate::platform::xcb::Window, name: &str)
+	{
+		window.set_property(
+			&crate::platform::xcb::Property
+			{
+				key  : self.atom("_NET_WM_NAME"),
+				value: crate::platform::xcb::PropertyValue::Raw
+				{
+					type_atom_id: self.atom("UTF8_STRING"),
+					format      : 8,
+					data        : name.as_bytes().to_vec(),
+				},
+			}
+		);
+	}
+
+	//NOTE(fpalacios): _NET_WORKAREA tiene x, y, ancho y alto por cada escritorio virtual
+	pub fn workarea(&self, screen: &crate::platform::xcb::Screen) -> Option<Vec<(i16, i16, u16, u16)>>
+	{
+		let values = screen.root_window().get_property(self.atom("_NET_WORKAREA")).ok()?.value.as_u32_list()?;
+
+		return Some(
+			values.chunks_exact(4)
+				.map(|area| (area[0] as i16, area[1] as i16, area[2] as u16, area[3] as u16))
+				.collect()
+		);
+	}
+
+	pub fn active_window(&self, screen: &crate::platform::xcb::Screen) -> Option<crate::platform::xcb::WindowID>
+	{
+		let windows = screen.root_window().get_property(self.atom("_NET_ACTIVE_WINDOW")).ok()?.value.as_window_list()?;
+
+		return windows.first().cloned().filter(|window_id| *window_id != xcb::NONE);
+	}
+
+	pub fn client_list(&self, screen: &crate::platform::xcb::Screen) -> Vec<crate::platform::xcb::WindowID>
+	{
+		return match screen.root_window().get_property(self.atom("_NET_CLIENT_LIST"))
+		{
+			Ok(property) => property.value.as_window_list().unwrap_or_default(),
+			Err(_)       => Vec::new(),
+		};
+	}
+}
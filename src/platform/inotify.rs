@@ -0,0 +1,134 @@
+pub type WatchID = libc::c_int;
+
+#[derive(Debug, Clone)]
+pub struct InotifyEvent
+{
+	pub watch: WatchID,
+	pub mask : u32,
+	pub name : Option<String>,
+}
+
+//NOTE(fpalacios): Envoltorio minimo de inotify(7) para enterarse cuando cambian archivos en disco
+pub struct Inotify
+{
+	fd: std::os::unix::io::RawFd,
+}
+
+impl Inotify
+{
+	pub fn new() -> std::io::Result<Inotify>
+	{
+		let fd = unsafe { libc::inotify_init1(libc::IN_CLOEXEC) };
+		if fd < 0
+		{
+			return Err(std::io::Error::last_os_error());
+		}
+
+		return Ok(Inotify { fd });
+	}
+
+	pub fn add_watch(&self, path: &std::path::Path, mask: u32) -> std::io::Result<WatchID>
+	{
+		let path = std::ffi::CString::new(std::os::unix::ffi::OsStrExt::as_bytes(path.as_os_str()))
+			.map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+
+		let watch = unsafe { libc::inotify_add_watch(self.fd, path.as_ptr(), mask) };
+		if watch < 0
+		{
+			return Err(std::io::Error::last_os_error());
+		}
+
+		return Ok(watch);
+	}
+
+	//NOTE(fpalacios): Se bloquea hasta que haya al menos un evento
+	pub fn read_events(&self) -> std::io::Result<Vec<InotifyEvent>>
+	{
+		//NOTE(fpalacios): Alcanza para varios eventos con nombres de hasta NAME_MAX
+		let mut buffer = vec![0_u8; 16 * (std::mem::size_of::<libc::inotify_event>() + 256)];
+
+		let length = loop
+		{
+			let result = unsafe { libc::read(self.fd, buffer.as_mut_ptr() as *mut libc::c_void, buffer.len()) };
+			if result >= 0
+			{
+				break result as usize;
+			}
+
+			let error = std::io::Error::last_os_error();
+			if error.kind() != std::io::ErrorKind::Interrupted
+			{
+				return Err(error);
+			}
+		};
+
+		let header_size = std::mem::size_of::<libc::inotify_event>();
+		let mut events = Vec::new();
+		let mut offset = 0;
+
+		while offset + header_size <= length
+		{
+			let event = unsafe { std::ptr::read_unaligned(buffer.as_ptr().add(offset) as *const libc::inotify_event) };
+
+			let name_start = offset + header_size;
+			let name_end   = std::cmp::min(name_start + event.len as usize, length);
+
+			//NOTE(fpalacios): El nombre viene rellenado con ceros hasta alinear
+			let name_bytes: Vec<u8> = buffer[name_start .. name_end].iter().cloned().take_while(|byte| *byte != 0).collect();
+
+			events.push(
+				InotifyEvent
+				{
+					watch: event.wd,
+					mask : event.mask,
+					name : if name_bytes.is_empty() { None } else { Some(String::from_utf8_lossy(&name_bytes).into_owned()) },
+				}
+			);
+
+			offset = name_end;
+		}
+
+		return Ok(events);
+	}
+}
+
+impl std::os::unix::io::AsRawFd for Inotify
+{
+	fn as_raw_fd(&self) -> std::os::unix::io::RawFd
+	{
+		return self.fd;
+	}
+}
+
+impl Drop for Inotify
+{
+	fn drop(&mut self)
+	{
+		unsafe { libc::close(self.fd) };
+	}
+}
+
+//NOTE(fpalacios): El directorio y todos sus subdirectorios, inotify no mira recursivamente
+pub fn directories(root: &std::path::Path) -> Vec<std::path::PathBuf>
+{
+	let mut directories = vec![root.to_path_buf()];
+	let mut index = 0;
+
+	while index < directories.len()
+	{
+		if let Ok(entries) = std::fs::read_dir(&directories[index])
+		{
+			for entry in entries.flatten()
+			{
+				if entry.file_type().map(|file_type| file_type.is_dir()).unwrap_or(false)
+				{
+					directories.push(entry.path());
+				}
+			}
+		}
+
+		index += 1;
+	}
+
+	return directories;
+}
@@ -1 +1,5 @@
+pub mod ewmh;
+pub mod inotify;
+pub mod poll;
+pub mod power;
 pub mod xcb;
\ No newline at end of file
@@ -0,0 +1,43 @@
+//NOTE(fpalacios): Espera con poll(2) a que alguno de los file descriptors tenga algo para leer o a que se cumpla el timeout.
+//Devuelve los que quedaron listos, vacio si fue por timeout
+pub fn wait_readable(fds: &[std::os::unix::io::RawFd], timeout: Option<std::time::Duration>) -> std::io::Result<Vec<std::os::unix::io::RawFd>>
+{
+	let mut poll_fds: Vec<libc::pollfd> = fds.iter().map(|fd|
+		libc::pollfd
+		{
+			fd     : *fd,
+			events : libc::POLLIN,
+			revents: 0,
+		}
+	).collect();
+
+	let timeout_ms = match timeout
+	{
+		//NOTE(fpalacios): Se redondea para arriba para no despertarse un poquito antes del deadline y quedar girando
+		Some(timeout) => std::cmp::min(timeout.as_micros().div_ceil(1000), libc::c_int::MAX as u128) as libc::c_int,
+		None          => -1,
+	};
+
+	loop
+	{
+		let result = unsafe { libc::poll(poll_fds.as_mut_ptr(), poll_fds.len() as libc::nfds_t, timeout_ms) };
+
+		if result >= 0
+		{
+			break;
+		}
+
+		let error = std::io::Error::last_os_error();
+		if error.kind() != std::io::ErrorKind::Interrupted
+		{
+			return Err(error);
+		}
+	}
+
+	return Ok(
+		poll_fds.iter()
+			.filter(|poll_fd| poll_fd.revents & (libc::POLLIN | libc::POLLHUP | libc::POLLERR) != 0)
+			.map(|poll_fd| poll_fd.fd)
+			.collect()
+	);
+}
@@ -0,0 +1,48 @@
+//NOTE(fpalacios): Lee /sys/class/power_supply. Se considera que esta en bateria si hay alguna bateria y ninguna fuente
+//de corriente (Mains/USB) enchufada. Si no se puede leer (no es linux, contenedor, etc) se asume que esta enchufado
+pub fn on_battery() -> bool
+{
+	let power_supplies = match std::fs::read_dir("/sys/class/power_supply")
+	{
+		Ok(power_supplies) => power_supplies,
+		Err(_)             => return false,
+	};
+
+	let mut has_battery    = false;
+	let mut external_power = false;
+
+	for power_supply in power_supplies.flatten()
+	{
+		let path = power_supply.path();
+
+		let supply_type = match std::fs::read_to_string(path.join("type"))
+		{
+			Ok(supply_type) => supply_type,
+			Err(_)          => continue,
+		};
+
+		match supply_type.trim()
+		{
+			"Battery" =>
+			{
+				//NOTE(fpalacios): Las baterias de los mouse y teclados inalambricos tienen scope Device, esas no cuentan
+				let scope = std::fs::read_to_string(path.join("scope")).unwrap_or_default();
+				if scope.trim() != "Device"
+				{
+					has_battery = true;
+				}
+			},
+			"Mains" | "USB" | "USB_C" | "USB_PD" =>
+			{
+				let online = std::fs::read_to_string(path.join("online")).unwrap_or_default();
+				if online.trim() == "1"
+				{
+					external_power = true;
+				}
+			},
+			_ => {},
+		}
+	}
+
+	return has_battery && !external_power;
+}
@@ -8,6 +8,7 @@ pub type EventKeyID        = xcb::EventMask;
 pub type ColorMapID        = xcb::Atom;
 pub type PixMapID          = xcb::Atom;
 pub type VisualID          = xcb::Atom;
+pub type OutputID          = xcb::randr::Output;
 
 #[derive(Debug)]
 pub struct Error
@@ -18,22 +19,213 @@ pub struct Error
 pub enum Event
 {
 	KeyEvent(KeyEvent),
-	ExposedEvent,
-	//TODO(fpalacios): Mover los datos de ClientMessageEvent a su propia struct (como en KeyEvent)
-	ClientMessageEvent
-	{
-		window       : WindowID,
-		event_type   : AtomID,
-		data         : [u32; 5],
-	},
+	ButtonEvent(ButtonEvent),
+	MotionEvent(PointerEvent),
+	CrossingEvent(CrossingEvent),
+	ExposedEvent(ExposedEvent),
+	VisibilityEvent(VisibilityEvent),
+	ConfigureEvent(ConfigureEvent),
+	PropertyEvent(PropertyEvent),
+	MapEvent(MapEvent),
+	ClientMessageEvent(ClientMessageEvent),
+	//NOTE(fpalacios): RandR avisa que se conectó, desconectó o movió algún monitor
+	RandrEvent(RandrEvent),
+	ShapeEvent(ShapeEvent),
+	ScreenSaverEvent(ScreenSaverEvent),
 	UnknownEvent(xcb::EventMask, xcb::Event<xcb::ffi::xcb_generic_event_t>)
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum KeyEvent
 {
-	KeyPress,
-	KeyReleased,
+	KeyPress(Key),
+	KeyReleased(Key),
+}
+
+#[derive(Debug, Clone)]
+pub struct Key
+{
+	pub window : WindowID,
+	pub keycode: xcb::Keycode,
+	pub keysym : xcb::Keysym,
+	//NOTE(fpalacios): Mascara de modificadores (xcb::MOD_MASK_*) y botones apretados al momento del evento
+	pub state  : u16,
+}
+
+#[derive(Debug, Clone)]
+pub enum ButtonEvent
+{
+	ButtonPress(Button),
+	ButtonReleased(Button),
+}
+
+#[derive(Debug, Clone)]
+pub struct Button
+{
+	pub button : xcb::Button,
+	pub pointer: PointerEvent,
+}
+
+#[derive(Debug, Clone)]
+pub struct PointerEvent
+{
+	pub window: WindowID,
+	pub x     : i16,
+	pub y     : i16,
+	pub root_x: i16,
+	pub root_y: i16,
+	pub state : u16,
+}
+
+#[derive(Debug, Clone)]
+pub enum CrossingEvent
+{
+	Enter(PointerEvent),
+	Leave(PointerEvent),
+}
+
+#[derive(Debug, Clone)]
+pub struct ExposedEvent
+{
+	pub window: WindowID,
+	pub x     : u16,
+	pub y     : u16,
+	pub width : u16,
+	pub height: u16,
+	//NOTE(fpalacios): Cuantos Expose mas vienen atras de este, se puede repintar cuando llega a 0
+	pub count : u16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Visibility
+{
+	Unobscured,
+	PartiallyObscured,
+	FullyObscured,
+}
+
+#[derive(Debug, Clone)]
+pub struct VisibilityEvent
+{
+	pub window    : WindowID,
+	pub visibility: Visibility,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfigureEvent
+{
+	pub window           : WindowID,
+	pub x                : i16,
+	pub y                : i16,
+	pub width            : u16,
+	pub height           : u16,
+	pub border_width     : u16,
+	pub override_redirect: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct PropertyEvent
+{
+	pub window : WindowID,
+	pub atom   : AtomID,
+	pub deleted: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum MapEvent
+{
+	Mapped(WindowID),
+	Unmapped(WindowID),
+}
+
+#[derive(Debug, Clone)]
+pub struct ClientMessageEvent
+{
+	pub window    : WindowID,
+	pub event_type: AtomID,
+	pub data      : [u32; 5],
+}
+
+#[derive(Debug, Clone)]
+pub enum RandrEvent
+{
+	ScreenChanged
+	{
+		root  : WindowID,
+		width : u16,
+		height: u16,
+	},
+	CrtcChanged
+	{
+		crtc  : xcb::randr::Crtc,
+		x     : i16,
+		y     : i16,
+		width : u16,
+		height: u16,
+	},
+	OutputChanged
+	{
+		output    : OutputID,
+		crtc      : xcb::randr::Crtc,
+		connection: u8,
+	},
+	Other(u8),
+}
+
+#[derive(Debug, Clone)]
+pub struct ShapeEvent
+{
+	pub window: WindowID,
+	pub kind  : xcb::shape::Kind,
+	pub x     : i16,
+	pub y     : i16,
+	pub width : u16,
+	pub height: u16,
+	pub shaped: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScreenSaverEvent
+{
+	//NOTE(fpalacios): true cuando se activa el protector de pantalla (los bloqueadores de pantalla lo activan)
+	pub active: bool,
+	pub forced: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScreenSaverInfo
+{
+	pub active    : bool,
+	pub idle_time : std::time::Duration,
+}
+
+//NOTE(fpalacios): Valores del primer campo de los mensajes _NET_WM_STATE segun EWMH
+#[derive(Debug, Clone, Copy)]
+pub enum WmStateAction
+{
+	Remove = 0,
+	Add    = 1,
+	Toggle = 2,
+}
+
+#[derive(Debug, Clone)]
+pub struct Monitor
+{
+	pub output : OutputID,
+	pub name   : String,
+	pub primary: bool,
+	pub x      : i16,
+	pub y      : i16,
+	pub width  : u16,
+	pub height : u16,
+}
+
+impl Monitor
+{
+	pub fn geometry(&self) -> (i16, i16, u16, u16)
+	{
+		return (self.x, self.y, self.width, self.height);
+	}
 }
 
 #[derive(Debug)]
@@ -43,15 +235,26 @@ pub struct Property
 	pub value: PropertyValue,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum PropertyValue
 {
 	String(String),
 	I32(i32),
+	I32List(Vec<i32>),
 	U32(u32),
+	U32List(Vec<u32>),
 	None,
 	Atom(AtomID),
-	UnknownAtom(AtomID),
+	AtomList(Vec<AtomID>),
+	Window(WindowID),
+	WindowList(Vec<WindowID>),
+	//NOTE(fpalacios): Cualquier otro tipo, se guardan los bytes tal cual vienen con su formato (8, 16 o 32 bits)
+	Raw
+	{
+		type_atom_id: AtomID,
+		format      : u8,
+		data        : Vec<u8>,
+	},
 }
 
 impl PropertyValue
@@ -62,66 +265,447 @@ impl PropertyValue
 		{
 			PropertyValue::String(_) => xcb::ATOM_STRING,
 			PropertyValue::I32(_) => xcb::ATOM_INTEGER,
+			PropertyValue::I32List(_) => xcb::ATOM_INTEGER,
 			PropertyValue::U32(_) => xcb::ATOM_CARDINAL,
+			PropertyValue::U32List(_) => xcb::ATOM_CARDINAL,
 			PropertyValue::Atom(_) => xcb::ATOM_ATOM,
-			PropertyValue::UnknownAtom(atom_id) => atom_id.clone(),
+			PropertyValue::AtomList(_) => xcb::ATOM_ATOM,
+			PropertyValue::Window(_) => xcb::ATOM_WINDOW,
+			PropertyValue::WindowList(_) => xcb::ATOM_WINDOW,
+			PropertyValue::Raw { type_atom_id, .. } => *type_atom_id,
 			PropertyValue::None => xcb::ATOM_NONE
 		};
 	}
+
+	//NOTE(fpalacios): Las propiedades con un solo elemento se leen como valor suelto, estos helpers aceptan las dos formas
+	pub fn as_u32_list(&self) -> Option<Vec<u32>>
+	{
+		return match self
+		{
+			PropertyValue::U32(value)      => Some(vec![*value]),
+			PropertyValue::U32List(values) => Some(values.clone()),
+			_                              => None,
+		};
+	}
+
+	pub fn as_i32_list(&self) -> Option<Vec<i32>>
+	{
+		return match self
+		{
+			PropertyValue::I32(value)      => Some(vec![*value]),
+			PropertyValue::I32List(values) => Some(values.clone()),
+			_                              => None,
+		};
+	}
+
+	pub fn as_atom_list(&self) -> Option<Vec<AtomID>>
+	{
+		return match self
+		{
+			PropertyValue::Atom(value)      => Some(vec![*value]),
+			PropertyValue::AtomList(values) => Some(values.clone()),
+			_                               => None,
+		};
+	}
+
+	pub fn as_window_list(&self) -> Option<Vec<WindowID>>
+	{
+		return match self
+		{
+			PropertyValue::Window(value)      => Some(vec![*value]),
+			PropertyValue::WindowList(values) => Some(values.clone()),
+			_                                 => None,
+		};
+	}
+}
+
+fn bytes_to_u32s(data: &[u8]) -> Vec<u32>
+{
+	return data.chunks_exact(4).map(|chunk| u32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]])).collect();
+}
+
+fn bytes_to_u16s(data: &[u8]) -> Vec<u16>
+{
+	return data.chunks_exact(2).map(|chunk| u16::from_ne_bytes([chunk[0], chunk[1]])).collect();
+}
+
+//NOTE(fpalacios): Los keysyms de todos los keycodes, keysyms_per_keycode columnas por cada uno desde min_keycode
+struct KeyboardMapping
+{
+	min_keycode        : xcb::Keycode,
+	keysyms_per_keycode: usize,
+	keysyms            : Vec<xcb::Keysym>,
 }
 
 pub struct Client<'conn>
 {
 	pub conn               : &'conn xcb::Connection,
+	//NOTE(fpalacios): Se pide la primera vez que llega una tecla y se vuelve a pedir despues de un MappingNotify
+	keyboard_mapping       : std::cell::RefCell<Option<KeyboardMapping>>,
 }
 
 impl<'conn> Client<'conn>
 {
-	pub fn new(conn: &'conn xcb::Connection) -> Client
+	pub fn new(conn: &'conn xcb::Connection) -> Client<'conn>
 	{
 		return Client
 		{
 			conn,
+			keyboard_mapping: std::cell::RefCell::new(None),
 		};
 	}
 
 	pub fn find_atom_id_by_name(&self, name: &str) -> Option<AtomID>
 	{
-		let atom_id = xcb::intern_atom(&self.conn, false, name).get_reply().unwrap().atom();
+		let atom_id = xcb::intern_atom(self.conn, false, name).get_reply().unwrap().atom();
 		return if atom_id == xcb::ATOM_NONE { None } else { Some(atom_id) };
 	}
 
 	pub fn find_atom_name(&self, atom_id: AtomID) -> String
 	{
-		return xcb::get_atom_name(&self.conn, atom_id).get_reply().unwrap().name().to_owned();
+		return xcb::get_atom_name(self.conn, atom_id).get_reply().unwrap().name().to_owned();
 	}
 
 	pub fn poll_events(&self) -> Option<Event>
 	{
-		let event = match self.conn.poll_for_event()
+		let event = self.conn.poll_for_event()?;
+		return Some(self.decode_event(event));
+	}
+
+	//NOTE(fpalacios): Bloquea hasta que llegue un evento, devuelve None si se cortó la conexion con el server
+	pub fn wait_for_event(&self) -> Option<Event>
+	{
+		let event = self.conn.wait_for_event()?;
+		return Some(self.decode_event(event));
+	}
+
+	fn decode_event(&self, event: xcb::GenericEvent) -> Event
+	{
+		let response_type = event.response_type() & !0x80;
+
+		if let Some(randr_first_event) = self.extension_first_event(xcb::randr::id())
 		{
-			Some(event) => event,
-			None => return None,
-		};
+			if response_type == randr_first_event + xcb::randr::SCREEN_CHANGE_NOTIFY
+			{
+				let screen_change: &xcb::randr::ScreenChangeNotifyEvent = unsafe { xcb::cast_event(&event) };
+				return Event::RandrEvent(
+					RandrEvent::ScreenChanged
+					{
+						root  : screen_change.root(),
+						width : screen_change.width(),
+						height: screen_change.height(),
+					}
+				);
+			}
 
-		match event.response_type() & !0x80
+			if response_type == randr_first_event + xcb::randr::NOTIFY
+			{
+				let notify: &xcb::randr::NotifyEvent = unsafe { xcb::cast_event(&event) };
+				return Event::RandrEvent(
+					match notify.sub_code() as u32
+					{
+						xcb::randr::NOTIFY_CRTC_CHANGE =>
+						{
+							let crtc_change = notify.u().cc();
+							RandrEvent::CrtcChanged
+							{
+								crtc  : crtc_change.crtc(),
+								x     : crtc_change.x(),
+								y     : crtc_change.y(),
+								width : crtc_change.width(),
+								height: crtc_change.height(),
+							}
+						},
+						xcb::randr::NOTIFY_OUTPUT_CHANGE =>
+						{
+							let output_change = notify.u().oc();
+							RandrEvent::OutputChanged
+							{
+								output    : output_change.output(),
+								crtc      : output_change.crtc(),
+								connection: output_change.connection(),
+							}
+						},
+						sub_code => RandrEvent::Other(sub_code as u8),
+					}
+				);
+			}
+		}
+
+		if let Some(shape_first_event) = self.extension_first_event(xcb::shape::id())
+		{
+			if response_type == shape_first_event + xcb::shape::NOTIFY
+			{
+				let shape_notify: &xcb::shape::NotifyEvent = unsafe { xcb::cast_event(&event) };
+				return Event::ShapeEvent(
+					ShapeEvent
+					{
+						window: shape_notify.affected_window(),
+						kind  : shape_notify.shape_kind(),
+						x     : shape_notify.extents_x(),
+						y     : shape_notify.extents_y(),
+						width : shape_notify.extents_width(),
+						height: shape_notify.extents_height(),
+						shaped: shape_notify.shaped(),
+					}
+				);
+			}
+		}
+
+		if let Some(screen_saver_first_event) = self.extension_first_event(xcb::screensaver::id())
 		{
-			xcb::EXPOSE => return Some(Event::ExposedEvent),
-			xcb::KEY_PRESS => return Some(Event::KeyEvent(KeyEvent::KeyPress)),
-			xcb::KEY_RELEASE => return Some(Event::KeyEvent(KeyEvent::KeyReleased)),
-			event =>
+			if response_type == screen_saver_first_event + xcb::screensaver::NOTIFY
 			{
-				println!("UNKOWN EVENT {:?}", event);
-				return None;
+				let screen_saver_notify: &xcb::screensaver::NotifyEvent = unsafe { xcb::cast_event(&event) };
+				return Event::ScreenSaverEvent(
+					ScreenSaverEvent
+					{
+						active: screen_saver_notify.state() == xcb::screensaver::STATE_ON as u8,
+						forced: screen_saver_notify.forced(),
+					}
+				);
 			}
+		}
+
+		return match response_type
+		{
+			xcb::VISIBILITY_NOTIFY =>
+			{
+				let visibility_notify: &xcb::VisibilityNotifyEvent = unsafe { xcb::cast_event(&event) };
+				Event::VisibilityEvent(
+					VisibilityEvent
+					{
+						window    : visibility_notify.window(),
+						visibility: match visibility_notify.state() as u32
+						{
+							xcb::VISIBILITY_UNOBSCURED         => Visibility::Unobscured,
+							xcb::VISIBILITY_PARTIALLY_OBSCURED => Visibility::PartiallyObscured,
+							_                                  => Visibility::FullyObscured,
+						},
+					}
+				)
+			},
+			xcb::EXPOSE =>
+			{
+				let expose: &xcb::ExposeEvent = unsafe { xcb::cast_event(&event) };
+				Event::ExposedEvent(
+					ExposedEvent
+					{
+						window: expose.window(),
+						x     : expose.x(),
+						y     : expose.y(),
+						width : expose.width(),
+						height: expose.height(),
+						count : expose.count(),
+					}
+				)
+			},
+			xcb::KEY_PRESS | xcb::KEY_RELEASE =>
+			{
+				let key_event: &xcb::KeyPressEvent = unsafe { xcb::cast_event(&event) };
+				let key = Key
+				{
+					window : key_event.event(),
+					keycode: key_event.detail(),
+					keysym : self.keysym(key_event.detail(), key_event.state()),
+					state  : key_event.state(),
+				};
+
+				if response_type == xcb::KEY_PRESS
+				{
+					Event::KeyEvent(KeyEvent::KeyPress(key))
+				}
+				else
+				{
+					Event::KeyEvent(KeyEvent::KeyReleased(key))
+				}
+			},
+			xcb::BUTTON_PRESS | xcb::BUTTON_RELEASE =>
+			{
+				let button_event: &xcb::ButtonPressEvent = unsafe { xcb::cast_event(&event) };
+				let button = Button
+				{
+					button : button_event.detail(),
+					pointer: PointerEvent
+					{
+						window: button_event.event(),
+						x     : button_event.event_x(),
+						y     : button_event.event_y(),
+						root_x: button_event.root_x(),
+						root_y: button_event.root_y(),
+						state : button_event.state(),
+					},
+				};
+
+				if response_type == xcb::BUTTON_PRESS
+				{
+					Event::ButtonEvent(ButtonEvent::ButtonPress(button))
+				}
+				else
+				{
+					Event::ButtonEvent(ButtonEvent::ButtonReleased(button))
+				}
+			},
+			xcb::MOTION_NOTIFY =>
+			{
+				let motion: &xcb::MotionNotifyEvent = unsafe { xcb::cast_event(&event) };
+				Event::MotionEvent(
+					PointerEvent
+					{
+						window: motion.event(),
+						x     : motion.event_x(),
+						y     : motion.event_y(),
+						root_x: motion.root_x(),
+						root_y: motion.root_y(),
+						state : motion.state(),
+					}
+				)
+			},
+			xcb::ENTER_NOTIFY | xcb::LEAVE_NOTIFY =>
+			{
+				let crossing: &xcb::EnterNotifyEvent = unsafe { xcb::cast_event(&event) };
+				let pointer = PointerEvent
+				{
+					window: crossing.event(),
+					x     : crossing.event_x(),
+					y     : crossing.event_y(),
+					root_x: crossing.root_x(),
+					root_y: crossing.root_y(),
+					state : crossing.state(),
+				};
+
+				if response_type == xcb::ENTER_NOTIFY
+				{
+					Event::CrossingEvent(CrossingEvent::Enter(pointer))
+				}
+				else
+				{
+					Event::CrossingEvent(CrossingEvent::Leave(pointer))
+				}
+			},
+			xcb::CONFIGURE_NOTIFY =>
+			{
+				let configure: &xcb::ConfigureNotifyEvent = unsafe { xcb::cast_event(&event) };
+				Event::ConfigureEvent(
+					ConfigureEvent
+					{
+						window           : configure.window(),
+						x                : configure.x(),
+						y                : configure.y(),
+						width            : configure.width(),
+						height           : configure.height(),
+						border_width     : configure.border_width(),
+						override_redirect: configure.override_redirect(),
+					}
+				)
+			},
+			xcb::PROPERTY_NOTIFY =>
+			{
+				let property: &xcb::PropertyNotifyEvent = unsafe { xcb::cast_event(&event) };
+				Event::PropertyEvent(
+					PropertyEvent
+					{
+						window : property.window(),
+						atom   : property.atom(),
+						deleted: property.state() == xcb::PROPERTY_DELETE as u8,
+					}
+				)
+			},
+			xcb::MAP_NOTIFY =>
+			{
+				let map: &xcb::MapNotifyEvent = unsafe { xcb::cast_event(&event) };
+				Event::MapEvent(MapEvent::Mapped(map.window()))
+			},
+			xcb::UNMAP_NOTIFY =>
+			{
+				let unmap: &xcb::UnmapNotifyEvent = unsafe { xcb::cast_event(&event) };
+				Event::MapEvent(MapEvent::Unmapped(unmap.window()))
+			},
+			xcb::CLIENT_MESSAGE =>
+			{
+				let client_message: &xcb::ClientMessageEvent = unsafe { xcb::cast_event(&event) };
+				let mut data = [0_u32; 5];
+				data.copy_from_slice(client_message.data().data32());
+
+				Event::ClientMessageEvent(
+					ClientMessageEvent
+					{
+						window    : client_message.window(),
+						event_type: client_message.type_(),
+						data,
+					}
+				)
+			},
+			//NOTE(fpalacios): Cambio la distribucion del teclado, el mapa se vuelve a pedir con la proxima tecla
+			xcb::MAPPING_NOTIFY =>
+			{
+				let mapping: &xcb::MappingNotifyEvent = unsafe { xcb::cast_event(&event) };
+				if mapping.request() == xcb::MAPPING_KEYBOARD as u8
+				{
+					self.keyboard_mapping.replace(None);
+				}
+
+				Event::UnknownEvent(response_type as xcb::EventMask, event)
+			},
+			_ => Event::UnknownEvent(response_type as xcb::EventMask, event),
+		};
+	}
+
+	//NOTE(fpalacios): Toma el keysym de la columna que corresponde segun si esta apretado shift, sin tener en cuenta lock ni grupos
+	pub fn keysym(&self, keycode: xcb::Keycode, state: u16) -> xcb::Keysym
+	{
+		let mut keyboard_mapping = self.keyboard_mapping.borrow_mut();
+
+		if keyboard_mapping.is_none()
+		{
+			*keyboard_mapping = self.fetch_keyboard_mapping();
+		}
+
+		let mapping = match keyboard_mapping.as_ref()
+		{
+			Some(mapping) if keycode >= mapping.min_keycode => mapping,
+			_                                               => return 0,
+		};
+
+		let start   = (keycode - mapping.min_keycode) as usize * mapping.keysyms_per_keycode;
+		let keysyms = match mapping.keysyms.get(start .. start + mapping.keysyms_per_keycode)
+		{
+			Some(keysyms) => keysyms,
+			None          => return 0,
 		};
+
+		let column = if state & xcb::MOD_MASK_SHIFT as u16 != 0 { 1 } else { 0 };
+
+		return match keysyms.get(column)
+		{
+			Some(keysym) if *keysym != 0 => *keysym,
+			_                            => keysyms.first().cloned().unwrap_or(0),
+		};
+	}
+
+	fn fetch_keyboard_mapping(&self) -> Option<KeyboardMapping>
+	{
+		let setup       = self.conn.get_setup();
+		let min_keycode = setup.min_keycode();
+		let count       = setup.max_keycode() - min_keycode + 1;
+
+		let mapping = xcb::get_keyboard_mapping(self.conn, min_keycode, count).get_reply().ok()?;
+
+		return Some(
+			KeyboardMapping
+			{
+				min_keycode,
+				keysyms_per_keycode: mapping.keysyms_per_keycode() as usize,
+				keysyms            : mapping.keysyms().to_vec(),
+			}
+		);
 	}
 
 	pub fn send_message(&self, destination: &Window, event: Event)
 	{
 		match event
 		{
-			Event::ClientMessageEvent {window, event_type, data , ..} =>
+			Event::ClientMessageEvent(ClientMessageEvent { window, event_type, data }) =>
 			{
 				let message_data = xcb::ffi::xproto::xcb_client_message_data_t::from_data32(data);
 
@@ -133,7 +717,7 @@ impl<'conn> Client<'conn>
 				);
 
 				xcb::send_event_checked(
-					&self.conn,
+					self.conn,
 					false,
 					destination.id,
 					xcb::EVENT_MASK_SUBSTRUCTURE_REDIRECT,
@@ -149,9 +733,45 @@ impl<'conn> Client<'conn>
 		self.flush().unwrap();
 	}
 
-	pub fn flush(&self) -> Result<(), ()>
+	//NOTE(fpalacios): Los eventos de las extensiones no tienen un response_type fijo, el server le asigna un rango a cada una
+	pub fn extension_first_event(&self, extension: &mut xcb::Extension) -> Option<u8>
+	{
+		let extension_data = self.conn.get_extension_data(extension)?;
+		return if extension_data.present() { Some(extension_data.first_event()) } else { None };
+	}
+
+	//NOTE(fpalacios): Un mensaje _NET_WM_STATE lleva como maximo dos estados, asi que se mandan de a pares
+	pub fn send_wm_states(&self, root: &Window, window: WindowID, action: WmStateAction, states: &[AtomID])
+	{
+		let net_wm_state = self.find_atom_id_by_name("_NET_WM_STATE").unwrap();
+
+		for pair in states.chunks(2)
+		{
+			self.send_message(
+				root,
+				Event::ClientMessageEvent(
+					ClientMessageEvent
+					{
+						window,
+						event_type: net_wm_state,
+						data:
+						[
+							action as u32,
+							pair[0],
+							if pair.len() > 1 { pair[1] } else { 0 },
+							//NOTE(fpalacios): Indica que el pedido viene de una aplicacion normal
+							1,
+							0,
+						],
+					}
+				)
+			);
+		}
+	}
+
+	pub fn flush(&self) -> Result<(), Error>
 	{
-		return if self.conn.flush() { Ok(()) } else { Err(()) };
+		return if self.conn.flush() { Ok(()) } else { Err(Error { error_code: 0 }) };
 	}
 
 	fn generate_id(&self) -> u32
@@ -183,7 +803,7 @@ impl<'client, 'conn> Screen<'client, 'conn>
 		);
 	}
 
-	pub fn root_window(&self) -> Window
+	pub fn root_window(&self) -> Window<'_, 'client, 'conn>
 	{
 		return Window
 		{
@@ -192,6 +812,131 @@ impl<'client, 'conn> Screen<'client, 'conn>
 		};
 	}
 
+	pub fn monitors(&self) -> Result<Vec<Monitor>, Error>
+	{
+		let conn = &self.client.conn;
+		let root = self.xcb_screen.root();
+
+		let resources = match xcb::randr::get_screen_resources_current(conn, root).get_reply()
+		{
+			Ok(resources) => resources,
+			Err(err)      => return Err(Error{ error_code: err.error_code() }),
+		};
+
+		let primary_output = match xcb::randr::get_output_primary(conn, root).get_reply()
+		{
+			Ok(primary) => primary.output(),
+			Err(_)      => xcb::NONE,
+		};
+
+		let mut monitors = Vec::with_capacity(resources.outputs().len());
+
+		for output in resources.outputs()
+		{
+			let output_info = match xcb::randr::get_output_info(conn, *output, resources.config_timestamp()).get_reply()
+			{
+				Ok(output_info) => output_info,
+				Err(err)        => return Err(Error{ error_code: err.error_code() }),
+			};
+
+			//NOTE(fpalacios): Las salidas desconectadas o apagadas no tienen crtc
+			if output_info.connection() != xcb::randr::CONNECTION_CONNECTED as u8 || output_info.crtc() == xcb::NONE
+			{
+				continue;
+			}
+
+			let crtc_info = match xcb::randr::get_crtc_info(conn, output_info.crtc(), resources.config_timestamp()).get_reply()
+			{
+				Ok(crtc_info) => crtc_info,
+				Err(err)      => return Err(Error{ error_code: err.error_code() }),
+			};
+
+			monitors.push(
+				Monitor
+				{
+					output : *output,
+					name   : String::from_utf8_lossy(output_info.name()).into_owned(),
+					primary: *output == primary_output,
+					x      : crtc_info.x(),
+					y      : crtc_info.y(),
+					width  : crtc_info.width(),
+					height : crtc_info.height(),
+				}
+			);
+		}
+
+		return Ok(monitors);
+	}
+
+	pub fn select_monitor_changes(&self) -> Result<(), Error>
+	{
+		if let Err(err) = xcb::randr::select_input_checked(
+			self.client.conn,
+			self.xcb_screen.root(),
+			(
+				xcb::randr::NOTIFY_MASK_SCREEN_CHANGE |
+				xcb::randr::NOTIFY_MASK_CRTC_CHANGE   |
+				xcb::randr::NOTIFY_MASK_OUTPUT_CHANGE
+			) as u16
+		).request_check()
+		{
+			return Err(Error{ error_code: err.error_code() });
+		}
+
+		return self.client.flush();
+	}
+
+	pub fn select_screen_saver_changes(&self) -> Result<(), Error>
+	{
+		if let Err(err) = xcb::screensaver::select_input_checked(
+			self.client.conn,
+			self.xcb_screen.root(),
+			xcb::screensaver::EVENT_NOTIFY_MASK
+		).request_check()
+		{
+			return Err(Error{ error_code: err.error_code() });
+		}
+
+		return self.client.flush();
+	}
+
+	pub fn screen_saver_info(&self) -> Result<ScreenSaverInfo, Error>
+	{
+		return match xcb::screensaver::query_info(self.client.conn, self.xcb_screen.root()).get_reply()
+		{
+			Ok(info) => Ok(
+				ScreenSaverInfo
+				{
+					active   : info.state() == xcb::screensaver::STATE_ON as u8,
+					idle_time: std::time::Duration::from_millis(info.ms_since_user_input() as u64),
+				}
+			),
+			Err(err) => Err(Error{ error_code: err.error_code() }),
+		};
+	}
+
+	//NOTE(fpalacios): Busca un visual TrueColor de 32 bits para poder tener ventanas con transparencia
+	pub fn find_argb_visual(&self) -> Option<VisualID>
+	{
+		for depth in self.xcb_screen.allowed_depths()
+		{
+			if depth.depth() != 32
+			{
+				continue;
+			}
+
+			for visual in depth.visuals()
+			{
+				if visual.class() == xcb::VISUAL_CLASS_TRUE_COLOR as u8
+				{
+					return Some(visual.visual_id());
+				}
+			}
+		}
+
+		return None;
+	}
+
 	pub fn get_black_pixel(&self) -> Color
 	{
 		return self.xcb_screen.black_pixel();
@@ -212,14 +957,14 @@ impl<'screen, 'client, 'conn> Window<'screen, 'client, 'conn>
 {
 	pub fn children(&self) -> Vec<Window<'screen, 'client, 'conn>>
 	{
-		let tree = xcb::query_tree(&self.screen.client.conn, self.id).get_reply().unwrap();
+		let tree = xcb::query_tree(self.screen.client.conn, self.id).get_reply().unwrap();
 		let children = tree.children();
 
 		let mut result = Vec::with_capacity(children.len());
 
 		for child in children
 		{
-			result.push(Window { screen: self.screen, id: child.clone()});
+			result.push(Window { screen: self.screen, id: *child});
 		}
 
 		return result;
@@ -227,41 +972,78 @@ impl<'screen, 'client, 'conn> Window<'screen, 'client, 'conn>
 
 	pub fn get_property(&self, atom: AtomID) -> Result<Property, Error>
 	{
-		let property = match xcb::get_property(
-			&self.screen.client.conn,
-			false,
-			self.id,
-			atom,
-			xcb::ATOM_ANY,
-			0,
-			1024
-		).get_reply()
+		let mut data        = Vec::new();
+		let mut long_offset = 0_u32;
+		let type_atom;
+		let format;
+
+		//NOTE(fpalacios): Se pide de a 1024 palabras de 32 bits hasta que bytes_after dice que no queda nada
+		loop
 		{
-			Ok(property) => property,
-			Err(err)     =>
+			let property = match xcb::get_property(
+				self.screen.client.conn,
+				false,
+				self.id,
+				atom,
+				xcb::ATOM_ANY,
+				long_offset,
+				1024
+			).get_reply()
 			{
-				return Err(
-					Error
-					{
-						error_code: err.error_code()
-					}
-				);
+				Ok(property) => property,
+				Err(err)     =>
+				{
+					return Err(
+						Error
+						{
+							error_code: err.error_code()
+						}
+					);
+				}
+			};
+
+			let chunk = property.value::<u8>();
+			data.extend_from_slice(chunk);
+			long_offset += (chunk.len() / 4) as u32;
+
+			if property.bytes_after() == 0 || chunk.is_empty()
+			{
+				type_atom = property.type_();
+				format    = property.format();
+				break;
 			}
-		};
+		}
 
-		let value = match property.type_()
+		let value = match type_atom
 		{
-			xcb::ATOM_STRING   => PropertyValue::String((*String::from_utf8_lossy(property.value::<u8>())).to_owned()),
-			xcb::ATOM_INTEGER  => PropertyValue::I32(property.value::<i32>()[0]),
+			xcb::ATOM_STRING   => PropertyValue::String((*String::from_utf8_lossy(&data)).to_owned()),
 			xcb::ATOM_NONE     => PropertyValue::None,
-			xcb::ATOM_ATOM     => PropertyValue::Atom(property.value::<u32>()[0]),
-			xcb::ATOM_CARDINAL => PropertyValue::U32(property.value::<u32>()[0]),
+			xcb::ATOM_INTEGER if format == 32 =>
+			{
+				let mut values: Vec<i32> = bytes_to_u32s(&data).into_iter().map(|value| value as i32).collect();
+				if values.len() == 1 { PropertyValue::I32(values.remove(0)) } else { PropertyValue::I32List(values) }
+			},
+			xcb::ATOM_CARDINAL if format == 32 =>
+			{
+				let mut values = bytes_to_u32s(&data);
+				if values.len() == 1 { PropertyValue::U32(values.remove(0)) } else { PropertyValue::U32List(values) }
+			},
+			xcb::ATOM_ATOM if format == 32 =>
+			{
+				let mut values = bytes_to_u32s(&data);
+				if values.len() == 1 { PropertyValue::Atom(values.remove(0)) } else { PropertyValue::AtomList(values) }
+			},
+			xcb::ATOM_WINDOW if format == 32 =>
+			{
+				let mut values = bytes_to_u32s(&data);
+				if values.len() == 1 { PropertyValue::Window(values.remove(0)) } else { PropertyValue::WindowList(values) }
+			},
 			unknown_atom =>
 			{
 				match self.screen.client.find_atom_name(unknown_atom).as_ref()
 				{
-					"UTF8_STRING" => PropertyValue::String((*String::from_utf8_lossy(property.value::<u8>())).to_owned()),
-					_             => PropertyValue::UnknownAtom(unknown_atom)
+					"UTF8_STRING" => PropertyValue::String((*String::from_utf8_lossy(&data)).to_owned()),
+					_             => PropertyValue::Raw { type_atom_id: unknown_atom, format, data }
 				}
 			}
 		};
@@ -278,7 +1060,7 @@ impl<'screen, 'client, 'conn> Window<'screen, 'client, 'conn>
 			PropertyValue::String(val) =>
 			{
 				xcb::change_property(
-					&self.screen.client.conn,
+					self.screen.client.conn,
 					xcb::PROP_MODE_REPLACE as u8,
 					self.id,
 					property.key,
@@ -287,46 +1069,58 @@ impl<'screen, 'client, 'conn> Window<'screen, 'client, 'conn>
 					val.as_bytes()
 				);
 			},
-			PropertyValue::Atom(val) =>
+			PropertyValue::Atom(val) | PropertyValue::U32(val) | PropertyValue::Window(val) =>
 			{
 				xcb::change_property(
-					&self.screen.client.conn,
+					self.screen.client.conn,
 					xcb::PROP_MODE_REPLACE as u8,
 					self.id,
 					property.key,
 					atom_type,
 					32,
-					&[val.clone()]
+					&[*val]
 				);
 			},
 			PropertyValue::I32(val) =>
 			{
 				xcb::change_property(
-					&self.screen.client.conn,
+					self.screen.client.conn,
 					xcb::PROP_MODE_REPLACE as u8,
 					self.id,
 					property.key,
 					atom_type,
 					32,
-					&[val.clone()]
+					&[*val]
 				);
 			},
-			PropertyValue::U32(val) =>
+			PropertyValue::AtomList(values) | PropertyValue::U32List(values) | PropertyValue::WindowList(values) =>
 			{
 				xcb::change_property(
-					&self.screen.client.conn,
+					self.screen.client.conn,
 					xcb::PROP_MODE_REPLACE as u8,
 					self.id,
 					property.key,
 					atom_type,
 					32,
-					&[val.clone()]
+					values
+				);
+			},
+			PropertyValue::I32List(values) =>
+			{
+				xcb::change_property(
+					self.screen.client.conn,
+					xcb::PROP_MODE_REPLACE as u8,
+					self.id,
+					property.key,
+					atom_type,
+					32,
+					values
 				);
 			},
 			PropertyValue::None =>
 			{
 				xcb::change_property(
-					&self.screen.client.conn,
+					self.screen.client.conn,
 					xcb::PROP_MODE_REPLACE as u8,
 					self.id,
 					property.key,
@@ -335,17 +1129,49 @@ impl<'screen, 'client, 'conn> Window<'screen, 'client, 'conn>
 					&[xcb::ATOM_NONE]
 				);
 			},
-			PropertyValue::UnknownAtom(_) =>
+			//NOTE(fpalacios): change_property cuenta elementos y no bytes, asi que hay que pasarle el tipo que corresponde al formato
+			PropertyValue::Raw { format: 32, data, .. } =>
+			{
+				xcb::change_property(
+					self.screen.client.conn,
+					xcb::PROP_MODE_REPLACE as u8,
+					self.id,
+					property.key,
+					atom_type,
+					32,
+					&bytes_to_u32s(data)
+				);
+			},
+			PropertyValue::Raw { format: 16, data, .. } =>
+			{
+				xcb::change_property(
+					self.screen.client.conn,
+					xcb::PROP_MODE_REPLACE as u8,
+					self.id,
+					property.key,
+					atom_type,
+					16,
+					&bytes_to_u16s(data)
+				);
+			},
+			PropertyValue::Raw { data, .. } =>
 			{
-				//TODO(fpalacios): Que hacemo acá?
-				panic!("Que hacemo acá?");
+				xcb::change_property(
+					self.screen.client.conn,
+					xcb::PROP_MODE_REPLACE as u8,
+					self.id,
+					property.key,
+					atom_type,
+					8,
+					data
+				);
 			},
 		};
 	}
 
 	pub fn geometry(&self) -> (i16, i16, u16, u16)
 	{
-		let geometry = match xcb::get_geometry(&self.screen.client.conn, self.id).get_reply()
+		let geometry = match xcb::get_geometry(self.screen.client.conn, self.id).get_reply()
 		{
 			Ok(geomerty) => geomerty,
 			Err(error) =>
@@ -358,9 +1184,48 @@ impl<'screen, 'client, 'conn> Window<'screen, 'client, 'conn>
 		return (geometry.x(), geometry.y(), geometry.width(), geometry.height());
 	}
 
+	//NOTE(fpalacios): La x e y de geometry son relativas al padre, que puede ser el marco que le pone el window manager
+	pub fn root_position(&self) -> Option<(i16, i16)>
+	{
+		return xcb::translate_coordinates(self.screen.client.conn, self.id, self.screen.xcb_screen.root(), 0, 0)
+			.get_reply()
+			.ok()
+			.map(|reply| (reply.dst_x(), reply.dst_y()));
+	}
+
+	//NOTE(fpalacios): Cada cliente tiene su propia mascara de eventos por ventana, esto no pisa la de glutin
+	pub fn select_events(&self, event_mask: EventKeyID) -> Result<(), Error>
+	{
+		if let Err(err) = xcb::change_window_attributes_checked(
+			self.screen.client.conn,
+			self.id,
+			&[(xcb::CW_EVENT_MASK, event_mask)]
+		).request_check()
+		{
+			return Err(Error{ error_code: err.error_code() });
+		}
+
+		return self.screen.client.flush();
+	}
+
+	pub fn configure(&self, (x, y, width, height): (i16, i16, u16, u16))
+	{
+		xcb::configure_window(
+			self.screen.client.conn,
+			self.id,
+			&[
+				(xcb::CONFIG_WINDOW_X as u16, x as u32),
+				(xcb::CONFIG_WINDOW_Y as u16, y as u32),
+				(xcb::CONFIG_WINDOW_WIDTH as u16, width as u32),
+				(xcb::CONFIG_WINDOW_HEIGHT as u16, height as u32),
+			]
+		);
+		self.screen.client.flush().unwrap();
+	}
+
 	pub fn map(&self)
 	{
-		xcb::map_window(&self.screen.client.conn, self.id);
+		xcb::map_window(self.screen.client.conn, self.id);
 		self.screen.client.flush().unwrap();
 	}
 
@@ -406,7 +1271,7 @@ impl<'screen, 'client, 'conn> Window<'screen, 'client, 'conn>
 		};
 
 		if let Err(e) = xcb::create_window_checked(
-			&self.screen.client.conn,
+			self.screen.client.conn,
 			depth,
 			child_id,
 			self.id,
@@ -444,12 +1309,26 @@ pub struct GraphicsContext<'client, 'conn>
 
 impl<'client, 'conn> GraphicsContext<'client, 'conn>
 {
+	pub fn from_id(client: &'client Client<'conn>, id: GraphicsContextID) -> GraphicsContext<'client, 'conn>
+	{
+		return GraphicsContext
+		{
+			client,
+			id,
+		};
+	}
+
+	pub fn id(&self) -> GraphicsContextID
+	{
+		return self.id;
+	}
+
 	pub fn generate(window: &Window<'_, 'client, 'conn>, foreground: Color, background: Color) -> GraphicsContext<'client, 'conn>
 	{
 		let id = window.screen.client.generate_id();
 
 		xcb::create_gc_checked(
-			&window.screen.client.conn,
+			window.screen.client.conn,
 			id,
 			window.id,
 			&[
@@ -471,61 +1350,69 @@ impl<'client, 'conn> GraphicsContext<'client, 'conn>
 
 	pub fn draw_rects(&self, window: &'conn Window, rectangles: &[xcb::Rectangle])
 	{
-		xcb::poly_rectangle(&self.client.conn, window.id, self.id, &rectangles);
+		xcb::poly_rectangle(self.client.conn, window.id, self.id, rectangles);
 	}
 
 	pub fn fill_rects(&self, window: &'conn Window, rectangles: &[xcb::Rectangle])
 	{
-		xcb::poly_fill_rectangle(&self.client.conn, window.id, self.id, &rectangles);
+		xcb::poly_fill_rectangle(self.client.conn, window.id, self.id, rectangles);
 	}
 
-	//TODO(fpalacios): Ver como pintar una imagen en xcb puro
-	pub fn draw_image(
+	//NOTE(fpalacios): Manda un buffer BGRA premultiplicado de 32 bits por pixel. Cada request tiene un tamaño maximo asi que
+	//se parte en tiras horizontales que entren
+	pub fn put_argb32(
 		&self,
 		drawable: DrawableID,
-		_image: &image::RgbaImage,
+		(width, height): (u16, u16),
 		(x, y): (i16, i16),
+		data: &[u8],
 	)
 	{
+		assert_eq!(data.len(), width as usize * height as usize * 4, "El buffer no mide {}x{} pixeles de 4 bytes", width, height);
 
-		//NOTE(fpalacios): Generar y llear este array es para una prueba puntal de ahora
-		let mut a = [0_u8; 100 * 100 * 4];
-
-		for i in (0 .. 100).step_by(4)
+		if width == 0 || height == 0
 		{
-			a[i + 0] = 0x00;
-			a[i + 1] = 0xFF;
-			a[i + 2] = 0xFF;
-			a[i + 3] = 0x00;
+			return;
 		}
 
-		if let Err(e) = xcb::put_image_checked(
-			&self.client.conn,
-			xcb::IMAGE_FORMAT_Z_PIXMAP as u8,
-			drawable,
-			self.id,
-			100,
-			100,
-			x,
-			y,
-			0,
-			32,
-			&a,
-		).request_check()
+		//NOTE(fpalacios): El maximo viene en unidades de 4 bytes, el encabezado de PutImage ocupa 24 bytes
+		let max_request_bytes = self.client.conn.get_maximum_request_length() as usize * 4;
+		let row_bytes         = width as usize * 4;
+		let rows_per_request  = std::cmp::max((max_request_bytes - 24) / row_bytes, 1);
+
+		for (chunk_index, chunk) in data.chunks(rows_per_request * row_bytes).enumerate()
 		{
-			println!("Error al pintar la imagen {:?}", e);
+			let chunk_rows = (chunk.len() / row_bytes) as u16;
+			let chunk_y    = y + (chunk_index * rows_per_request) as i16;
+
+			xcb::put_image(
+				self.client.conn,
+				xcb::IMAGE_FORMAT_Z_PIXMAP as u8,
+				drawable,
+				self.id,
+				width,
+				chunk_rows,
+				x,
+				chunk_y,
+				0,
+				32,
+				chunk,
+			);
 		}
+
+		//NOTE(fpalacios): Sin request_check no se espera la respuesta, si el server rechaza algo llega como evento de error
+		self.client.flush().unwrap();
 	}
 
 	pub fn clear_window(&self, window: &'conn Window)
 	{
 		let (x, y, width, height) = window.geometry();
-		xcb::clear_area(&self.client.conn, true, window.id, x, y, width, height);
+		xcb::clear_area(self.client.conn, true, window.id, x, y, width, height);
 	}
 
 	pub fn clear_area(&self, window: &'conn Window, (x, y, width, height): (i16, i16, u16, u16))
 	{
-		xcb::clear_area(&self.client.conn, true, window.id, x, y, width, height);
+		xcb::clear_area(self.client.conn, true, window.id, x, y, width, height);
 	}
 }
 
@@ -543,7 +1430,7 @@ impl<'client, 'conn> ColorMap<'client, 'conn>
 		let id = client.generate_id();
 
 		xcb::create_colormap_checked(
-			&client.conn,
+			client.conn,
 			xcb::COLORMAP_ALLOC_NONE as u8,
 			id,
 			window.id,
@@ -574,7 +1461,7 @@ impl<'client, 'conn> PixMap<'client, 'conn>
 		let id = client.generate_id();
 
 		xcb::create_pixmap_checked(
-			&client.conn,
+			client.conn,
 			32,
 			id,
 			drawable,
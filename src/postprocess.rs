@@ -0,0 +1,441 @@
+//NOTE(fpalacios): Pases de pantalla completa que se aplican despues de dibujar la escena. Se configuran en config.toml:
+//[[theme.postprocess]]
+//type = "glow"
+//threshold = 0.7
+//y se aplican en el orden en que aparecen
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(try_from = "PassTable")]
+pub enum PostProcessPass
+{
+	ColorGrading
+	{
+		//NOTE(fpalacios): Tira de N cuadrados de NxN (ej: 256x16), relativa al archivo de configuracion
+		lut     : std::path::PathBuf,
+		strength: f32,
+	},
+	Glow
+	{
+		//NOTE(fpalacios): Brillo a partir del cual un pixel empieza a brillar, los bichitos de luz de noche
+		threshold: f32,
+		//NOTE(fpalacios): En pixeles
+		radius   : f32,
+		intensity: f32,
+	},
+	Vignette
+	{
+		strength: f32,
+		//NOTE(fpalacios): Distancia al centro donde empieza a oscurecer, 1.0 es la esquina
+		radius  : f32,
+		softness: f32,
+	},
+	Pixelate
+	{
+		pixel_size: f32,
+	},
+}
+
+impl PostProcessPass
+{
+	pub fn fragment_shader(&self) -> &'static str
+	{
+		return match self
+		{
+			PostProcessPass::ColorGrading { .. } => crate::shaders::COLOR_GRADING_FRAGMENT_SHADER,
+			PostProcessPass::Glow { .. }         => crate::shaders::GLOW_FRAGMENT_SHADER,
+			PostProcessPass::Vignette { .. }     => crate::shaders::VIGNETTE_FRAGMENT_SHADER,
+			PostProcessPass::Pixelate { .. }     => crate::shaders::PIXELATE_FRAGMENT_SHADER,
+		};
+	}
+}
+
+//NOTE(fpalacios): Un [[theme.postprocess]] tal cual esta en el archivo. Los parametros son opcionales, los valores por
+//defecto y los rangos estan en el TryFrom
+#[derive(serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum PassTable
+{
+	ColorGrading
+	{
+		lut     : std::path::PathBuf,
+		strength: Option<f32>,
+	},
+	Glow
+	{
+		threshold: Option<f32>,
+		radius   : Option<f32>,
+		intensity: Option<f32>,
+	},
+	Vignette
+	{
+		strength: Option<f32>,
+		radius  : Option<f32>,
+		softness: Option<f32>,
+	},
+	Pixelate
+	{
+		pixel_size: Option<f32>,
+	},
+}
+
+impl std::convert::TryFrom<PassTable> for PostProcessPass
+{
+	type Error = String;
+
+	fn try_from(pass: PassTable) -> Result<PostProcessPass, String>
+	{
+		return Ok(
+			match pass
+			{
+				PassTable::ColorGrading { lut, strength } => PostProcessPass::ColorGrading
+				{
+					lut,
+					strength: number(strength, "color_grading.strength", 1.0, 0.0, 1.0)?,
+				},
+				PassTable::Glow { threshold, radius, intensity } => PostProcessPass::Glow
+				{
+					//NOTE(fpalacios): El shader usa smoothstep(threshold, 1.0, ...) que no esta definido con threshold >= 1
+					threshold: number(threshold, "glow.threshold", 0.7, 0.0, 0.99)?,
+					radius   : number(radius,    "glow.radius",    6.0, 0.0, 64.0)?,
+					intensity: number(intensity, "glow.intensity", 0.8, 0.0, 8.0)?,
+				},
+				PassTable::Vignette { strength, radius, softness } => PostProcessPass::Vignette
+				{
+					strength: number(strength, "vignette.strength", 0.5,  0.0, 1.0)?,
+					radius  : number(radius,   "vignette.radius",   0.6,  0.0, 1.0)?,
+					softness: number(softness, "vignette.softness", 0.45, 0.0, 2.0)?,
+				},
+				PassTable::Pixelate { pixel_size } => PostProcessPass::Pixelate
+				{
+					pixel_size: number(pixel_size, "pixelate.pixel_size", 4.0, 1.0, 256.0)?,
+				},
+			}
+		);
+	}
+}
+
+#[derive(Debug)]
+pub enum PostProcessError
+{
+	Invalid(String),
+	Lut
+	{
+		path : std::path::PathBuf,
+		error: image::ImageError,
+	},
+	Shader(crate::shaders::ShaderError),
+	Texture(String),
+}
+
+//NOTE(fpalacios): nan no pasa el rango porque las comparaciones con nan son siempre falsas
+fn number(value: Option<f32>, name: &str, default: f32, min: f32, max: f32) -> Result<f32, String>
+{
+	let value = value.unwrap_or(default);
+
+	if !(value >= min && value <= max)
+	{
+		return Err(format!("{} tiene que ser un numero entre {} y {}, es {}", name, min, max, value));
+	}
+
+	return Ok(value);
+}
+
+//NOTE(fpalacios): TEXTURE_VERTEX_SHADER transforma el quad, el de los pases ya cubre la pantalla
+const FULLSCREEN_TRANSFORM: [[f32; 3]; 3] =
+[
+	[1.0, 0.0, 0.0],
+	[0.0, 1.0, 0.0],
+	[0.0, 0.0, 1.0],
+];
+
+struct CompiledPass
+{
+	pass   : PostProcessPass,
+	program: glium::Program,
+	lut    : Option<glium::texture::Texture2d>,
+}
+
+//NOTE(fpalacios): La escena se dibuja en scene y cada pase lee el resultado del anterior. Los intermedios van alternando
+//entre dos texturas y el pase de salida escribe en el destino final. Sin pases queda solo el de salida, asi que
+//GliumRenderer siempre tiene uno
+pub struct PostProcessor
+{
+	passes        : Vec<CompiledPass>,
+	//NOTE(fpalacios): Ver OUTPUT_FRAGMENT_SHADER, el destino final no es sRGB
+	output_program: glium::Program,
+	quad          : glium::VertexBuffer<crate::renderer::Vertex2D>,
+	quad_indices  : glium::IndexBuffer<u16>,
+	//NOTE(fpalacios): sRGB para no perder precision en los oscuros, se guarda codificado y los shaders leen en lineal. Asi
+	//el blending de la escena tambien es en lineal
+	scene         : glium::texture::SrgbTexture2d,
+	depth_buffer  : glium::framebuffer::DepthRenderBuffer,
+	intermediate  : Vec<glium::texture::SrgbTexture2d>,
+}
+
+impl PostProcessor
+{
+	pub fn new(
+		facade         : &impl glium::backend::Facade,
+		shader_source  : &crate::shaders::ShaderSource,
+		glsl_version   : &crate::shaders::GlslVersion,
+		passes         : Vec<PostProcessPass>,
+		(width, height): (u32, u32)
+	)
+	-> Result<PostProcessor, PostProcessError>
+	{
+		let mut compiled_passes = Vec::new();
+
+		for pass in passes
+		{
+			let program = shader_source.compile(facade, glsl_version, crate::shaders::TEXTURE_VERTEX_SHADER, pass.fragment_shader())
+				.map_err(PostProcessError::Shader)?;
+
+			let lut = match &pass
+			{
+				PostProcessPass::ColorGrading { lut, .. } => Some(load_lut(facade, lut)?),
+				_                                         => None,
+			};
+
+			compiled_passes.push(
+				CompiledPass
+				{
+					pass,
+					program,
+					lut,
+				}
+			);
+		}
+
+		let output_program = shader_source.compile_encoded(facade, glsl_version, crate::shaders::TEXTURE_VERTEX_SHADER, crate::shaders::OUTPUT_FRAGMENT_SHADER)
+			.map_err(PostProcessError::Shader)?;
+
+		let (scene, depth_buffer, intermediate) = create_targets(facade, compiled_passes.len(), width, height)?;
+
+		return Ok(
+			PostProcessor
+			{
+				passes        : compiled_passes,
+				output_program,
+				quad          : glium::VertexBuffer::new(
+					facade,
+					&[
+						crate::renderer::Vertex2D { position: [-1.0, -1.0], texture_coords: [0.0, 0.0] },
+						crate::renderer::Vertex2D { position: [ 1.0, -1.0], texture_coords: [1.0, 0.0] },
+						crate::renderer::Vertex2D { position: [ 1.0,  1.0], texture_coords: [1.0, 1.0] },
+						crate::renderer::Vertex2D { position: [-1.0,  1.0], texture_coords: [0.0, 1.0] },
+					]
+				).map_err(|err| PostProcessError::Texture(format!("{:?}", err)))?,
+				quad_indices  : glium::IndexBuffer::new(
+					facade,
+					glium::index::PrimitiveType::TrianglesList,
+					&[
+						0_u16, 1_u16, 2_u16,
+						2_u16, 3_u16, 0_u16,
+					]
+				).map_err(|err| PostProcessError::Texture(format!("{:?}", err)))?,
+				scene,
+				depth_buffer,
+				intermediate,
+			}
+		);
+	}
+
+	//NOTE(fpalacios): Igual que GliumRenderer::reload_shaders, si un pase no compila se queda con el programa anterior
+	pub fn reload_shaders(&mut self, facade: &impl glium::backend::Facade, shader_source: &crate::shaders::ShaderSource, glsl_version: &crate::shaders::GlslVersion)
+	{
+		for compiled_pass in &mut self.passes
+		{
+			match shader_source.compile(facade, glsl_version, crate::shaders::TEXTURE_VERTEX_SHADER, compiled_pass.pass.fragment_shader())
+			{
+				Ok(program) => compiled_pass.program = program,
+				Err(err)    => println!("{}", err),
+			}
+		}
+
+		match shader_source.compile_encoded(facade, glsl_version, crate::shaders::TEXTURE_VERTEX_SHADER, crate::shaders::OUTPUT_FRAGMENT_SHADER)
+		{
+			Ok(program) => self.output_program = program,
+			Err(err)    => println!("{}", err),
+		}
+	}
+
+	pub fn dimensions(&self) -> (u32, u32)
+	{
+		return self.scene.dimensions();
+	}
+
+	pub fn resize(&mut self, facade: &impl glium::backend::Facade, width: u32, height: u32) -> Result<(), PostProcessError>
+	{
+		let (scene, depth_buffer, intermediate) = create_targets(facade, self.passes.len(), width, height)?;
+
+		self.scene        = scene;
+		self.depth_buffer = depth_buffer;
+		self.intermediate = intermediate;
+
+		return Ok(());
+	}
+
+	//NOTE(fpalacios): Donde se dibuja la escena antes de aplicar los pases
+	pub fn scene_framebuffer<'post>(&'post self, facade: &impl glium::backend::Facade) -> glium::framebuffer::SimpleFrameBuffer<'post>
+	{
+		return glium::framebuffer::SimpleFrameBuffer::with_depth_buffer(facade, &self.scene, &self.depth_buffer).unwrap();
+	}
+
+	//NOTE(fpalacios): output es la ventana o la textura de offscreen, las dos sin sRGB
+	pub fn apply(&self, facade: &impl glium::backend::Facade, output: &mut impl glium::Surface)
+	{
+		let mut input = &self.scene;
+
+		for (index, compiled_pass) in self.passes.iter().enumerate()
+		{
+			let texture = &self.intermediate[index % 2];
+			let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(facade, texture).unwrap();
+			self.draw_pass(compiled_pass, input, &mut framebuffer);
+			input = texture;
+		}
+
+		//NOTE(fpalacios): Pixel a pixel, la escena mide lo mismo que el destino
+		let uniforms = glium::uniform!
+		{
+			texture2d: input.sampled()
+				.minify_filter(glium::uniforms::MinifySamplerFilter::Nearest)
+				.magnify_filter(glium::uniforms::MagnifySamplerFilter::Nearest),
+			transform: FULLSCREEN_TRANSFORM,
+		};
+		self.draw(output, &self.output_program, &uniforms);
+	}
+
+	fn draw_pass(&self, compiled_pass: &CompiledPass, input: &glium::texture::SrgbTexture2d, target: &mut impl glium::Surface)
+	{
+		let (width, height) = input.dimensions();
+		let texel_size = [1.0 / width as f32, 1.0 / height as f32];
+
+		let input = input.sampled()
+			.wrap_function(glium::uniforms::SamplerWrapFunction::Clamp)
+			.minify_filter(glium::uniforms::MinifySamplerFilter::Linear)
+			.magnify_filter(glium::uniforms::MagnifySamplerFilter::Linear);
+
+		match (&compiled_pass.pass, &compiled_pass.lut)
+		{
+			(PostProcessPass::ColorGrading { strength, .. }, Some(lut)) =>
+			{
+				let uniforms = glium::uniform!
+				{
+					texture2d: input,
+					transform: FULLSCREEN_TRANSFORM,
+					lut      : lut.sampled()
+						.wrap_function(glium::uniforms::SamplerWrapFunction::Clamp)
+						.minify_filter(glium::uniforms::MinifySamplerFilter::Linear)
+						.magnify_filter(glium::uniforms::MagnifySamplerFilter::Linear),
+					lut_size : lut.height() as f32,
+					strength : *strength,
+				};
+				self.draw(target, &compiled_pass.program, &uniforms);
+			},
+			(PostProcessPass::Glow { threshold, radius, intensity }, _) =>
+			{
+				let uniforms = glium::uniform!
+				{
+					texture2d : input,
+					transform : FULLSCREEN_TRANSFORM,
+					texel_size: texel_size,
+					threshold : *threshold,
+					radius    : *radius,
+					intensity : *intensity,
+				};
+				self.draw(target, &compiled_pass.program, &uniforms);
+			},
+			(PostProcessPass::Vignette { strength, radius, softness }, _) =>
+			{
+				let uniforms = glium::uniform!
+				{
+					texture2d: input,
+					transform: FULLSCREEN_TRANSFORM,
+					strength : *strength,
+					radius   : *radius,
+					softness : *softness,
+				};
+				self.draw(target, &compiled_pass.program, &uniforms);
+			},
+			(PostProcessPass::Pixelate { pixel_size }, _) =>
+			{
+				let uniforms = glium::uniform!
+				{
+					texture2d : input,
+					transform : FULLSCREEN_TRANSFORM,
+					texel_size: texel_size,
+					pixel_size: *pixel_size,
+				};
+				self.draw(target, &compiled_pass.program, &uniforms);
+			},
+			//NOTE(fpalacios): new siempre carga la LUT de los pases de color_grading
+			(PostProcessPass::ColorGrading { .. }, None) => unreachable!(),
+		}
+	}
+
+	fn draw(&self, target: &mut impl glium::Surface, program: &glium::Program, uniforms: &impl glium::uniforms::Uniforms)
+	{
+		//NOTE(fpalacios): Los pases reemplazan el pixel, el alpha de la escena se mantiene para la ventana transparente
+		glium::Surface::draw(
+			target,
+			&self.quad,
+			&self.quad_indices,
+			program,
+			uniforms,
+			&glium::DrawParameters::default()
+		).unwrap();
+	}
+}
+
+fn load_lut(facade: &impl glium::backend::Facade, path: &std::path::Path) -> Result<glium::texture::Texture2d, PostProcessError>
+{
+	let image = image::open(path).map_err(|error| PostProcessError::Lut { path: path.to_path_buf(), error })?.to_rgba8();
+	let (width, height) = image.dimensions();
+
+	if height < 2 || width != height * height
+	{
+		return Err(PostProcessError::Invalid(format!("La LUT {} tiene que medir N*N x N y mide {}x{}", path.display(), width, height)));
+	}
+
+	//NOTE(fpalacios): Sin dar vuelta, asi la fila 0 de la imagen (verde 0) queda en v = 0 como espera el shader
+	let raw = glium::texture::RawImage2d::from_raw_rgba(image.into_raw(), (width, height));
+
+	return glium::texture::Texture2d::with_mipmaps(facade, raw, glium::texture::MipmapsOption::NoMipmap)
+		.map_err(|err| PostProcessError::Texture(format!("{:?}", err)));
+}
+
+fn create_targets(
+	facade    : &impl glium::backend::Facade,
+	pass_count: usize,
+	width     : u32,
+	height    : u32
+)
+-> Result<(glium::texture::SrgbTexture2d, glium::framebuffer::DepthRenderBuffer, Vec<glium::texture::SrgbTexture2d>), PostProcessError>
+{
+	let width  = width.max(1);
+	let height = height.max(1);
+
+	let create_texture = ||
+	{
+		glium::texture::SrgbTexture2d::empty_with_format(
+			facade,
+			glium::texture::SrgbFormat::U8U8U8U8,
+			glium::texture::MipmapsOption::NoMipmap,
+			width,
+			height
+		).map_err(|err| PostProcessError::Texture(format!("{:?}", err)))
+	};
+
+	let scene = create_texture()?;
+
+	let depth_buffer = glium::framebuffer::DepthRenderBuffer::new(facade, glium::texture::DepthFormat::I24, width, height)
+		.map_err(|err| PostProcessError::Texture(format!("{:?}", err)))?;
+
+	//NOTE(fpalacios): Cada pase escribe en un intermedio y el siguiente lee de ahi, con dos alcanza para cualquier cantidad
+	let mut intermediate = Vec::new();
+	for _ in 0 .. pass_count.min(2)
+	{
+		intermediate.push(create_texture()?);
+	}
+
+	return Ok((scene, depth_buffer, intermediate));
+}
@@ -0,0 +1,146 @@
+//NOTE(fpalacios): Renderer que no dibuja nada, solo anota lo que le piden. Sirve para probar la logica del mundo sin
+//contexto de OpenGL ni servidor X
+#[derive(Debug, Clone, PartialEq)]
+pub enum RenderCall
+{
+	BeginFrame,
+	//NOTE(fpalacios): image es la direccion del Rc, igual que la clave que usan los otros backends
+	Sprite
+	{
+		image    : usize,
+		area     : crate::world::Area,
+		transform: crate::renderer::Transform2D,
+	},
+	Mesh
+	{
+		mesh : usize,
+		model: nalgebra::Matrix4<f32>,
+		area : crate::world::Area,
+	},
+	EndFrame,
+	ReleaseTexture
+	{
+		image: usize,
+	},
+	ReleaseMesh
+	{
+		mesh: usize,
+	},
+}
+
+pub struct RecordingRenderer
+{
+	pub calls: Vec<RenderCall>,
+}
+
+impl Default for RecordingRenderer
+{
+	fn default() -> RecordingRenderer
+	{
+		return RecordingRenderer::new();
+	}
+}
+
+impl RecordingRenderer
+{
+	pub fn new() -> RecordingRenderer
+	{
+		return RecordingRenderer
+		{
+			calls: Vec::new(),
+		};
+	}
+
+	pub fn clear(&mut self)
+	{
+		self.calls.clear();
+	}
+
+	//NOTE(fpalacios): Los sprites del ultimo frame terminado, en el orden en que se dibujaron
+	pub fn last_frame(&self) -> Vec<(usize, crate::world::Area)>
+	{
+		let end = match self.calls.iter().rposition(|call| *call == RenderCall::EndFrame)
+		{
+			Some(end) => end,
+			None      => return Vec::new(),
+		};
+
+		let begin = self.calls[.. end].iter().rposition(|call| *call == RenderCall::BeginFrame).unwrap_or(0);
+
+		return self.calls[begin .. end].iter().filter_map(|call| match call
+		{
+			RenderCall::Sprite { image, area, .. } => Some((*image, *area)),
+			_                                      => None,
+		}).collect();
+	}
+}
+
+impl crate::renderer::Renderer for RecordingRenderer
+{
+	fn begin_frame(&mut self)
+	{
+		self.calls.push(RenderCall::BeginFrame);
+	}
+
+	fn draw_sprite(
+		&mut self,
+		image    : &std::rc::Rc<image::RgbaImage>,
+		_options : &crate::assets::TextureOptions,
+		area     : &crate::world::Area,
+		transform: &crate::renderer::Transform2D
+	)
+	{
+		self.calls.push(
+			RenderCall::Sprite
+			{
+				image    : std::rc::Rc::as_ptr(image) as usize,
+				area     : *area,
+				transform: *transform,
+			}
+		);
+	}
+
+	fn draw_mesh(
+		&mut self,
+		mesh     : &std::rc::Rc<crate::mesh::Mesh>,
+		_vertices: Option<&[crate::renderer::Vertex3D]>,
+		model    : &nalgebra::Matrix4<f32>,
+		_camera  : &crate::camera::Camera,
+		area     : &crate::world::Area
+	)
+	{
+		self.calls.push(
+			RenderCall::Mesh
+			{
+				mesh : std::rc::Rc::as_ptr(mesh) as usize,
+				model: *model,
+				area : *area,
+			}
+		);
+	}
+
+	fn end_frame(&mut self)
+	{
+		self.calls.push(RenderCall::EndFrame);
+	}
+
+	fn release_texture(&mut self, image: &std::rc::Rc<image::RgbaImage>)
+	{
+		self.calls.push(
+			RenderCall::ReleaseTexture
+			{
+				image: std::rc::Rc::as_ptr(image) as usize,
+			}
+		);
+	}
+
+	fn release_mesh(&mut self, mesh: &std::rc::Rc<crate::mesh::Mesh>)
+	{
+		self.calls.push(
+			RenderCall::ReleaseMesh
+			{
+				mesh: std::rc::Rc::as_ptr(mesh) as usize,
+			}
+		);
+	}
+}
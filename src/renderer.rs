@@ -6,42 +6,138 @@ pub struct Vertex2D
 	pub texture_coords: [f32; 2],
 }
 
-glium::implement_vertex!(Vertex3D, position, color);
+glium::implement_vertex!(Vertex3D, position, normal, texture_coords, color);
 #[derive(Copy, Clone)]
 pub struct Vertex3D
 {
-	pub position: [f32; 3],
-	pub color   : [f32; 3],
+	pub position      : [f32; 3],
+	pub normal        : [f32; 3],
+	pub texture_coords: [f32; 2],
+	pub color         : [f32; 3],
 }
 
 pub struct Model2D
 {
 	pub vertex_buffer         : glium::VertexBuffer<Vertex2D>,
 	pub index_buffer          : glium::IndexBuffer<u16>,
-	pub texture               : std::rc::Rc<glium::texture::CompressedSrgbTexture2d>,
 	pub texture_shader_program: glium::Program,
 }
 
+//NOTE(fpalacios): Como se dibuja un sprite dentro de su area. El sprite es un cuadrado de (0, 0) a (1, 1) que se escala,
+//se rota y se mueve alrededor del pivot. La rotacion se hace en pixeles para que no se deforme si el area no es cuadrada
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform2D
+{
+	//NOTE(fpalacios): Desplazamiento en coordenadas normalizadas de la ventana, igual que las areas
+	pub position: [f32; 2],
+	//NOTE(fpalacios): En radianes, antihorario
+	pub rotation: f32,
+	pub scale   : [f32; 2],
+	//NOTE(fpalacios): (0, 0) es abajo a la izquierda del sprite y (1, 1) arriba a la derecha
+	pub pivot   : [f32; 2],
+	pub flip_x  : bool,
+	pub flip_y  : bool,
+	pub tint    : crate::color::RgbaColor,
+	pub opacity : f32,
+}
+
+impl Default for Transform2D
+{
+	fn default() -> Transform2D
+	{
+		return Transform2D::new();
+	}
+}
+
+impl Transform2D
+{
+	pub fn new() -> Transform2D
+	{
+		return Transform2D
+		{
+			position: [0.0, 0.0],
+			rotation: 0.0,
+			scale   : [1.0, 1.0],
+			//NOTE(fpalacios): La base de la maceta, asi las plantas crecen y se mecen desde abajo
+			pivot   : [0.5, 0.0],
+			flip_x  : false,
+			flip_y  : false,
+			tint    : crate::color::RgbaColor::WHITE,
+			opacity : 1.0,
+		};
+	}
+
+	//NOTE(fpalacios): Lleva un punto del sprite (0 a 1) a coordenadas normalizadas de la ventana. Dar vuelta es escalar
+	//en negativo alrededor del pivot
+	pub fn matrix(&self, area: &crate::world::Area, (width, height): (u32, u32)) -> nalgebra::Matrix3<f32>
+	{
+		let width  = width.max(1)  as f32;
+		let height = height.max(1) as f32;
+
+		let size   = nalgebra::Vector2::new(area.width / 2.0 * width, area.height / 2.0 * height);
+		let origin = nalgebra::Vector2::new((area.x + 1.0) / 2.0 * width, (area.y + 1.0) / 2.0 * height);
+		let pivot  = nalgebra::Vector2::new(self.pivot[0], self.pivot[1]);
+		let offset = nalgebra::Vector2::new(self.position[0] / 2.0 * width, self.position[1] / 2.0 * height);
+
+		let flip = nalgebra::Vector2::new(
+			if self.flip_x { -1.0 } else { 1.0 },
+			if self.flip_y { -1.0 } else { 1.0 }
+		);
+
+		let sprite_to_pixels =
+			nalgebra::Matrix3::new_translation(&(origin + pivot.component_mul(&size) + offset)) *
+			nalgebra::Matrix3::new_rotation(self.rotation) *
+			nalgebra::Matrix3::new_nonuniform_scaling(&size.component_mul(&flip).component_mul(&nalgebra::Vector2::new(self.scale[0], self.scale[1]))) *
+			nalgebra::Matrix3::new_translation(&-pivot);
+
+		let pixels_to_window =
+			nalgebra::Matrix3::new_translation(&nalgebra::Vector2::new(-1.0, -1.0)) *
+			nalgebra::Matrix3::new_nonuniform_scaling(&nalgebra::Vector2::new(2.0 / width, 2.0 / height));
+
+		return pixels_to_window * sprite_to_pixels;
+	}
+
+	//NOTE(fpalacios): El tinte con la opacidad ya aplicada y premultiplicado, igual que las texturas. Se multiplica por el
+	//color de la textura
+	pub fn color(&self) -> [f32; 4]
+	{
+		let alpha = self.tint.a * self.opacity;
+		return [self.tint.r * alpha, self.tint.g * alpha, self.tint.b * alpha, alpha];
+	}
+}
+
 pub struct Model3D
 {
-	pub rotation_matrix    : nalgebra::Matrix4<f32>,
-	pub translation_vector : nalgebra::Vector3<f32>,
-	pub vertex_buffer      : glium::VertexBuffer<Vertex3D>,
-	pub index_buffer       : glium::IndexBuffer<u16>,
-	pub fill_shader_program: glium::Program,
-	pub wire_shader_program: glium::Program,
+	pub vertex_buffer: glium::VertexBuffer<Vertex3D>,
+	pub index_buffer : glium::IndexBuffer<u32>,
 }
 
 pub struct Animation2DStep
 {
-	pub texture  : std::rc::Rc<glium::texture::CompressedSrgbTexture2d>,
+	pub texture  : std::rc::Rc<crate::assets::Texture>,
 	pub duration : std::time::Duration,
 	pub next_step: Option<std::rc::Rc<Animation2DStep>>,
 }
 
+impl Animation2DStep
+{
+	//NOTE(fpalacios): Lo que dura la cadena desde este paso hasta el final. Desde el primero es una vuelta entera
+	pub fn chain_duration(&self) -> std::time::Duration
+	{
+		let mut duration = self.duration;
+		let mut step = self.next_step.as_ref();
+		while let Some(current) = step
+		{
+			duration += current.duration;
+			step = current.next_step.as_ref();
+		}
+
+		return duration;
+	}
+}
+
 pub struct Animation2D
 {
-	pub model           : Model2D,
 	pub timer           : std::time::Duration,
 	pub root_steps_chain: std::rc::Rc<Animation2DStep>,
 	pub current_step    : std::rc::Rc<Animation2DStep>,
@@ -49,12 +145,30 @@ pub struct Animation2D
 
 impl Animation2D
 {
-	pub fn update(&mut self, delta: &std::time::Duration)
+	//NOTE(fpalacios): Devuelve true si cambió de paso y hay que volver a dibujar
+	pub fn update(&mut self, delta: &std::time::Duration) -> bool
 	{
 		self.timer += *delta;
 
+		let mut changed = false;
+
+		//NOTE(fpalacios): Las vueltas enteras no cambian el paso en el que queda, asi un delta de horas no recorre la cadena
+		//miles de veces. Con todos los pasos en 0 el while de abajo no termina nunca, asi que tampoco se avanza
+		let period = self.root_steps_chain.chain_duration();
+		if period.is_zero()
+		{
+			return false;
+		}
+
+		if self.timer >= period
+		{
+			changed = true;
+			self.timer = std::time::Duration::from_nanos((self.timer.as_nanos() % period.as_nanos()) as u64);
+		}
+
 		while self.timer >= self.current_step.duration
 		{
+			changed = true;
 			self.timer -= self.current_step.duration;
 			if let Some(next_step) = &self.current_step.next_step
 			{
@@ -64,86 +178,730 @@ impl Animation2D
 			{
 				self.current_step = self.root_steps_chain.clone();
 			}
-
-			self.model.texture = self.current_step.texture.clone();
 		}
+
+		return changed;
+	}
+
+	pub fn current_texture(&self) -> &std::rc::Rc<crate::assets::Texture>
+	{
+		return &self.current_step.texture;
+	}
+
+	pub fn time_until_next_step(&self) -> std::time::Duration
+	{
+		return self.current_step.duration.checked_sub(self.timer).unwrap_or_default();
 	}
 }
 
-pub fn draw_animation_2d(display: &glium::Display, animation: &Animation2D)
+//NOTE(fpalacios): Negro transparente, que premultiplicado y en sRGB es igual a cero en los dos espacios
+pub fn clear_frame(target: &mut impl glium::Surface)
 {
-	draw_model_2d(display, &animation.model);
+	glium::Surface::clear_all(target, (0.0, 0.0, 0.0, 0.0), 1.0, 0);
 }
 
-pub fn draw_model_2d(display: &glium::Display, model: &Model2D)
+//NOTE(fpalacios): "Source over" con alpha premultiplicado. Las texturas se suben premultiplicadas y la escena es una textura
+//sRGB, asi que la placa mezcla en lineal. Despues el pase de salida de PostProcessor lo deja como espera el compositor
+pub fn premultiplied_blend() -> glium::Blend
 {
-	let uniforms = glium::uniform!
+	return glium::Blend
 	{
-		texture2d: &*model.texture
+		color: glium::BlendingFunction::Addition
+		{
+			source     : glium::LinearBlendingFactor::One,
+			destination: glium::LinearBlendingFactor::OneMinusSourceAlpha,
+		},
+		alpha: glium::BlendingFunction::Addition
+		{
+			source     : glium::LinearBlendingFactor::One,
+			destination: glium::LinearBlendingFactor::OneMinusSourceAlpha,
+		},
+		constant_value: (0.0, 0.0, 0.0, 0.0),
 	};
+}
 
-	let mut target = display.draw();
+//NOTE(fpalacios): Lo unico que World sabe de como se dibuja. Las imagenes viven en memoria y cada backend las sube o
+//las convierte como le quede comodo, identificandolas por la direccion del Rc
+pub trait Renderer
+{
+	fn begin_frame(&mut self);
+	fn draw_sprite(
+		&mut self,
+		image    : &std::rc::Rc<image::RgbaImage>,
+		options  : &crate::assets::TextureOptions,
+		area     : &crate::world::Area,
+		transform: &Transform2D
+	);
+	//NOTE(fpalacios): Dibuja la malla con la camara usando el area como viewport. Las mallas deformables (esqueleto o
+	//morph targets) mandan sus vertices ya deformados en cada frame
+	fn draw_mesh(
+		&mut self,
+		mesh    : &std::rc::Rc<crate::mesh::Mesh>,
+		vertices: Option<&[Vertex3D]>,
+		model   : &nalgebra::Matrix4<f32>,
+		camera  : &crate::camera::Camera,
+		area    : &crate::world::Area
+	);
+	fn end_frame(&mut self);
 
-	glium::Surface::clear_color(&mut target, 0.0_f32, 0.0_f32, 0.0_f32, 0.0_f32);
-	glium::Surface::clear_depth_and_stencil(&mut target, 0.0_f32, 0_i32);
-	glium::Surface::clear_all_srgb(&mut target, (0.0, 0.0, 0.0, 0.0), 0.0, 0);
+	//NOTE(fpalacios): Avisan que la imagen o la malla no se va a dibujar mas y el backend puede soltar lo que tenga de ella
+	fn release_texture(&mut self, image: &std::rc::Rc<image::RgbaImage>);
+	fn release_mesh(&mut self, mesh: &std::rc::Rc<crate::mesh::Mesh>);
+}
 
-	let mut draw_parameters = glium::DrawParameters::default();
-	draw_parameters.blend.alpha = glium::BlendingFunction::AlwaysReplace;
-	draw_parameters.blend.color = glium::BlendingFunction::AlwaysReplace;
+//NOTE(fpalacios): Lo que se sube para cada imagen segun TextureOptions::compressed
+enum GliumTexture
+{
+	Compressed(glium::texture::CompressedSrgbTexture2d),
+	Uncompressed(glium::texture::SrgbTexture2d),
+}
 
-	glium::Surface::draw(
-		&mut target,
-		&model.vertex_buffer,
-		&model.index_buffer,
-		&model.texture_shader_program,
-		&uniforms,
-		&draw_parameters
-	).unwrap();
+impl GliumTexture
+{
+	fn sampled(&self, options: &crate::assets::TextureOptions) -> GliumSampler<'_>
+	{
+		return GliumSampler(self, sampler_behavior(options));
+	}
+}
 
-	target.finish().unwrap();
+struct GliumSampler<'texture>(&'texture GliumTexture, glium::uniforms::SamplerBehavior);
+
+impl glium::uniforms::AsUniformValue for GliumSampler<'_>
+{
+	fn as_uniform_value(&self) -> glium::uniforms::UniformValue<'_>
+	{
+		return match self.0
+		{
+			GliumTexture::Compressed(texture)   => glium::uniforms::UniformValue::CompressedSrgbTexture2d(texture, Some(self.1)),
+			GliumTexture::Uncompressed(texture) => glium::uniforms::UniformValue::SrgbTexture2d(texture, Some(self.1)),
+		};
+	}
 }
 
-pub fn draw_model_3d(display: &glium::Display, model: &Model3D)
+pub fn sampler_behavior(options: &crate::assets::TextureOptions) -> glium::uniforms::SamplerBehavior
 {
-	let uniforms = glium::uniform!
+	let wrap = match options.wrap
+	{
+		crate::assets::TextureWrap::Clamp          => glium::uniforms::SamplerWrapFunction::Clamp,
+		crate::assets::TextureWrap::Repeat         => glium::uniforms::SamplerWrapFunction::Repeat,
+		crate::assets::TextureWrap::MirroredRepeat => glium::uniforms::SamplerWrapFunction::Mirror,
+	};
+
+	//NOTE(fpalacios): Los filtros con mipmap solo valen si la textura los tiene
+	let minify_filter = match (options.filter, options.mipmaps)
+	{
+		(crate::assets::TextureFilter::Linear,  true)  => glium::uniforms::MinifySamplerFilter::LinearMipmapLinear,
+		(crate::assets::TextureFilter::Linear,  false) => glium::uniforms::MinifySamplerFilter::Linear,
+		(crate::assets::TextureFilter::Nearest, true)  => glium::uniforms::MinifySamplerFilter::NearestMipmapNearest,
+		(crate::assets::TextureFilter::Nearest, false) => glium::uniforms::MinifySamplerFilter::Nearest,
+	};
+
+	let magnify_filter = match options.filter
 	{
-		rotation:
-		[
-			[model.rotation_matrix[ 0], model.rotation_matrix[ 1], model.rotation_matrix[ 2], model.rotation_matrix[ 3]],
-			[model.rotation_matrix[ 4], model.rotation_matrix[ 5], model.rotation_matrix[ 6], model.rotation_matrix[ 7]],
-			[model.rotation_matrix[ 8], model.rotation_matrix[ 9], model.rotation_matrix[10], model.rotation_matrix[11]],
-			[model.rotation_matrix[12], model.rotation_matrix[13], model.rotation_matrix[14], model.rotation_matrix[15]],
-		],
-		translation: [model.translation_vector.x, model.translation_vector.y, model.translation_vector.z, 0_f32]
+		crate::assets::TextureFilter::Linear  => glium::uniforms::MagnifySamplerFilter::Linear,
+		crate::assets::TextureFilter::Nearest => glium::uniforms::MagnifySamplerFilter::Nearest,
 	};
 
-	let mut target = display.draw();
-	glium::Surface::clear_color(&mut target, 0.0_f32, 0.0_f32, 0.0_f32, 0.0_f32);
+	return glium::uniforms::SamplerBehavior
+	{
+		wrap_function: (wrap, wrap, wrap),
+		minify_filter,
+		magnify_filter,
+		..Default::default()
+	};
+}
+
+//NOTE(fpalacios): Donde termina el pase de salida. La textura de offscreen no es sRGB, igual que la ventana, asi se lee lo
+//mismo que veria el compositor
+enum GliumTarget
+{
+	Window,
+	Texture(glium::texture::Texture2d),
+}
+
+fn target_texture(facade: &impl glium::backend::Facade, width: u32, height: u32) -> Result<glium::texture::Texture2d, String>
+{
+	return glium::texture::Texture2d::empty_with_format(
+		facade,
+		glium::texture::UncompressedFloatFormat::U8U8U8U8,
+		glium::texture::MipmapsOption::NoMipmap,
+		width,
+		height
+	).map_err(|err| format!("{:?}", err));
+}
+
+//NOTE(fpalacios): Tiene lo que hace falta para dibujar sprites y mallas con glium. Las texturas y los buffers se suben
+//la primera vez que se dibuja cada imagen o malla
+pub struct GliumRenderer
+{
+	context            : std::rc::Rc<glium::backend::Context>,
+	shader_source      : crate::shaders::ShaderSource,
+	//NOTE(fpalacios): La que eligio el programa de sprites, todos los demas se compilan con la misma
+	glsl_version       : &'static crate::shaders::GlslVersion,
+	quad               : Model2D,
+	//NOTE(fpalacios): None si el shader de mallas no compilo, se siguen dibujando los sprites y los modelos no se ven
+	mesh_shader_program: Option<glium::Program>,
+	//NOTE(fpalacios): Se guarda tambien el Rc de la imagen para que la direccion que se usa de clave no se pueda reusar. La
+	//misma imagen con otras opciones es otra textura
+	textures           : std::collections::HashMap<(usize, crate::assets::TextureOptions), (std::rc::Rc<image::RgbaImage>, std::rc::Rc<GliumTexture>)>,
+	meshes             : std::collections::HashMap<usize, (std::rc::Rc<crate::mesh::Mesh>, std::rc::Rc<Model3D>)>,
+	//NOTE(fpalacios): Para las mallas sin textura, asi alcanza con un solo shader
+	white_image        : std::rc::Rc<image::RgbaImage>,
+	target             : GliumTarget,
+	frame              : Option<glium::Frame>,
+	post_processor     : crate::postprocess::PostProcessor,
+}
+
+impl GliumRenderer
+{
+	pub fn new(facade: &impl glium::backend::Facade, shader_source: crate::shaders::ShaderSource) -> Result<GliumRenderer, String>
+	{
+		let (texture_shader_program, glsl_version) = shader_source.negotiate(
+			facade,
+			crate::shaders::TEXTURE_VERTEX_SHADER,
+			crate::shaders::TEXTURE_FRAGMENT_SHADER
+		).map_err(|err| err.to_string())?;
+
+		let mesh_shader_program = match shader_source.compile(
+			facade,
+			glsl_version,
+			crate::shaders::MESH_VERTEX_SHADER,
+			crate::shaders::MESH_FRAGMENT_SHADER
+		)
+		{
+			Ok(program) => Some(program),
+			Err(err)    =>
+			{
+				println!("{}\nLos modelos 3D no se van a dibujar", err);
+				None
+			},
+		};
+
+		let post_processor = crate::postprocess::PostProcessor::new(facade, &shader_source, glsl_version, Vec::new(), facade.get_context().get_framebuffer_dimensions())
+			.map_err(|err| match err
+			{
+				crate::postprocess::PostProcessError::Shader(err) => err.to_string(),
+				err                                               => format!("No se pudo crear la textura de la escena {:?}", err),
+			})?;
+
+		println!("Usando los shaders de GLSL {}", glsl_version.directory);
+
+		return Ok(
+			GliumRenderer
+			{
+				context: facade.get_context().clone(),
+				shader_source,
+				glsl_version,
+				quad   : Model2D
+				{
+					//NOTE(fpalacios): El sprite en sus propias coordenadas, Transform2D::matrix lo lleva a la ventana
+					vertex_buffer: glium::VertexBuffer::new(
+						facade,
+						&[
+							Vertex2D { position: [0.0, 0.0], texture_coords: [0.0, 0.0] },
+							Vertex2D { position: [1.0, 0.0], texture_coords: [1.0, 0.0] },
+							Vertex2D { position: [1.0, 1.0], texture_coords: [1.0, 1.0] },
+							Vertex2D { position: [0.0, 1.0], texture_coords: [0.0, 1.0] },
+						]
+					).unwrap(),
+					index_buffer: glium::IndexBuffer::new(
+						facade,
+						glium::index::PrimitiveType::TrianglesList,
+						&[
+							0_u16, 1_u16, 2_u16,
+							2_u16, 3_u16, 0_u16,
+						]
+					).unwrap(),
+					texture_shader_program,
+				},
+				mesh_shader_program,
+				textures      : std::collections::HashMap::new(),
+				meshes        : std::collections::HashMap::new(),
+				white_image   : std::rc::Rc::new(image::RgbaImage::from_pixel(1, 1, image::Rgba([255, 255, 255, 255]))),
+				target        : GliumTarget::Window,
+				frame         : None,
+				post_processor,
+			}
+		);
+	}
+
+	//NOTE(fpalacios): Si algun shader no compila se imprime el log y se sigue usando el programa anterior. La version de GLSL
+	//queda la que se eligio al crear el renderer
+	pub fn reload_shaders(&mut self)
+	{
+		match self.shader_source.compile(
+			&self.context,
+			self.glsl_version,
+			crate::shaders::TEXTURE_VERTEX_SHADER,
+			crate::shaders::TEXTURE_FRAGMENT_SHADER
+		)
+		{
+			Ok(program) =>
+			{
+				self.quad.texture_shader_program = program;
+				println!("Shaders recargados (GLSL {})", self.glsl_version.directory);
+			},
+			Err(err) => println!("{}", err),
+		}
+
+		match self.shader_source.compile(
+			&self.context,
+			self.glsl_version,
+			crate::shaders::MESH_VERTEX_SHADER,
+			crate::shaders::MESH_FRAGMENT_SHADER
+		)
+		{
+			Ok(program) => self.mesh_shader_program = Some(program),
+			Err(err)    => println!("{}", err),
+		}
+
+		self.post_processor.reload_shaders(&self.context, &self.shader_source, self.glsl_version);
+	}
+
+	//NOTE(fpalacios): Con una lista vacia queda solo el pase de salida. Si falla se sigue con los pases de antes
+	pub fn set_post_processing(&mut self, passes: Vec<crate::postprocess::PostProcessPass>) -> Result<(), crate::postprocess::PostProcessError>
+	{
+		let dimensions = self.target_dimensions();
+		self.post_processor = crate::postprocess::PostProcessor::new(&self.context, &self.shader_source, self.glsl_version, passes, dimensions)?;
+
+		return Ok(());
+	}
+
+	//NOTE(fpalacios): Compila los pases de theme.postprocess, si fallan se dibuja sin
+	pub fn apply_theme(&mut self, theme: &crate::config::Theme)
+	{
+		if let Err(err) = self.set_post_processing(theme.postprocess.clone())
+		{
+			println!("Error al cargar el post procesado {:?}", err);
+		}
+	}
+
+	//NOTE(fpalacios): Dibuja sobre una textura en vez del framebuffer por defecto, despues se lee con read_image
+	pub fn offscreen(
+		facade       : &impl glium::backend::Facade,
+		shader_source: crate::shaders::ShaderSource,
+		width        : u32,
+		height       : u32
+	)
+	-> Result<GliumRenderer, String>
+	{
+		let texture = target_texture(facade, width, height)?;
+
+		let mut renderer = GliumRenderer::new(facade, shader_source)?;
+		renderer.target = GliumTarget::Texture(texture);
+
+		return Ok(renderer);
+	}
+
+	//NOTE(fpalacios): Para las capturas. draw dibuja un frame sobre una textura del tamaño del destino, con los mismos shaders,
+	//texturas y post procesado, y despues se vuelve a dibujar donde se dibujaba
+	pub fn capture(&mut self, draw: impl FnOnce(&mut GliumRenderer)) -> Result<image::RgbaImage, String>
+	{
+		let (width, height) = self.target_dimensions();
+		let texture = target_texture(&self.context, width, height)?;
+
+		let previous = std::mem::replace(&mut self.target, GliumTarget::Texture(texture));
+		draw(self);
+		let image = self.read_image();
+		self.target = previous;
+
+		return image.ok_or_else(|| String::from("El tamaño de la imagen leida no coincide"));
+	}
+
+	//NOTE(fpalacios): Solo tiene sentido para los renderers creados con offscreen
+	pub fn read_image(&self) -> Option<image::RgbaImage>
+	{
+		let texture = match &self.target
+		{
+			GliumTarget::Texture(texture) => texture,
+			GliumTarget::Window           => return None,
+		};
+
+		let raw: glium::texture::RawImage2d<u8> = texture.read();
+		let image = image::RgbaImage::from_raw(raw.width, raw.height, raw.data.into_owned())?;
+
+		//NOTE(fpalacios): OpenGL tiene el origen abajo a la izquierda y las imagenes arriba a la izquierda. Lo que hay en la
+		//textura esta premultiplicado y los PNG no
+		return Some(crate::color::unpremultiply(&image::imageops::flip_vertical(&image)));
+	}
+
+	//NOTE(fpalacios): Se sube una vez por cada imagen y opciones. Si la compresion falla (ej: el driver no tiene el formato)
+	//se sube sin comprimir
+	fn texture(
+		&mut self,
+		image  : &std::rc::Rc<image::RgbaImage>,
+		options: &crate::assets::TextureOptions
+	)
+	-> Result<std::rc::Rc<GliumTexture>, glium::texture::TextureCreationError>
+	{
+		let key = (std::rc::Rc::as_ptr(image) as usize, *options);
+
+		if let Some((_, texture)) = self.textures.get(&key)
+		{
+			return Ok(texture.clone());
+		}
+
+		let premultiplied = crate::color::premultiply_srgb(image);
+
+		let compressed = if options.compressed
+		{
+			match self.compressed_texture(&premultiplied, options.mipmaps)
+			{
+				Ok(texture) => Some(texture),
+				Err(err) =>
+				{
+					println!("No se pudo subir la textura comprimida, se sube sin comprimir {:?}", err);
+					None
+				}
+			}
+		}
+		else
+		{
+			None
+		};
+
+		let texture = match compressed
+		{
+			Some(texture) => GliumTexture::Compressed(texture),
+			None          =>
+			{
+				let mipmaps = if options.mipmaps
+				{
+					glium::texture::MipmapsOption::AutoGeneratedMipmaps
+				}
+				else
+				{
+					glium::texture::MipmapsOption::NoMipmap
+				};
+
+				let glium_image = glium::texture::RawImage2d::from_raw_rgba_reversed(premultiplied.as_raw(), premultiplied.dimensions());
+				GliumTexture::Uncompressed(glium::texture::SrgbTexture2d::with_mipmaps(&self.context, glium_image, mipmaps)?)
+			},
+		};
+
+		let texture = std::rc::Rc::new(texture);
+
+		self.textures.insert(key, (image.clone(), texture.clone()));
+
+		return Ok(texture);
+	}
+
+	//NOTE(fpalacios): OpenGL no genera mipmaps de texturas comprimidas, se achican en la CPU y se sube cada nivel
+	fn compressed_texture(
+		&self,
+		premultiplied: &image::RgbaImage,
+		mipmaps      : bool
+	)
+	-> Result<glium::texture::CompressedSrgbTexture2d, glium::texture::TextureCreationError>
+	{
+		let mipmaps = if mipmaps
+		{
+			glium::texture::CompressedMipmapsOption::EmptyMipmaps
+		}
+		else
+		{
+			glium::texture::CompressedMipmapsOption::NoMipmap
+		};
+
+		let glium_image = glium::texture::RawImage2d::from_raw_rgba_reversed(premultiplied.as_raw(), premultiplied.dimensions());
+		let texture = glium::texture::CompressedSrgbTexture2d::with_mipmaps(&self.context, glium_image, mipmaps)?;
+
+		for level in 1 .. texture.get_mipmap_levels()
+		{
+			let (width, height) = premultiplied.dimensions();
+			let level_width  = std::cmp::max(width  >> level, 1);
+			let level_height = std::cmp::max(height >> level, 1);
+
+			//NOTE(fpalacios): Se achica la imagen premultiplicada, asi los bordes transparentes no oscurecen el color
+			let level_image = image::imageops::resize(premultiplied, level_width, level_height, image::imageops::FilterType::Triangle);
+
+			if let Some(mipmap) = texture.mipmap(level)
+			{
+				mipmap.write(
+					glium::Rect { left: 0, bottom: 0, width: level_width, height: level_height },
+					glium::texture::RawImage2d::from_raw_rgba_reversed(level_image.as_raw(), level_image.dimensions())
+				);
+			}
+		}
+
+		return Ok(texture);
+	}
+
+	fn model_3d(&mut self, mesh: &std::rc::Rc<crate::mesh::Mesh>) -> std::rc::Rc<Model3D>
+	{
+		let key = std::rc::Rc::as_ptr(mesh) as usize;
+
+		if let Some((_, model)) = self.meshes.get(&key)
+		{
+			return model.clone();
+		}
+
+		let vertex_buffer = if mesh.is_deformable()
+		{
+			glium::VertexBuffer::dynamic(&self.context, &mesh.vertices).unwrap()
+		}
+		else
+		{
+			glium::VertexBuffer::new(&self.context, &mesh.vertices).unwrap()
+		};
 
-	let mut draw_parameters = glium::DrawParameters::default();
+		let model = std::rc::Rc::new(
+			Model3D
+			{
+				vertex_buffer,
+				index_buffer : glium::IndexBuffer::new(&self.context, glium::index::PrimitiveType::TrianglesList, &mesh.indices).unwrap(),
+			}
+		);
 
-	draw_parameters.polygon_mode = glium::PolygonMode::Fill;
+		self.meshes.insert(key, (mesh.clone(), model.clone()));
+
+		return model;
+	}
+
+	fn target_dimensions(&self) -> (u32, u32)
+	{
+		return match &self.target
+		{
+			GliumTarget::Window           => self.context.get_framebuffer_dimensions(),
+			GliumTarget::Texture(texture) => texture.dimensions(),
+		};
+	}
+}
+
+impl Renderer for GliumRenderer
+{
+	fn begin_frame(&mut self)
+	{
+		let dimensions = self.target_dimensions();
+
+		//NOTE(fpalacios): Si no se puede se sigue con la escena del tamaño anterior, el pase de salida la estira
+		if self.post_processor.dimensions() != dimensions
+		{
+			if let Err(err) = self.post_processor.resize(&self.context, dimensions.0, dimensions.1)
+			{
+				println!("Error al redimensionar la escena {:?}", err);
+			}
+		}
+
+		clear_frame(&mut self.post_processor.scene_framebuffer(&self.context));
+
+		//NOTE(fpalacios): El pase de salida pisa todos los pixeles del destino, no hace falta limpiarlo
+		if let GliumTarget::Window = &self.target
+		{
+			self.frame = Some(glium::Frame::new(self.context.clone(), dimensions));
+		}
+	}
+
+	fn draw_sprite(
+		&mut self,
+		image    : &std::rc::Rc<image::RgbaImage>,
+		options  : &crate::assets::TextureOptions,
+		area     : &crate::world::Area,
+		transform: &Transform2D
+	)
+	{
+		let matrix = transform.matrix(area, self.target_dimensions());
+		let texture = match self.texture(image, options)
+		{
+			Ok(texture) => texture,
+			Err(err) =>
+			{
+				println!("No se pudo subir la textura {:?}", err);
+				return;
+			}
+		};
+
+		draw_model_2d(&mut self.post_processor.scene_framebuffer(&self.context), &self.quad, texture.sampled(options), &matrix, transform.color());
+	}
+
+	fn draw_mesh(
+		&mut self,
+		mesh    : &std::rc::Rc<crate::mesh::Mesh>,
+		vertices: Option<&[Vertex3D]>,
+		model   : &nalgebra::Matrix4<f32>,
+		camera  : &crate::camera::Camera,
+		area    : &crate::world::Area
+	)
+	{
+		let (width, height) = self.target_dimensions();
+
+		//NOTE(fpalacios): El viewport de OpenGL tambien tiene el origen abajo a la izquierda, igual que las areas
+		let viewport = glium::Rect
+		{
+			left  : ((area.x + 1.0) / 2.0 * width as f32).max(0.0) as u32,
+			bottom: ((area.y + 1.0) / 2.0 * height as f32).max(0.0) as u32,
+			width : (area.width  / 2.0 * width  as f32).max(1.0) as u32,
+			height: (area.height / 2.0 * height as f32).max(1.0) as u32,
+		};
+
+		let transform = MeshTransform
+		{
+			model     : *model,
+			view      : camera.view_matrix(),
+			projection: camera.projection_matrix(viewport.width as f32 / viewport.height as f32),
+		};
+
+		let model_3d = self.model_3d(mesh);
+
+		if let Some(vertices) = vertices.filter(|vertices| vertices.len() == model_3d.vertex_buffer.len())
+		{
+			model_3d.vertex_buffer.write(vertices);
+		}
+
+		//NOTE(fpalacios): Las texturas de los modelos no pasan por las definiciones de assets. Repeat es lo que asume glTF
+		let options = crate::assets::TextureOptions
+		{
+			wrap: crate::assets::TextureWrap::Repeat,
+			..crate::assets::TextureOptions::new()
+		};
+
+		let image = mesh.texture.clone().unwrap_or_else(|| self.white_image.clone());
+		let texture = match self.texture(&image, &options)
+		{
+			Ok(texture) => texture,
+			Err(err) =>
+			{
+				println!("No se pudo subir la textura de la malla {:?}", err);
+				return;
+			}
+		};
+
+		if let Some(mesh_shader_program) = &self.mesh_shader_program
+		{
+			draw_model_3d(
+				&mut self.post_processor.scene_framebuffer(&self.context),
+				&model_3d,
+				texture.sampled(&options),
+				mesh_shader_program,
+				&transform,
+				viewport
+			);
+		}
+	}
+
+	fn end_frame(&mut self)
+	{
+		match &self.target
+		{
+			GliumTarget::Window =>
+			{
+				if let Some(frame) = &mut self.frame
+				{
+					self.post_processor.apply(&self.context, frame);
+				}
+			},
+			GliumTarget::Texture(target_texture) =>
+			{
+				let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&self.context, target_texture).unwrap();
+				self.post_processor.apply(&self.context, &mut framebuffer);
+			},
+		}
+
+		if let Some(frame) = self.frame.take()
+		{
+			if let Err(err) = frame.finish()
+			{
+				println!("Error al terminar el frame {:?}", err);
+			}
+		}
+	}
+
+	fn release_texture(&mut self, image: &std::rc::Rc<image::RgbaImage>)
+	{
+		let key = std::rc::Rc::as_ptr(image) as usize;
+		self.textures.retain(|(image_key, _), _| *image_key != key);
+	}
+
+	fn release_mesh(&mut self, mesh: &std::rc::Rc<crate::mesh::Mesh>)
+	{
+		self.meshes.remove(&(std::rc::Rc::as_ptr(mesh) as usize));
+	}
+}
+
+pub fn draw_model_2d(
+	target   : &mut impl glium::Surface,
+	model    : &Model2D,
+	texture  : impl glium::uniforms::AsUniformValue,
+	transform: &nalgebra::Matrix3<f32>,
+	tint     : [f32; 4]
+)
+{
+	let transform: [[f32; 3]; 3] = (*transform).into();
+
+	let uniforms = glium::uniform!
+	{
+		texture2d: texture,
+		transform: transform,
+		tint     : tint,
+	};
+
+	let draw_parameters = glium::DrawParameters
+	{
+		blend: premultiplied_blend(),
+		..Default::default()
+	};
 
 	glium::Surface::draw(
-		&mut target,
+		target,
 		&model.vertex_buffer,
 		&model.index_buffer,
-		&model.fill_shader_program,
+		&model.texture_shader_program,
 		&uniforms,
 		&draw_parameters
 	).unwrap();
+}
+
+//NOTE(fpalacios): Luz que viene de arriba, adelante y un poco de la izquierda. El backend por software ilumina igual
+pub const LIGHT_DIRECTION: [f32; 3] = [0.4, -0.8, -0.45];
 
-	draw_parameters.polygon_mode = glium::PolygonMode::Line;
+pub struct MeshTransform
+{
+	pub model     : nalgebra::Matrix4<f32>,
+	pub view      : nalgebra::Matrix4<f32>,
+	pub projection: nalgebra::Matrix4<f32>,
+}
+
+pub fn draw_model_3d(
+	target   : &mut impl glium::Surface,
+	model    : &Model3D,
+	texture  : impl glium::uniforms::AsUniformValue,
+	program  : &glium::Program,
+	transform: &MeshTransform,
+	viewport : glium::Rect
+)
+{
+	let model_matrix     : [[f32; 4]; 4] = transform.model.into();
+	let view_matrix      : [[f32; 4]; 4] = transform.view.into();
+	let projection_matrix: [[f32; 4]; 4] = transform.projection.into();
+
+	let uniforms = glium::uniform!
+	{
+		model          : model_matrix,
+		view           : view_matrix,
+		projection     : projection_matrix,
+		texture2d      : texture,
+		light_direction: LIGHT_DIRECTION,
+	};
+
+	let draw_parameters = glium::DrawParameters
+	{
+		viewport: Some(viewport),
+		blend   : premultiplied_blend(),
+		depth   : glium::Depth
+		{
+			test : glium::DepthTest::IfLess,
+			write: true,
+			..Default::default()
+		},
+		..Default::default()
+	};
 
 	glium::Surface::draw(
-		&mut target,
+		target,
 		&model.vertex_buffer,
 		&model.index_buffer,
-		&model.wire_shader_program,
+		program,
 		&uniforms,
 		&draw_parameters
 	).unwrap();
-
-	target.finish().unwrap();
-}
\ No newline at end of file
+}
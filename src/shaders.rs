@@ -0,0 +1,237 @@
+//NOTE(fpalacios): Rutas relativas al directorio de cada version de GLSL
+pub const TEXTURE_VERTEX_SHADER        : &str = "2D/TextureVertShader.glsl";
+pub const TEXTURE_FRAGMENT_SHADER      : &str = "2D/TextureFragShader.glsl";
+pub const MESH_VERTEX_SHADER           : &str = "3D/MeshVertShader.glsl";
+pub const MESH_FRAGMENT_SHADER         : &str = "3D/MeshFragShader.glsl";
+//NOTE(fpalacios): Los pases de post procesado usan TEXTURE_VERTEX_SHADER con un quad que cubre toda la pantalla
+pub const COLOR_GRADING_FRAGMENT_SHADER: &str = "2D/ColorGradingFragShader.glsl";
+pub const GLOW_FRAGMENT_SHADER         : &str = "2D/GlowFragShader.glsl";
+pub const VIGNETTE_FRAGMENT_SHADER     : &str = "2D/VignetteFragShader.glsl";
+pub const PIXELATE_FRAGMENT_SHADER     : &str = "2D/PixelateFragShader.glsl";
+//NOTE(fpalacios): El ultimo pase, pasa la escena a lo que se escribe en la ventana (ver PostProcessor)
+pub const OUTPUT_FRAGMENT_SHADER       : &str = "2D/OutputFragShader.glsl";
+
+pub struct GlslVersion
+{
+	pub directory: &'static str,
+	pub version  : glium::Version,
+}
+
+//NOTE(fpalacios): En orden de preferencia, se usa la primera que soporte el contexto
+pub const GLSL_VERSIONS: &[GlslVersion] =
+&[
+	GlslVersion { directory: "460",   version: glium::Version(glium::Api::Gl,   4, 6) },
+	GlslVersion { directory: "140",   version: glium::Version(glium::Api::Gl,   1, 4) },
+	GlslVersion { directory: "120",   version: glium::Version(glium::Api::Gl,   1, 2) },
+	GlslVersion { directory: "300es", version: glium::Version(glium::Api::GlEs, 3, 0) },
+];
+
+//NOTE(fpalacios): Copia de los shaders dentro del binario, es lo que se usa cuando no se pide leerlos de disco
+const EMBEDDED_SHADERS: &[(&str, &str)] =
+&[
+	("460/2D/TextureVertShader.glsl",        include_str!("../shaders/460/2D/TextureVertShader.glsl")),
+	("460/2D/TextureFragShader.glsl",        include_str!("../shaders/460/2D/TextureFragShader.glsl")),
+	("460/2D/ColorGradingFragShader.glsl",   include_str!("../shaders/460/2D/ColorGradingFragShader.glsl")),
+	("460/2D/GlowFragShader.glsl",           include_str!("../shaders/460/2D/GlowFragShader.glsl")),
+	("460/2D/VignetteFragShader.glsl",       include_str!("../shaders/460/2D/VignetteFragShader.glsl")),
+	("460/2D/PixelateFragShader.glsl",       include_str!("../shaders/460/2D/PixelateFragShader.glsl")),
+	("460/2D/OutputFragShader.glsl",         include_str!("../shaders/460/2D/OutputFragShader.glsl")),
+	("460/3D/MeshVertShader.glsl",           include_str!("../shaders/460/3D/MeshVertShader.glsl")),
+	("460/3D/MeshFragShader.glsl",           include_str!("../shaders/460/3D/MeshFragShader.glsl")),
+	("140/2D/TextureVertShader.glsl",        include_str!("../shaders/140/2D/TextureVertShader.glsl")),
+	("140/2D/TextureFragShader.glsl",        include_str!("../shaders/140/2D/TextureFragShader.glsl")),
+	("140/2D/ColorGradingFragShader.glsl",   include_str!("../shaders/140/2D/ColorGradingFragShader.glsl")),
+	("140/2D/GlowFragShader.glsl",           include_str!("../shaders/140/2D/GlowFragShader.glsl")),
+	("140/2D/VignetteFragShader.glsl",       include_str!("../shaders/140/2D/VignetteFragShader.glsl")),
+	("140/2D/PixelateFragShader.glsl",       include_str!("../shaders/140/2D/PixelateFragShader.glsl")),
+	("140/2D/OutputFragShader.glsl",         include_str!("../shaders/140/2D/OutputFragShader.glsl")),
+	("140/3D/MeshVertShader.glsl",           include_str!("../shaders/140/3D/MeshVertShader.glsl")),
+	("140/3D/MeshFragShader.glsl",           include_str!("../shaders/140/3D/MeshFragShader.glsl")),
+	("120/2D/TextureVertShader.glsl",        include_str!("../shaders/120/2D/TextureVertShader.glsl")),
+	("120/2D/TextureFragShader.glsl",        include_str!("../shaders/120/2D/TextureFragShader.glsl")),
+	("120/2D/ColorGradingFragShader.glsl",   include_str!("../shaders/120/2D/ColorGradingFragShader.glsl")),
+	("120/2D/GlowFragShader.glsl",           include_str!("../shaders/120/2D/GlowFragShader.glsl")),
+	("120/2D/VignetteFragShader.glsl",       include_str!("../shaders/120/2D/VignetteFragShader.glsl")),
+	("120/2D/PixelateFragShader.glsl",       include_str!("../shaders/120/2D/PixelateFragShader.glsl")),
+	("120/2D/OutputFragShader.glsl",         include_str!("../shaders/120/2D/OutputFragShader.glsl")),
+	("120/3D/MeshVertShader.glsl",           include_str!("../shaders/120/3D/MeshVertShader.glsl")),
+	("120/3D/MeshFragShader.glsl",           include_str!("../shaders/120/3D/MeshFragShader.glsl")),
+	("300es/2D/TextureVertShader.glsl",      include_str!("../shaders/300es/2D/TextureVertShader.glsl")),
+	("300es/2D/TextureFragShader.glsl",      include_str!("../shaders/300es/2D/TextureFragShader.glsl")),
+	("300es/2D/ColorGradingFragShader.glsl", include_str!("../shaders/300es/2D/ColorGradingFragShader.glsl")),
+	("300es/2D/GlowFragShader.glsl",         include_str!("../shaders/300es/2D/GlowFragShader.glsl")),
+	("300es/2D/VignetteFragShader.glsl",     include_str!("../shaders/300es/2D/VignetteFragShader.glsl")),
+	("300es/2D/PixelateFragShader.glsl",     include_str!("../shaders/300es/2D/PixelateFragShader.glsl")),
+	("300es/2D/OutputFragShader.glsl",       include_str!("../shaders/300es/2D/OutputFragShader.glsl")),
+	("300es/3D/MeshVertShader.glsl",         include_str!("../shaders/300es/3D/MeshVertShader.glsl")),
+	("300es/3D/MeshFragShader.glsl",         include_str!("../shaders/300es/3D/MeshFragShader.glsl")),
+];
+
+#[derive(Debug, Clone)]
+pub enum ShaderSource
+{
+	Embedded,
+	//NOTE(fpalacios): Modo desarrollo, se leen de disco cada vez que se compilan y se pueden recargar sin recompilar
+	Directory(std::path::PathBuf),
+}
+
+#[derive(Debug)]
+pub enum ShaderError
+{
+	Read
+	{
+		path : std::path::PathBuf,
+		error: std::io::Error,
+	},
+	NotEmbedded(String),
+	Compile
+	{
+		vertex  : String,
+		fragment: String,
+		error   : glium::ProgramCreationError,
+	},
+	NoSupportedVersion
+	{
+		supported: glium::Version,
+		tried    : Vec<String>,
+	},
+}
+
+impl std::fmt::Display for ShaderError
+{
+	fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result
+	{
+		return match self
+		{
+			ShaderError::Read { path, error }                => write!(formatter, "No se pudo leer el shader {}: {}", path.display(), error),
+			ShaderError::NotEmbedded(path)                   => write!(formatter, "El shader {} no esta incluido en el binario", path),
+			//NOTE(fpalacios): El Display de glium incluye el log del compilador de GLSL
+			ShaderError::Compile { vertex, fragment, error } => write!(formatter, "Error al compilar {} + {}\n{}", vertex, fragment, error),
+			ShaderError::NoSupportedVersion { supported, tried } =>
+			{
+				write!(formatter, "El contexto soporta GLSL {:?} {}.{} y no hay shaders que anden:", supported.0, supported.1, supported.2)?;
+				for attempt in tried
+				{
+					write!(formatter, "\n\t{}", attempt)?;
+				}
+				Ok(())
+			},
+		};
+	}
+}
+
+impl ShaderSource
+{
+	//NOTE(fpalacios): JARDINCHO_SHADERS_DIR apunta al directorio shaders/ del repo para desarrollar los shaders en vivo
+	pub fn from_env() -> ShaderSource
+	{
+		return match std::env::var_os("JARDINCHO_SHADERS_DIR")
+		{
+			Some(directory) => ShaderSource::Directory(std::path::PathBuf::from(directory)),
+			None            => ShaderSource::Embedded,
+		};
+	}
+
+	pub fn read(&self, path: &str) -> Result<String, ShaderError>
+	{
+		return match self
+		{
+			ShaderSource::Embedded =>
+			{
+				EMBEDDED_SHADERS.iter()
+					.find(|(embedded_path, _)| *embedded_path == path)
+					.map(|(_, source)| String::from(*source))
+					.ok_or_else(|| ShaderError::NotEmbedded(String::from(path)))
+			},
+			ShaderSource::Directory(directory) =>
+			{
+				let path = directory.join(path);
+				std::fs::read_to_string(&path).map_err(|error| ShaderError::Read { path, error })
+			},
+		};
+	}
+
+	//NOTE(fpalacios): Prueba las versiones de GLSL_VERSIONS en orden con un programa y devuelve la primera que compila. Se
+	//saltean las que el contexto no soporta y las que no tienen los archivos, pero un error de compilacion se devuelve tal
+	//cual porque es un bug del shader y no de la placa. Los demas programas se compilan despues con la version que salio
+	pub fn negotiate(&self, facade: &impl glium::backend::Facade, vertex: &str, fragment: &str) -> Result<(glium::Program, &'static GlslVersion), ShaderError>
+	{
+		let context = facade.get_context();
+		let mut tried = Vec::new();
+
+		for glsl_version in GLSL_VERSIONS
+		{
+			if !context.is_glsl_version_supported(&glsl_version.version)
+			{
+				tried.push(format!("{}: no soportada", glsl_version.directory));
+				continue;
+			}
+
+			match self.compile(facade, glsl_version, vertex, fragment)
+			{
+				Ok(program)                            => return Ok((program, glsl_version)),
+				Err(err @ ShaderError::Compile { .. }) => return Err(err),
+				Err(err)                               => tried.push(format!("{}: {}", glsl_version.directory, err)),
+			}
+		}
+
+		return Err(
+			ShaderError::NoSupportedVersion
+			{
+				supported: context.get_supported_glsl_version(),
+				tried,
+			}
+		);
+	}
+
+	pub fn compile(&self, facade: &impl glium::backend::Facade, glsl_version: &GlslVersion, vertex: &str, fragment: &str) -> Result<glium::Program, ShaderError>
+	{
+		return self.compile_program(facade, glsl_version, vertex, fragment, false);
+	}
+
+	//NOTE(fpalacios): Para los shaders que ya escriben el color codificado en sRGB. glium apaga GL_FRAMEBUFFER_SRGB mientras
+	//se usan y lo que sale del shader va tal cual al framebuffer
+	pub fn compile_encoded(&self, facade: &impl glium::backend::Facade, glsl_version: &GlslVersion, vertex: &str, fragment: &str) -> Result<glium::Program, ShaderError>
+	{
+		return self.compile_program(facade, glsl_version, vertex, fragment, true);
+	}
+
+	fn compile_program(
+		&self,
+		facade      : &impl glium::backend::Facade,
+		glsl_version: &GlslVersion,
+		vertex      : &str,
+		fragment    : &str,
+		outputs_srgb: bool
+	)
+	-> Result<glium::Program, ShaderError>
+	{
+		let vertex_path   = format!("{}/{}", glsl_version.directory, vertex);
+		let fragment_path = format!("{}/{}", glsl_version.directory, fragment);
+
+		let vertex_source   = self.read(&vertex_path)?;
+		let fragment_source = self.read(&fragment_path)?;
+
+		return glium::Program::new(
+			facade,
+			glium::program::ProgramCreationInput::SourceCode
+			{
+				vertex_shader                 : &vertex_source,
+				tessellation_control_shader   : None,
+				tessellation_evaluation_shader: None,
+				geometry_shader               : None,
+				fragment_shader               : &fragment_source,
+				transform_feedback_varyings   : None,
+				outputs_srgb,
+				uses_point_size               : false,
+			}
+		).map_err(|error|
+			ShaderError::Compile
+			{
+				vertex  : vertex_path,
+				fragment: fragment_path,
+				error,
+			}
+		);
+	}
+}
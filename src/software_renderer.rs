@@ -0,0 +1,514 @@
+//NOTE(fpalacios): Backend sin OpenGL. Compone los sprites en la CPU sobre un buffer ARGB y lo manda al server con put_image,
+//para las maquinas donde no hay GLX
+pub struct SoftwareRenderer
+{
+	pub width : u32,
+	pub height: u32,
+	//NOTE(fpalacios): BGRA con alpha premultiplicado, que es lo que espera un visual ARGB de 32 bits en little endian
+	pixels    : Vec<u8>,
+	//NOTE(fpalacios): Igual que la textura scene de PostProcessor, BGRA con el color lineal premultiplicado guardado
+	//codificado en sRGB y el alpha lineal. Se mezcla en lineal y end_frame lo pasa a pixels como el pase de salida
+	scene     : Vec<u8>,
+	//NOTE(fpalacios): Profundidad de 0.0 a 1.0 por pixel para las mallas, como el depth buffer de OpenGL. Los sprites no la tocan
+	depth     : Vec<f32>,
+	//NOTE(fpalacios): De sRGB a lineal para cada valor de un canal
+	decode    : Vec<f32>,
+}
+
+impl SoftwareRenderer
+{
+	pub fn new(width: u32, height: u32) -> SoftwareRenderer
+	{
+		return SoftwareRenderer
+		{
+			width,
+			height,
+			pixels: vec![0; (width * height * 4) as usize],
+			scene : vec![0; (width * height * 4) as usize],
+			depth : vec![1.0; (width * height) as usize],
+			decode: (0 ..= 255).map(|value| crate::color::srgb_to_linear(value as f32 / 255.0)).collect(),
+		};
+	}
+
+	pub fn resize(&mut self, width: u32, height: u32)
+	{
+		self.width  = width;
+		self.height = height;
+		self.pixels = vec![0; (width * height * 4) as usize];
+		self.scene  = vec![0; (width * height * 4) as usize];
+		self.depth  = vec![1.0; (width * height) as usize];
+	}
+
+	pub fn clear(&mut self)
+	{
+		for byte in self.pixels.iter_mut().chain(self.scene.iter_mut())
+		{
+			*byte = 0;
+		}
+
+		for depth in self.depth.iter_mut()
+		{
+			*depth = 1.0;
+		}
+	}
+
+	pub fn pixels(&self) -> &[u8]
+	{
+		return &self.pixels;
+	}
+
+	//NOTE(fpalacios): Para guardar en PNG, pasa a RGBA sin premultiplicar
+	pub fn to_image(&self) -> image::RgbaImage
+	{
+		let mut image = image::RgbaImage::new(self.width, self.height);
+
+		for (pixel, bgra) in image.pixels_mut().zip(self.pixels.chunks_exact(4))
+		{
+			pixel.0 = [bgra[2], bgra[1], bgra[0], bgra[3]];
+		}
+
+		return crate::color::unpremultiply(&image);
+	}
+
+	//NOTE(fpalacios): "Source over" premultiplicado en lineal, como el blending de OpenGL sobre una textura sRGB. source va
+	//en BGRA de 0.0 a 1.0, con el color lineal ya multiplicado por su alpha
+	fn blend(&mut self, index: usize, source: [f32; 4])
+	{
+		let inverse_alpha = 1.0 - source[3];
+		let destination = &mut self.scene[index .. index + 4];
+
+		for channel in 0 .. 3
+		{
+			let linear = (source[channel] + self.decode[destination[channel] as usize] * inverse_alpha).clamp(0.0, 1.0);
+			destination[channel] = (crate::color::linear_to_srgb(linear) * 255.0).round() as u8;
+		}
+
+		destination[3] = ((source[3] + destination[3] as f32 / 255.0 * inverse_alpha).clamp(0.0, 1.0) * 255.0).round() as u8;
+	}
+
+	//NOTE(fpalacios): Lo mismo que OUTPUT_FRAGMENT_SHADER: divide por alpha, codifica en sRGB y premultiplica despues de
+	//codificar, que es lo que espera el compositor
+	fn resolve(&mut self)
+	{
+		for (pixel, scene) in self.pixels.chunks_exact_mut(4).zip(self.scene.chunks_exact(4))
+		{
+			let alpha = scene[3];
+
+			if alpha == 0
+			{
+				pixel.copy_from_slice(&[0, 0, 0, 0]);
+				continue;
+			}
+
+			for channel in 0 .. 3
+			{
+				let straight = (self.decode[scene[channel] as usize] / (alpha as f32 / 255.0)).clamp(0.0, 1.0);
+				pixel[channel] = crate::color::premultiply((crate::color::linear_to_srgb(straight) * 255.0).round() as u8, alpha);
+			}
+
+			pixel[3] = alpha;
+		}
+	}
+
+	//NOTE(fpalacios): Un triangulo ya recortado contra el plano near, con las coordenadas de la ventana de OpenGL (y crece
+	//para arriba). Interpola con correccion de perspectiva y sombrea igual que MeshFragShader
+	fn rasterize(&mut self, triangle: [&ClipVertex; 3], shading: &MeshShading, viewport: (u32, u32, u32, u32))
+	{
+		let (left, bottom, viewport_width, viewport_height) = viewport;
+
+		//NOTE(fpalacios): x e y en pixeles, z la profundidad y w la inversa de w para la correccion de perspectiva
+		let window: Vec<nalgebra::Vector4<f32>> = triangle.iter().map(|vertex|
+		{
+			let inverse_w = 1.0 / vertex.position.w;
+			let ndc = vertex.position.xyz() * inverse_w;
+
+			return nalgebra::Vector4::new(
+				left   as f32 + (ndc.x + 1.0) / 2.0 * viewport_width  as f32,
+				bottom as f32 + (ndc.y + 1.0) / 2.0 * viewport_height as f32,
+				ndc.z * 0.5 + 0.5,
+				inverse_w
+			);
+		}).collect();
+
+		let edge = |a: &nalgebra::Vector4<f32>, b: &nalgebra::Vector4<f32>, x: f32, y: f32| (b.x - a.x) * (y - a.y) - (b.y - a.y) * (x - a.x);
+
+		let area = edge(&window[0], &window[1], window[2].x, window[2].y);
+		if area == 0.0 || !area.is_finite()
+		{
+			return;
+		}
+
+		//NOTE(fpalacios): Fuera del viewport OpenGL lo recorta con los planos x e y, aca alcanza con no recorrer esos pixeles
+		let start_x = std::cmp::max(window.iter().map(|vertex| vertex.x).fold(f32::INFINITY, f32::min).floor() as i64, left as i64);
+		let start_y = std::cmp::max(window.iter().map(|vertex| vertex.y).fold(f32::INFINITY, f32::min).floor() as i64, bottom as i64);
+		let end_x   = std::cmp::min(
+			window.iter().map(|vertex| vertex.x).fold(f32::NEG_INFINITY, f32::max).ceil() as i64,
+			std::cmp::min(left as i64 + viewport_width as i64, self.width as i64)
+		);
+		let end_y   = std::cmp::min(
+			window.iter().map(|vertex| vertex.y).fold(f32::NEG_INFINITY, f32::max).ceil() as i64,
+			std::cmp::min(bottom as i64 + viewport_height as i64, self.height as i64)
+		);
+
+		for y in start_y.max(0) .. end_y
+		{
+			for x in start_x.max(0) .. end_x
+			{
+				let (center_x, center_y) = (x as f32 + 0.5, y as f32 + 0.5);
+
+				//NOTE(fpalacios): Dividir por el area con signo deja las dos caras igual, OpenGL tampoco descarta ninguna
+				let barycentric = [
+					edge(&window[1], &window[2], center_x, center_y) / area,
+					edge(&window[2], &window[0], center_x, center_y) / area,
+					edge(&window[0], &window[1], center_x, center_y) / area,
+				];
+
+				if barycentric.iter().any(|weight| *weight < 0.0)
+				{
+					continue;
+				}
+
+				let depth = barycentric[0] * window[0].z + barycentric[1] * window[1].z + barycentric[2] * window[2].z;
+
+				//NOTE(fpalacios): El buffer tiene y para abajo
+				let pixel = ((self.height as i64 - 1 - y) as u32 * self.width + x as u32) as usize;
+
+				if depth > 1.0 || depth >= self.depth[pixel]
+				{
+					continue;
+				}
+
+				self.depth[pixel] = depth;
+
+				let weights = [barycentric[0] * window[0].w, barycentric[1] * window[1].w, barycentric[2] * window[2].w];
+				let total   = weights[0] + weights[1] + weights[2];
+				let weights = [weights[0] / total, weights[1] / total, weights[2] / total];
+
+				let interpolate = |attribute: &dyn Fn(&ClipVertex) -> nalgebra::Vector3<f32>|
+				{
+					return attribute(triangle[0]) * weights[0] + attribute(triangle[1]) * weights[1] + attribute(triangle[2]) * weights[2];
+				};
+
+				let normal         = interpolate(&|vertex| vertex.normal);
+				let color          = interpolate(&|vertex| vertex.color);
+				let texture_coords = interpolate(&|vertex| nalgebra::Vector3::new(vertex.texture_coords.x, vertex.texture_coords.y, 0.0));
+
+				let [r, g, b, a] = shading.texel(texture_coords.x, texture_coords.y);
+
+				if a == 0
+				{
+					continue;
+				}
+
+				let diffuse = normal.try_normalize(0.0).map(|normal| normal.dot(&-shading.light_direction).max(0.0)).unwrap_or(0.0);
+				let light   = 0.35 + 0.65 * diffuse;
+
+				//NOTE(fpalacios): En lineal como el shader, sale premultiplicado para el blending
+				let alpha = a as f32 / 255.0;
+				let decode = &self.decode;
+				let shade = |channel: f32, value: u8| (channel * decode[value as usize] * light).clamp(0.0, 1.0) * alpha;
+				let source = [shade(color.z, b), shade(color.y, g), shade(color.x, r), alpha];
+
+				self.blend(pixel * 4, source);
+			}
+		}
+	}
+
+	pub fn present(&self, graphics_context: &crate::platform::xcb::GraphicsContext, drawable: crate::platform::xcb::DrawableID)
+	{
+		graphics_context.put_argb32(drawable, (self.width as u16, self.height as u16), (0, 0), &self.pixels);
+	}
+}
+
+impl crate::renderer::Renderer for SoftwareRenderer
+{
+	fn begin_frame(&mut self)
+	{
+		self.clear();
+	}
+
+	//NOTE(fpalacios): Recorre los pixeles que tapa el sprite transformado y los lleva de vuelta al sprite con la inversa,
+	//muestrea con nearest neighbour y compone en lineal con "source over" premultiplicado. Las opciones de la textura no
+	//se usan
+	fn draw_sprite(
+		&mut self,
+		image    : &std::rc::Rc<image::RgbaImage>,
+		_options : &crate::assets::TextureOptions,
+		area     : &crate::world::Area,
+		transform: &crate::renderer::Transform2D
+	)
+	{
+		let (image_width, image_height) = image.dimensions();
+		let tint = transform.color();
+
+		if image_width == 0 || image_height == 0 || tint[3] <= 0.0
+		{
+			return;
+		}
+
+		let half_width  = self.width  as f32 / 2.0;
+		let half_height = self.height as f32 / 2.0;
+
+		//NOTE(fpalacios): En coordenadas normalizadas y crece para arriba, en el buffer para abajo
+		let window_to_buffer = nalgebra::Matrix3::new(
+			half_width, 0.0,          half_width,
+			0.0,        -half_height, half_height,
+			0.0,        0.0,          1.0
+		);
+
+		let sprite_to_buffer = window_to_buffer * transform.matrix(area, (self.width, self.height));
+
+		let buffer_to_sprite = match sprite_to_buffer.try_inverse()
+		{
+			Some(matrix) => matrix,
+			//NOTE(fpalacios): Escala 0, no se ve
+			None         => return,
+		};
+
+		let corners: Vec<nalgebra::Point2<f32>> = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)].iter()
+			.map(|(x, y)| sprite_to_buffer.transform_point(&nalgebra::Point2::new(*x, *y)))
+			.collect();
+
+		let start_x = std::cmp::max(corners.iter().map(|corner| corner.x).fold(f32::INFINITY, f32::min).floor() as i64, 0);
+		let start_y = std::cmp::max(corners.iter().map(|corner| corner.y).fold(f32::INFINITY, f32::min).floor() as i64, 0);
+		let end_x   = std::cmp::min(corners.iter().map(|corner| corner.x).fold(f32::NEG_INFINITY, f32::max).ceil() as i64, self.width as i64);
+		let end_y   = std::cmp::min(corners.iter().map(|corner| corner.y).fold(f32::NEG_INFINITY, f32::max).ceil() as i64, self.height as i64);
+
+		for y in start_y .. end_y
+		{
+			for x in start_x .. end_x
+			{
+				let sprite_point = buffer_to_sprite.transform_point(&nalgebra::Point2::new(x as f32 + 0.5, y as f32 + 0.5));
+
+				if sprite_point.x < 0.0 || sprite_point.x >= 1.0 || sprite_point.y <= 0.0 || sprite_point.y > 1.0
+				{
+					continue;
+				}
+
+				let u = ((sprite_point.x * image_width as f32) as u32).min(image_width - 1);
+				//NOTE(fpalacios): En el sprite y crece para arriba y en la imagen para abajo
+				let v = (((1.0 - sprite_point.y) * image_height as f32) as u32).min(image_height - 1);
+
+				let [r, g, b, a] = image.get_pixel(u, v).0;
+
+				if a == 0
+				{
+					continue;
+				}
+
+				//NOTE(fpalacios): Como TextureFragShader, el texel lineal premultiplicado por el tinte que ya viene premultiplicado
+				let alpha = a as f32 / 255.0;
+				let source = [
+					self.decode[b as usize] * alpha * tint[2],
+					self.decode[g as usize] * alpha * tint[1],
+					self.decode[r as usize] * alpha * tint[0],
+					alpha * tint[3],
+				];
+
+				self.blend(((y as u32 * self.width + x as u32) * 4) as usize, source);
+			}
+		}
+	}
+
+	//NOTE(fpalacios): Rasteriza los triangulos con z-buffer, con el mismo viewport y la misma luz que GliumRenderer. La
+	//textura se muestrea con nearest y repeat, y se mezcla en lineal como los sprites
+	fn draw_mesh(
+		&mut self,
+		mesh    : &std::rc::Rc<crate::mesh::Mesh>,
+		vertices: Option<&[crate::renderer::Vertex3D]>,
+		model   : &nalgebra::Matrix4<f32>,
+		camera  : &crate::camera::Camera,
+		area    : &crate::world::Area
+	)
+	{
+		let vertices = vertices.filter(|vertices| vertices.len() == mesh.vertices.len()).unwrap_or(&mesh.vertices);
+
+		let viewport = (
+			((area.x + 1.0) / 2.0 * self.width  as f32).max(0.0) as u32,
+			((area.y + 1.0) / 2.0 * self.height as f32).max(0.0) as u32,
+			(area.width  / 2.0 * self.width  as f32).max(1.0) as u32,
+			(area.height / 2.0 * self.height as f32).max(1.0) as u32,
+		);
+
+		let clip_matrix   = camera.projection_matrix(viewport.2 as f32 / viewport.3 as f32) * camera.view_matrix() * model;
+		//NOTE(fpalacios): Igual que mat3(model) en MeshVertShader
+		let normal_matrix = model.fixed_slice::<3, 3>(0, 0).into_owned();
+
+		let clip_vertices: Vec<ClipVertex> = vertices.iter().map(|vertex| ClipVertex
+		{
+			position      : clip_matrix * nalgebra::Vector4::new(vertex.position[0], vertex.position[1], vertex.position[2], 1.0),
+			normal        : normal_matrix * nalgebra::Vector3::from(vertex.normal),
+			texture_coords: nalgebra::Vector2::from(vertex.texture_coords),
+			color         : nalgebra::Vector3::from(vertex.color),
+		}).collect();
+
+		let shading = MeshShading
+		{
+			texture        : mesh.texture.as_deref(),
+			light_direction: nalgebra::Vector3::from(crate::renderer::LIGHT_DIRECTION),
+		};
+
+		for triangle in mesh.indices.chunks_exact(3)
+		{
+			let triangle: Option<Vec<&ClipVertex>> = triangle.iter().map(|index| clip_vertices.get(*index as usize)).collect();
+
+			let polygon = match triangle
+			{
+				Some(triangle) => clip_near(&triangle),
+				None           => continue,
+			};
+
+			for index in 1 .. polygon.len().saturating_sub(1)
+			{
+				self.rasterize([&polygon[0], &polygon[index], &polygon[index + 1]], &shading, viewport);
+			}
+		}
+	}
+
+	//NOTE(fpalacios): Deja el buffer listo, present lo manda al server porque necesita la conexion
+	fn end_frame(&mut self)
+	{
+		self.resolve();
+	}
+
+	//NOTE(fpalacios): Lee directo de la imagen, no guarda nada
+	fn release_texture(&mut self, _image: &std::rc::Rc<image::RgbaImage>)
+	{
+	}
+
+	fn release_mesh(&mut self, _mesh: &std::rc::Rc<crate::mesh::Mesh>)
+	{
+	}
+}
+
+//NOTE(fpalacios): Un vertice despues de MeshVertShader
+#[derive(Clone)]
+struct ClipVertex
+{
+	position      : nalgebra::Vector4<f32>,
+	normal        : nalgebra::Vector3<f32>,
+	texture_coords: nalgebra::Vector2<f32>,
+	color         : nalgebra::Vector3<f32>,
+}
+
+impl ClipVertex
+{
+	fn lerp(&self, other: &ClipVertex, amount: f32) -> ClipVertex
+	{
+		return ClipVertex
+		{
+			position      : self.position.lerp(&other.position, amount),
+			normal        : self.normal.lerp(&other.normal, amount),
+			texture_coords: self.texture_coords.lerp(&other.texture_coords, amount),
+			color         : self.color.lerp(&other.color, amount),
+		};
+	}
+}
+
+//NOTE(fpalacios): Lo que usa MeshFragShader y es igual para toda la malla
+struct MeshShading<'mesh>
+{
+	//NOTE(fpalacios): None es blanco, como white_image en GliumRenderer
+	texture        : Option<&'mesh image::RgbaImage>,
+	light_direction: nalgebra::Vector3<f32>,
+}
+
+impl MeshShading<'_>
+{
+	fn texel(&self, u: f32, v: f32) -> [u8; 4]
+	{
+		let texture = match self.texture
+		{
+			Some(texture) if texture.width() > 0 && texture.height() > 0 => texture,
+			_                                                             => return [255, 255, 255, 255],
+		};
+
+		let (width, height) = texture.dimensions();
+		let x = (((u - u.floor()) * width as f32) as u32).min(width - 1);
+		//NOTE(fpalacios): Las texturas se suben dadas vuelta, v = 0 es la ultima fila de la imagen
+		let y = (((1.0 - (v - v.floor())) * height as f32) as u32).min(height - 1);
+
+		return texture.get_pixel(x, y).0;
+	}
+}
+
+//NOTE(fpalacios): Sutherland-Hodgman contra el plano near (z >= -w en clip space). Lo que queda detras de la camara no se
+//puede dividir por w. Devuelve un poligono convexo de 0, 3 o 4 vertices
+fn clip_near(triangle: &[&ClipVertex]) -> Vec<ClipVertex>
+{
+	let mut polygon = Vec::with_capacity(4);
+
+	for (index, current) in triangle.iter().enumerate()
+	{
+		let next = triangle[(index + 1) % triangle.len()];
+
+		let current_distance = current.position.z + current.position.w;
+		let next_distance    = next.position.z + next.position.w;
+
+		if current_distance >= 0.0
+		{
+			polygon.push((*current).clone());
+		}
+
+		if (current_distance >= 0.0) != (next_distance >= 0.0)
+		{
+			polygon.push(current.lerp(next, current_distance / (current_distance - next_distance)));
+		}
+	}
+
+	return polygon;
+}
+
+#[cfg(test)]
+mod tests
+{
+	fn sprite(pixel: [u8; 4], renderer: &mut super::SoftwareRenderer)
+	{
+		let image = image::RgbaImage::from_pixel(1, 1, image::Rgba(pixel));
+
+		crate::renderer::Renderer::draw_sprite(
+			renderer,
+			&std::rc::Rc::new(image),
+			&crate::assets::TextureOptions::new(),
+			&crate::world::Area::full_window(),
+			&crate::renderer::Transform2D::new()
+		);
+	}
+
+	//NOTE(fpalacios): Verde con alpha 128 sobre rojo. Mezclando en sRGB quedaria (127, 128, 0), en lineal cada uno aporta
+	//la mitad y codificado queda cerca de 188
+	#[test]
+	fn blends_in_linear()
+	{
+		let mut renderer = super::SoftwareRenderer::new(2, 2);
+
+		crate::renderer::Renderer::begin_frame(&mut renderer);
+		sprite([255, 0, 0, 255], &mut renderer);
+		sprite([0, 255, 0, 128], &mut renderer);
+		crate::renderer::Renderer::end_frame(&mut renderer);
+
+		for pixel in renderer.to_image().pixels()
+		{
+			assert_eq!(pixel.0, [187, 188, 0, 255]);
+		}
+	}
+
+	//NOTE(fpalacios): Sobre transparente el color tiene que salir igual que en la imagen, solo con menos alpha
+	#[test]
+	fn translucent_over_nothing()
+	{
+		let mut renderer = super::SoftwareRenderer::new(2, 2);
+
+		crate::renderer::Renderer::begin_frame(&mut renderer);
+		sprite([200, 100, 50, 128], &mut renderer);
+		crate::renderer::Renderer::end_frame(&mut renderer);
+
+		for pixel in renderer.to_image().pixels()
+		{
+			for (channel, expected) in pixel.0.iter().zip([200, 100, 50, 128])
+			{
+				assert!((*channel as i32 - expected).abs() <= 2, "{:?}", pixel.0);
+			}
+		}
+	}
+}
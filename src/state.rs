@@ -0,0 +1,147 @@
+//NOTE(fpalacios): Lo que el jardin guarda entre ejecuciones va en $XDG_STATE_HOME/jardincho, o ~/.local/state/jardincho si
+//la variable no esta. "jardincho reset" borra el directorio entero
+pub fn directory() -> Option<std::path::PathBuf>
+{
+	let state_home = std::env::var_os("XDG_STATE_HOME")
+		.filter(|value| !value.is_empty())
+		.map(std::path::PathBuf::from)
+		.or_else(|| std::env::var_os("HOME").map(|home| std::path::Path::new(&home).join(".local/state")))?;
+
+	return Some(state_home.join("jardincho"));
+}
+
+pub const GARDEN_FILE: &str = "garden.json";
+
+//NOTE(fpalacios): Lo que cambia mientras corre (con el socket de control) y no esta en la configuracion. Se guarda como
+//{ "plants": [ { "name": "bonsai", "water": 0.8 } ], "species": ["bonsai"], "weather": "clear", "paused": false, "saved_at": 1700000000 }
+#[derive(Debug, Clone, PartialEq)]
+pub struct GardenState
+{
+	pub plants  : Vec<crate::garden::GardenPlant>,
+	//NOTE(fpalacios): Las plantas que habia en la configuracion al guardar, para saber cuales se agregaron despues
+	pub species : Vec<String>,
+	pub weather : crate::garden::Weather,
+	pub paused  : bool,
+	pub saved_at: std::time::SystemTime,
+}
+
+impl GardenState
+{
+	//NOTE(fpalacios): None si nunca se guardo nada
+	pub fn load() -> Result<Option<GardenState>, String>
+	{
+		let path = match directory()
+		{
+			Some(directory) => directory.join(GARDEN_FILE),
+			None            => return Ok(None),
+		};
+
+		let source = match std::fs::read_to_string(&path)
+		{
+			Ok(source) => source,
+			Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+			Err(err) => return Err(format!("No se pudo leer {}: {}", path.display(), err)),
+		};
+
+		let invalid = |message: &str| format!("Error en {}: {}", path.display(), message);
+
+		let json = crate::json::parse(&source)
+			.map_err(|err| invalid(&format!("JSON invalido en la posicion {}: {}", err.position, err.message)))?;
+
+		let mut plants = Vec::new();
+		for plant in json.get("plants").and_then(crate::json::Json::as_array).ok_or_else(|| invalid("falta la lista \"plants\""))?
+		{
+			plants.push(
+				crate::garden::GardenPlant
+				{
+					name : String::from(plant.get("name").and_then(crate::json::Json::as_str).ok_or_else(|| invalid("una planta no tiene \"name\""))?),
+					water: plant.get("water").and_then(crate::json::Json::as_f64).unwrap_or(1.0).clamp(0.0, 1.0) as f32,
+				}
+			);
+		}
+
+		//NOTE(fpalacios): Los archivos viejos no tienen "species", se toman las que estan sembradas
+		let species = match json.get("species").and_then(crate::json::Json::as_array)
+		{
+			Some(species) => species.iter().filter_map(crate::json::Json::as_str).map(String::from).collect(),
+			None          => plants.iter().map(|plant| plant.name.clone()).collect(),
+		};
+
+		let weather = json.get("weather")
+			.and_then(crate::json::Json::as_str)
+			.and_then(crate::garden::Weather::parse)
+			.unwrap_or(crate::garden::Weather::Clear);
+
+		let saved_at = json.get("saved_at")
+			.and_then(crate::json::Json::as_f64)
+			.and_then(from_unix_seconds)
+			.unwrap_or_else(std::time::SystemTime::now);
+
+		return Ok(
+			Some(
+				GardenState
+				{
+					plants,
+					species,
+					weather,
+					paused: json.get("paused").and_then(crate::json::Json::as_bool).unwrap_or(false),
+					saved_at,
+				}
+			)
+		);
+	}
+
+	//NOTE(fpalacios): Escribe a un temporal y despues lo renombra, asi si se corta a la mitad no queda un archivo roto
+	pub fn save(&self) -> Result<(), String>
+	{
+		let directory = directory().ok_or_else(|| String::from("No se sabe donde guardar el estado, falta HOME"))?;
+		std::fs::create_dir_all(&directory).map_err(|err| format!("No se pudo crear {}: {}", directory.display(), err))?;
+
+		let saved_at = self.saved_at.duration_since(std::time::UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).unwrap_or(0);
+
+		let json = crate::json::Json::Object(vec![
+			(String::from("plants"), crate::json::Json::Array(
+				self.plants.iter().map(|plant| crate::json::Json::Object(vec![
+					(String::from("name"),  crate::json::Json::String(plant.name.clone())),
+					(String::from("water"), crate::json::Json::Number(plant.water as f64)),
+				])).collect()
+			)),
+			(String::from("species"),  crate::json::Json::Array(
+				self.species.iter().map(|name| crate::json::Json::String(name.clone())).collect()
+			)),
+			(String::from("weather"),  crate::json::Json::String(String::from(self.weather.name()))),
+			(String::from("paused"),   crate::json::Json::Bool(self.paused)),
+			(String::from("saved_at"), crate::json::Json::Number(saved_at as f64)),
+		]);
+
+		let path = directory.join(GARDEN_FILE);
+		let temporary_path = directory.join(format!("{}.tmp", GARDEN_FILE));
+
+		std::fs::write(&temporary_path, json.to_string())
+			.and_then(|_| std::fs::rename(&temporary_path, &path))
+			.map_err(|err| format!("No se pudo guardar {}: {}", path.display(), err))?;
+
+		return Ok(());
+	}
+}
+
+//NOTE(fpalacios): None si el numero no entra en un SystemTime, con un archivo editado a mano se toma como recien guardado
+fn from_unix_seconds(seconds: f64) -> Option<std::time::SystemTime>
+{
+	let elapsed = std::time::Duration::try_from_secs_f64(seconds.max(0.0)).ok()?;
+	return std::time::UNIX_EPOCH.checked_add(elapsed);
+}
+
+#[cfg(test)]
+mod tests
+{
+	#[test]
+	fn from_unix_seconds()
+	{
+		assert_eq!(super::from_unix_seconds(1700000000.0), Some(std::time::UNIX_EPOCH + std::time::Duration::from_secs(1700000000)));
+		assert_eq!(super::from_unix_seconds(-5.0), Some(std::time::UNIX_EPOCH));
+		assert_eq!(super::from_unix_seconds(1e300), None);
+		assert_eq!(super::from_unix_seconds(f64::INFINITY), None);
+		assert_eq!(super::from_unix_seconds(1.8e19), None);
+	}
+}
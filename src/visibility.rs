@@ -0,0 +1,95 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RenderMode
+{
+	Normal,
+	//NOTE(fpalacios): Se dibuja pero a pocos fps, por ejemplo en bateria o si hace rato que nadie toca la compu
+	Throttled,
+	//NOTE(fpalacios): No se dibuja nada, la simulacion sigue avanzando con el reloj cuando se vuelve a despertar
+	Suspended,
+}
+
+//NOTE(fpalacios): Junta todo lo que dice si vale la pena dibujar: si la ventana se ve, si esta bloqueada la pantalla y la bateria
+pub struct DesktopState
+{
+	pub visibility         : crate::platform::xcb::Visibility,
+	pub hidden             : bool,
+	pub screen_saver_active: bool,
+	pub idle               : bool,
+	pub on_battery         : bool,
+
+	pub throttle_on_battery: bool,
+	pub idle_timeout       : std::time::Duration,
+	pub check_interval     : std::time::Duration,
+	next_check             : std::time::Instant,
+}
+
+impl Default for DesktopState
+{
+	fn default() -> DesktopState
+	{
+		return DesktopState::new();
+	}
+}
+
+impl DesktopState
+{
+	pub fn new() -> DesktopState
+	{
+		return DesktopState
+		{
+			visibility         : crate::platform::xcb::Visibility::Unobscured,
+			hidden             : false,
+			screen_saver_active: false,
+			idle               : false,
+			on_battery         : false,
+
+			throttle_on_battery: true,
+			idle_timeout       : std::time::Duration::from_secs(5 * 60),
+			check_interval     : std::time::Duration::from_secs(30),
+			next_check         : std::time::Instant::now(),
+		};
+	}
+
+	pub fn render_mode(&self) -> RenderMode
+	{
+		if self.hidden || self.screen_saver_active || self.visibility == crate::platform::xcb::Visibility::FullyObscured
+		{
+			return RenderMode::Suspended;
+		}
+
+		if self.idle || (self.throttle_on_battery && self.on_battery)
+		{
+			return RenderMode::Throttled;
+		}
+
+		return RenderMode::Normal;
+	}
+
+	pub fn next_check(&self) -> std::time::Instant
+	{
+		return self.next_check;
+	}
+
+	//NOTE(fpalacios): La bateria y el tiempo sin tocar la compu no avisan con eventos, hay que consultarlos cada tanto
+	pub fn check(&mut self, now: std::time::Instant, screen: &crate::platform::xcb::Screen) -> bool
+	{
+		if now < self.next_check
+		{
+			return false;
+		}
+
+		let previous_mode = self.render_mode();
+
+		self.on_battery = crate::platform::power::on_battery();
+
+		if let Ok(info) = screen.screen_saver_info()
+		{
+			self.screen_saver_active = info.active;
+			self.idle = info.idle_time >= self.idle_timeout;
+		}
+
+		self.next_check = now + self.check_interval;
+
+		return previous_mode != self.render_mode();
+	}
+}
@@ -1,121 +1,875 @@
-use glium::program;
+//NOTE(fpalacios): Vueltas por segundo del modelo 3D, lento para que no distraiga
+const MODEL_ROTATION_SPEED: f32 = 0.05;
+
+pub enum PlantVisual
+{
+	Sprite(crate::renderer::Animation2D),
+	Model
+	{
+		model   : std::rc::Rc<crate::mesh::Model>,
+		//NOTE(fpalacios): Angulo alrededor del eje y, en radianes
+		rotation: f32,
+		//NOTE(fpalacios): Segundos de la animacion del modelo
+		time    : f32,
+	},
+}
+
 pub struct Plant
 {
-	animation: crate::renderer::Animation2D,
+	pub visual      : PlantVisual,
+	//NOTE(fpalacios): Donde se dibuja el sprite o el viewport del modelo, en coordenadas normalizadas de la ventana
+	pub sprite_area : Area,
+	//NOTE(fpalacios): Posicion, escala, tinte, etc del sprite dentro de sprite_area. Los modelos no lo usan
+	pub transform   : crate::renderer::Transform2D,
+	//NOTE(fpalacios): Indice en World::garden, las copias de la misma planta en cada monitor comparten el agua
+	pub garden_index: usize,
 }
 
 impl Plant
 {
-	fn update(&mut self, delta: &std::time::Duration)
+	//NOTE(fpalacios): Si se armo con los assets de esa especie. Cuando la configuracion cambia una planta la especie es otra
+	fn is_species(&self, species: &Species) -> bool
+	{
+		return match (&self.visual, &species.visual)
+		{
+			(PlantVisual::Sprite(animation), SpeciesVisual::Sprite(root_steps_chain)) => std::rc::Rc::ptr_eq(&animation.root_steps_chain, root_steps_chain),
+			(PlantVisual::Model { model, .. }, SpeciesVisual::Model(species_model))   => std::rc::Rc::ptr_eq(model, species_model),
+			_                                                                         => false,
+		};
+	}
+
+	fn update(&mut self, delta: &std::time::Duration) -> bool
 	{
-		self.animation.update(delta);
+		return match &mut self.visual
+		{
+			PlantVisual::Sprite(animation) => animation.update(delta),
+			PlantVisual::Model { model, rotation, time } =>
+			{
+				*rotation = (*rotation + MODEL_ROTATION_SPEED * std::f32::consts::TAU * delta.as_secs_f32()) % std::f32::consts::TAU;
+				*time += delta.as_secs_f32();
+
+				if let Some(animation) = model.animation.as_ref().filter(|animation| animation.duration > 0.0)
+				{
+					*time %= animation.duration;
+				}
+				true
+			},
+		};
 	}
+}
+
+//NOTE(fpalacios): Rectangulo en coordenadas normalizadas de la ventana (-1.0 a 1.0) donde va una planta
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Area
+{
+	pub x     : f32,
+	pub y     : f32,
+	pub width : f32,
+	pub height: f32,
+}
 
-	pub fn draw(&self, display: &glium::Display)
+impl Area
+{
+	pub fn full_window() -> Area
 	{
-		crate::renderer::draw_animation_2d(&display, &self.animation);
+		return Area
+		{
+			x     : -1.0,
+			y     : -1.0,
+			width :  2.0,
+			height:  2.0,
+		};
+	}
+}
+
+//NOTE(fpalacios): Una planta de la configuracion, de la que se hace una copia en cada monitor
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlantKind
+{
+	Sprite
+	{
+		//NOTE(fpalacios): Nombres de los assets, uno por paso de la animacion
+		frames        : Vec<String>,
+		frame_duration: std::time::Duration,
+	},
+	Model(std::path::PathBuf),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlantDefinition
+{
+	pub name: String,
+	pub kind: PlantKind,
+}
+
+//NOTE(fpalacios): Una PlantDefinition con los assets ya cargados
+#[derive(Clone)]
+struct Species
+{
+	definition: PlantDefinition,
+	visual    : SpeciesVisual,
+}
+
+#[derive(Clone)]
+enum SpeciesVisual
+{
+	Sprite(std::rc::Rc<crate::renderer::Animation2DStep>),
+	Model(std::rc::Rc<crate::mesh::Model>),
+}
+
+#[derive(Debug)]
+pub enum WorldError
+{
+	Asset(crate::assets::AssetError),
+	Model
+	{
+		path : std::path::PathBuf,
+		error: crate::mesh::MeshError,
+	},
+	NoFrames(String),
+}
+
+impl std::fmt::Display for WorldError
+{
+	fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result
+	{
+		return match self
+		{
+			WorldError::Asset(error)          => write!(formatter, "{}", error),
+			WorldError::Model { path, error } => write!(formatter, "No se pudo cargar el modelo {}: {:?}", path.display(), error),
+			WorldError::NoFrames(name)        => write!(formatter, "La planta {} no tiene frames", name),
+		};
 	}
 }
 
 pub struct World
 {
-	pub plant: Plant,
+	pub plants: Vec<Plant>,
+	pub camera: crate::camera::Camera,
+
+	species  : Vec<Species>,
+	garden   : Vec<crate::garden::GardenPlant>,
+	weather  : crate::garden::Weather,
+	//NOTE(fpalacios): En pausa no avanzan las animaciones ni se secan las plantas
+	paused   : bool,
+	drying   : Option<std::time::Duration>,
+	anchor   : crate::layout::Anchor,
+	scale    : f32,
+	tint     : crate::color::RgbaColor,
+	opacity  : f32,
+	areas    : Vec<Area>,
+	//NOTE(fpalacios): Duracion del ultimo tick, para poder interpolar al dibujar
+	last_tick: std::time::Duration,
 }
 
 impl World
 {
-	pub fn new(display: &glium::Display) -> World
+	pub fn new(assets: &mut crate::assets::AssetCache, config: &crate::config::Config) -> Result<World, WorldError>
 	{
-		let image1 = image::open("assets/Bonsai_1-500x500.png").unwrap().to_rgba8();
-		let image2 = image::open("assets/Bonsai_2-500x500.png").unwrap().to_rgba8();
-		let image3 = image::open("assets/Bonsai_3-500x500.png").unwrap().to_rgba8();
+		let mut world = World
+		{
+			plants   : Vec::new(),
+			camera   : crate::camera::Camera::new(),
+			species  : Vec::new(),
+			garden   : Vec::new(),
+			weather  : crate::garden::Weather::Clear,
+			paused   : false,
+			drying   : None,
+			anchor   : crate::layout::Anchor::CENTER,
+			scale    : 1.0,
+			tint     : crate::color::RgbaColor::WHITE,
+			opacity  : 1.0,
+			areas    : vec![Area::full_window()],
+			last_tick: std::time::Duration::from_millis(0),
+		};
 
-		let image_dimensions_1 = image1.dimensions();
-		let image_dimensions_2 = image2.dimensions();
-		let image_dimensions_3 = image3.dimensions();
+		world.configure(assets, config)?;
 
-		let glium_image1 = glium::texture::RawImage2d::from_raw_rgba_reversed(&image1.into_raw(), image_dimensions_1);
-		let glium_image2 = glium::texture::RawImage2d::from_raw_rgba_reversed(&image2.into_raw(), image_dimensions_2);
-		let glium_image3 = glium::texture::RawImage2d::from_raw_rgba_reversed(&image3.into_raw(), image_dimensions_3);
+		return Ok(world);
+	}
 
-		let texture1 = std::rc::Rc::new(glium::texture::CompressedSrgbTexture2d::new(display, glium_image1).unwrap());
-		let texture2 = std::rc::Rc::new(glium::texture::CompressedSrgbTexture2d::new(display, glium_image2).unwrap());
-		let texture3 = std::rc::Rc::new(glium::texture::CompressedSrgbTexture2d::new(display, glium_image3).unwrap());
+	//NOTE(fpalacios): Aplica la configuracion y vuelve a acomodar las plantas. Las plantas que no cambiaron no se vuelven a
+	//cargar. Las que se agregan a la configuracion se siembran una vez y las que se sacan se arrancan del jardin. Devuelve
+	//los modelos que se dejaron de usar para que el backend suelte las mallas
+	pub fn configure(
+		&mut self,
+		assets: &mut crate::assets::AssetCache,
+		config: &crate::config::Config
+	)
+	-> Result<Vec<std::rc::Rc<crate::mesh::Model>>, WorldError>
+	{
+		let species = load_species(assets, &config.plants, &self.species)?;
 
-		let root_steps_chain = std::rc::Rc::new(
-			crate::renderer::Animation2DStep
+		let unused_models = self.species.iter()
+			.filter_map(|old| match &old.visual
 			{
-				texture  : texture1.clone(),
-				duration : std::time::Duration::from_millis(1000),
-				next_step: Some(std::rc::Rc::new(
-					crate::renderer::Animation2DStep
-					{
-						texture  : texture2,
-						duration : std::time::Duration::from_millis(1000),
-						next_step: Some(std::rc::Rc::new(
-							crate::renderer::Animation2DStep
-							{
-								texture  : texture3,
-								duration : std::time::Duration::from_millis(1000),
-								next_step: None,
-							}
-						)),
-					}
-				)),
+				SpeciesVisual::Model(model) => Some(model.clone()),
+				SpeciesVisual::Sprite(_)    => None,
+			})
+			.filter(|model| !species.iter().any(|new| matches!(&new.visual, SpeciesVisual::Model(new_model) if std::rc::Rc::ptr_eq(model, new_model))))
+			.collect();
+
+		for definition in &config.plants
+		{
+			if !self.species.iter().any(|old| old.definition.name == definition.name)
+			{
+				self.garden.push(crate::garden::GardenPlant::new(&definition.name));
 			}
-		);
+		}
+		self.garden.retain(|plant| species.iter().any(|new| new.definition.name == plant.name));
+
+		self.species = species;
+		self.drying  = config.garden.drying;
+		self.anchor  = config.window.anchor;
+		self.scale   = config.window.scale;
+		self.tint    = config.theme.tint;
+		self.opacity = config.theme.opacity;
 
-		return World
+		let areas = self.areas.clone();
+		self.layout(&areas);
+
+		return Ok(unused_models);
+	}
+
+	//NOTE(fpalacios): Pone todas las plantas en fila en cada area, se vuelve a llamar cuando cambian los monitores. Las que
+	//ya estaban siguen con su animacion y su giro, solo cambia donde se dibujan
+	pub fn layout(&mut self, areas: &[Area])
+	{
+		self.areas = areas.to_vec();
+
+		let mut previous = std::mem::take(&mut self.plants);
+
+		if self.garden.is_empty()
+		{
+			return;
+		}
+
+		for area in areas
 		{
-			plant: Plant
+			let count  = self.garden.len() as f32;
+			let width  = 0.28 * area.width  * self.scale;
+			let height = 0.5  * area.height * self.scale;
+
+			//NOTE(fpalacios): Si la fila no entra en el monitor se achica toda junta
+			let fit = (area.width / (width * count)).min(area.height / height).min(1.0);
+			let (x, y) = self.anchor.place(area, width * fit * count, height * fit);
+
+			for (index, garden_plant) in self.garden.iter().enumerate()
 			{
-				animation: crate::renderer::Animation2D
+				let species = match self.species.iter().find(|species| species.definition.name == garden_plant.name)
+				{
+					Some(species) => species,
+					None          => continue,
+				};
+
+				let sprite_area = Area
 				{
-					model: crate::renderer::Model2D
+					x     : x + index as f32 * width * fit,
+					y,
+					width : width  * fit,
+					height: height * fit,
+				};
+
+				let plant = match previous.iter().position(|plant| plant.is_species(species))
+				{
+					Some(position) =>
 					{
-						vertex_buffer: glium::VertexBuffer::new(
-							display,
-							&[
-								crate::renderer::Vertex2D { position: [-0.28, -0.5], texture_coords: [0.0, 0.0] },
-								crate::renderer::Vertex2D { position: [ 0.28, -0.5], texture_coords: [1.0, 0.0] },
-								crate::renderer::Vertex2D { position: [ 0.28,  0.5], texture_coords: [1.0, 1.0] },
-								crate::renderer::Vertex2D { position: [-0.28,  0.5], texture_coords: [0.0, 1.0] },
-							]
-						).unwrap(),
-						index_buffer: glium::IndexBuffer::new(
-							display,
-							glium::index::PrimitiveType::TrianglesList,
-							&[
-								0_u16, 1_u16, 2_u16,
-								2_u16, 3_u16, 0_u16,
-							]
-						).unwrap(),
-						texture: texture1,
-						texture_shader_program: glium::program!(
-							display,
-							140 =>
-							{
-								vertex  : include_str!("../shaders/460/2D/TextureVertShader.glsl"),
-								fragment: include_str!("../shaders/460/2D/TextureFragShader.glsl"),
-							}
-						).unwrap(),
+						let mut plant = previous.remove(position);
+						plant.sprite_area       = sprite_area;
+						plant.garden_index      = index;
+						plant.transform.tint    = self.tint;
+						plant.transform.opacity = self.opacity;
+						plant
 					},
+					None => self.create_plant(species, index, sprite_area),
+				};
+
+				self.plants.push(plant);
+			}
+		}
+	}
+
+	fn create_plant(&self, species: &Species, garden_index: usize, sprite_area: Area) -> Plant
+	{
+		let visual = match &species.visual
+		{
+			SpeciesVisual::Model(model) => PlantVisual::Model
+			{
+				model   : model.clone(),
+				rotation: 0.0,
+				time    : 0.0,
+			},
+			SpeciesVisual::Sprite(root_steps_chain) => PlantVisual::Sprite(
+				crate::renderer::Animation2D
+				{
 					timer: std::time::Duration::from_millis(0),
 					root_steps_chain: root_steps_chain.clone(),
-					current_step: root_steps_chain,
+					current_step: root_steps_chain.clone(),
 				}
+			),
+		};
+
+		let mut transform = crate::renderer::Transform2D::new();
+		transform.tint    = self.tint;
+		transform.opacity = self.opacity;
+
+		return Plant
+		{
+			visual,
+			sprite_area,
+			transform,
+			garden_index,
+		};
+	}
+
+	//NOTE(fpalacios): Devuelve true si algo cambió y hay que volver a dibujar
+	pub fn update(&mut self, delta: &std::time::Duration) -> bool
+	{
+		self.last_tick = *delta;
+
+		return self.advance(delta);
+	}
+
+	//NOTE(fpalacios): Para el tiempo que el GameLoop no simula de a ticks, por ejemplo al volver de una suspension. El agua y
+	//las animaciones se adelantan todo de una, sin tocar el tick que se usa para interpolar
+	pub fn catch_up(&mut self, elapsed: &std::time::Duration) -> bool
+	{
+		return self.advance(elapsed);
+	}
+
+	fn advance(&mut self, delta: &std::time::Duration) -> bool
+	{
+		if self.paused
+		{
+			return false;
+		}
+
+		crate::garden::pass_time(&mut self.garden, self.weather, self.drying, delta.as_secs_f32());
+
+		let mut changed = false;
+		for plant in &mut self.plants
+		{
+			if crate::garden::has_water(self.drying, &self.garden[plant.garden_index])
+			{
+				changed |= plant.update(delta);
 			}
+		}
+
+		return changed;
+	}
+
+	fn is_growing(&self, plant: &Plant) -> bool
+	{
+		return !self.paused && crate::garden::has_water(self.drying, &self.garden[plant.garden_index]);
+	}
+
+	pub fn time_until_next_change(&self) -> Option<std::time::Duration>
+	{
+		return self.plants.iter().filter(|plant| self.is_growing(plant)).filter_map(|plant| match &plant.visual
+		{
+			PlantVisual::Sprite(animation) => Some(animation.time_until_next_step()),
+			PlantVisual::Model { .. }      => None,
+		}).min();
+	}
+
+	//NOTE(fpalacios): Cuanto tarda en repetirse la animacion mas larga. None si no hay ninguna que se repita, los modelos
+	//sin animacion solo giran
+	pub fn animation_period(&self) -> Option<std::time::Duration>
+	{
+		return self.species.iter().filter_map(|species| match &species.visual
+		{
+			SpeciesVisual::Sprite(root_steps_chain) => Some(root_steps_chain.chain_duration()),
+			SpeciesVisual::Model(model) => model.animation.as_ref()
+				.filter(|animation| animation.duration > 0.0)
+				.map(|animation| std::time::Duration::from_secs_f32(animation.duration)),
+		}).max();
+	}
+
+	//NOTE(fpalacios): Los modelos giran todo el tiempo asi que hay que dibujar a los fps configurados. Cuando haya
+	//particulas o tweens activos tambien
+	pub fn is_animating(&self) -> bool
+	{
+		return self.plants.iter().any(|plant| self.is_growing(plant) && matches!(plant.visual, PlantVisual::Model { .. }));
+	}
+
+	pub fn garden(&self) -> &[crate::garden::GardenPlant]
+	{
+		return &self.garden;
+	}
+
+	pub fn species_names(&self) -> Vec<&str>
+	{
+		return self.species.iter().map(|species| species.definition.name.as_str()).collect();
+	}
+
+	//NOTE(fpalacios): Con None riega todo el jardin. Devuelve cuantas plantas se regaron
+	pub fn water(&mut self, name: Option<&str>) -> usize
+	{
+		let mut watered = 0;
+		for plant in self.garden.iter_mut().filter(|plant| name.is_none_or(|name| plant.name == name))
+		{
+			plant.water = 1.0;
+			watered += 1;
+		}
+
+		return watered;
+	}
+
+	pub fn add_plant(&mut self, name: &str) -> Result<(), String>
+	{
+		if !self.species.iter().any(|species| species.definition.name == name)
+		{
+			return Err(format!("No hay ninguna planta {} en la configuracion, las que hay son: {}", name, self.species_names().join(", ")));
+		}
+
+		self.garden.push(crate::garden::GardenPlant::new(name));
+
+		let areas = self.areas.clone();
+		self.layout(&areas);
+
+		return Ok(());
+	}
+
+	//NOTE(fpalacios): Arranca la ultima que se sembro con ese nombre, o la ultima de todas con None
+	pub fn remove_plant(&mut self, name: Option<&str>) -> Result<(), String>
+	{
+		let index = self.garden.iter()
+			.rposition(|plant| name.is_none_or(|name| plant.name == name))
+			.ok_or_else(|| match name
+			{
+				Some(name) => format!("No hay ninguna planta {} sembrada", name),
+				None       => String::from("El jardin esta vacio"),
+			})?;
+
+		self.garden.remove(index);
+
+		let areas = self.areas.clone();
+		self.layout(&areas);
+
+		return Ok(());
+	}
 
+	pub fn weather(&self) -> crate::garden::Weather
+	{
+		return self.weather;
+	}
+
+	pub fn set_weather(&mut self, weather: crate::garden::Weather)
+	{
+		self.weather = weather;
+	}
+
+	pub fn is_paused(&self) -> bool
+	{
+		return self.paused;
+	}
+
+	pub fn set_paused(&mut self, paused: bool)
+	{
+		self.paused = paused;
+	}
+
+	pub fn state(&self) -> crate::state::GardenState
+	{
+		return crate::state::GardenState
+		{
+			plants  : self.garden.clone(),
+			species : self.species_names().into_iter().map(String::from).collect(),
+			weather : self.weather,
+			paused  : self.paused,
+			saved_at: std::time::SystemTime::now(),
 		};
 	}
 
-	pub fn update(&mut self, delta: &std::time::Duration)
+	//NOTE(fpalacios): Vuelve al jardin guardado. Si se secan, siguen secandose mientras jardincho esta cerrado. Igual que en
+	//configure, las que ya no estan en la configuracion se pierden y las que se agregaron con el jardin cerrado se siembran
+	pub fn restore(&mut self, state: &crate::state::GardenState)
 	{
-		self.plant.update(delta);
+		let species = &self.species;
+		self.garden = state.plants.iter()
+			.filter(|plant| species.iter().any(|species| species.definition.name == plant.name))
+			.cloned()
+			.collect();
+
+		for species in &self.species
+		{
+			if !state.species.contains(&species.definition.name)
+			{
+				self.garden.push(crate::garden::GardenPlant::new(&species.definition.name));
+			}
+		}
+
+		self.weather = state.weather;
+		self.paused  = state.paused;
+
+		if !self.paused
+		{
+			if let Ok(elapsed) = state.saved_at.elapsed()
+			{
+				crate::garden::pass_time(&mut self.garden, self.weather, self.drying, elapsed.as_secs_f32());
+			}
+		}
+
+		let areas = self.areas.clone();
+		self.layout(&areas);
 	}
 
-	pub fn draw(&self, display: &glium::Display)
+	//NOTE(fpalacios): Tinte del tema por el del clima y el agua de la planta
+	fn plant_tint(&self, plant: &Plant) -> crate::color::RgbaColor
 	{
-		self.plant.draw(display);
+		return plant.transform.tint.multiply(&crate::garden::tint(self.weather, self.drying, &self.garden[plant.garden_index]));
 	}
-}
\ No newline at end of file
+
+	//NOTE(fpalacios): interpolation es la fraccion de tick que todavia no se simuló (ver game_loop::Step). Las animaciones
+	//2D son por pasos asi que solo se interpola la rotacion de los modelos
+	pub fn draw(&self, renderer: &mut impl crate::renderer::Renderer, interpolation: f32)
+	{
+		renderer.begin_frame();
+
+		for plant in &self.plants
+		{
+			match &plant.visual
+			{
+				PlantVisual::Sprite(animation) =>
+				{
+					let mut transform = plant.transform;
+					transform.tint = self.plant_tint(plant);
+
+					let texture = animation.current_texture();
+					renderer.draw_sprite(&texture.image(), &texture.options(), &plant.sprite_area, &transform);
+				},
+				PlantVisual::Model { model, rotation, time } =>
+				{
+					let pending = self.last_tick.as_secs_f32() * interpolation;
+					let angle   = rotation + MODEL_ROTATION_SPEED * std::f32::consts::TAU * pending;
+					let spin    = nalgebra::Matrix4::from_euler_angles(0.0, angle, 0.0);
+
+					for part in model.pose(time + pending)
+					{
+						renderer.draw_mesh(part.mesh, part.vertices.as_deref(), &(spin * part.transform), &self.camera, &plant.sprite_area);
+					}
+				},
+			}
+		}
+
+		renderer.end_frame();
+	}
+}
+
+//NOTE(fpalacios): Las definiciones que ya estaban cargadas se reusan tal cual, asi no se vuelven a subir los modelos
+fn load_species(
+	assets  : &mut crate::assets::AssetCache,
+	plants  : &[PlantDefinition],
+	previous: &[Species]
+)
+-> Result<Vec<Species>, WorldError>
+{
+	let mut species = Vec::new();
+
+	for definition in plants
+	{
+		if let Some(existing) = previous.iter().find(|existing| &existing.definition == definition)
+		{
+			species.push(existing.clone());
+			continue;
+		}
+
+		let visual = match &definition.kind
+		{
+			PlantKind::Sprite { frames, frame_duration } =>
+			{
+				//NOTE(fpalacios): La cadena se arma de atras para adelante porque cada paso apunta al siguiente
+				let mut next_step = None;
+				for frame in frames.iter().rev()
+				{
+					next_step = Some(std::rc::Rc::new(
+						crate::renderer::Animation2DStep
+						{
+							texture  : assets.texture(frame).map_err(WorldError::Asset)?,
+							duration : *frame_duration,
+							next_step,
+						}
+					));
+				}
+
+				SpeciesVisual::Sprite(next_step.ok_or_else(|| WorldError::NoFrames(definition.name.clone()))?)
+			},
+			PlantKind::Model(path) =>
+			{
+				let model = crate::mesh::load(path).map_err(|error| WorldError::Model { path: path.clone(), error })?;
+				SpeciesVisual::Model(std::rc::Rc::new(model))
+			},
+		};
+
+		species.push(
+			Species
+			{
+				definition: definition.clone(),
+				visual,
+			}
+		);
+	}
+
+	return Ok(species);
+}
+
+#[cfg(test)]
+mod tests
+{
+	fn sprite_plant(name: &str, frame: &str) -> super::PlantDefinition
+	{
+		return super::PlantDefinition
+		{
+			name: String::from(name),
+			kind: super::PlantKind::Sprite
+			{
+				frames        : vec![String::from(frame)],
+				frame_duration: std::time::Duration::from_secs(1),
+			},
+		};
+	}
+
+	fn sprites(renderer: &crate::recording_renderer::RecordingRenderer) -> Vec<(usize, super::Area, crate::renderer::Transform2D)>
+	{
+		return renderer.calls.iter().filter_map(|call| match call
+		{
+			crate::recording_renderer::RenderCall::Sprite { image, area, transform } => Some((*image, *area, *transform)),
+			_                                                                       => None,
+		}).collect();
+	}
+
+	fn assert_area(actual: &super::Area, expected: &super::Area)
+	{
+		let close = |actual: f32, expected: f32| (actual - expected).abs() < 1e-5;
+		assert!(
+			close(actual.x, expected.x) && close(actual.y, expected.y) && close(actual.width, expected.width) && close(actual.height, expected.height),
+			"{:?} en vez de {:?}",
+			actual,
+			expected
+		);
+	}
+
+	#[test]
+	fn layout_puts_the_garden_in_a_row_on_every_area()
+	{
+		let (mut assets, directory) = crate::assets::test_assets("world-layout");
+
+		let mut config = crate::config::Config::new();
+		config.plants = vec![sprite_plant("a", "a.png"), sprite_plant("b", "b.png")];
+
+		let mut world = super::World::new(&mut assets, &config).unwrap();
+		world.layout(&[
+			super::Area { x: -1.0, y: -1.0, width: 1.0, height: 2.0 },
+			super::Area { x:  0.0, y: -1.0, width: 1.0, height: 2.0 },
+		]);
+
+		let mut renderer = crate::recording_renderer::RecordingRenderer::new();
+		world.draw(&mut renderer, 0.0);
+
+		let a = std::rc::Rc::as_ptr(&assets.texture("a.png").unwrap().image()) as usize;
+		let b = std::rc::Rc::as_ptr(&assets.texture("b.png").unwrap().image()) as usize;
+
+		//NOTE(fpalacios): Cada planta mide 0.28 del ancho y la mitad del alto del area, la fila va centrada
+		let frame = renderer.last_frame();
+		assert_eq!(frame.iter().map(|(image, _)| *image).collect::<Vec<usize>>(), vec![a, b, a, b]);
+		assert_area(&frame[0].1, &super::Area { x: -0.78, y: -0.5, width: 0.28, height: 1.0 });
+		assert_area(&frame[1].1, &super::Area { x: -0.50, y: -0.5, width: 0.28, height: 1.0 });
+		assert_area(&frame[2].1, &super::Area { x:  0.22, y: -0.5, width: 0.28, height: 1.0 });
+		assert_area(&frame[3].1, &super::Area { x:  0.50, y: -0.5, width: 0.28, height: 1.0 });
+
+		//NOTE(fpalacios): Con el doble de escala la fila mide 2.24 y no entra, se achica toda junta hasta ocupar el ancho
+		config.window.scale = 2.0;
+		world.configure(&mut assets, &config).unwrap();
+		world.layout(&[super::Area::full_window()]);
+		renderer.clear();
+		world.draw(&mut renderer, 0.0);
+
+		let fit   = 2.0 / 2.24;
+		let frame = renderer.last_frame();
+		assert_eq!(frame.len(), 2);
+		assert_area(&frame[0].1, &super::Area { x: -1.0, y: -fit, width: 1.0, height: 2.0 * fit });
+		assert_area(&frame[1].1, &super::Area { x:  0.0, y: -fit, width: 1.0, height: 2.0 * fit });
+
+		let _ = std::fs::remove_dir_all(directory);
+	}
+
+	#[test]
+	fn layout_keeps_the_animations()
+	{
+		let (mut assets, directory) = crate::assets::test_assets("world-relayout");
+
+		let mut config = crate::config::Config::new();
+		config.plants = vec![
+			sprite_plant("a", "a.png"),
+			super::PlantDefinition { name: String::from("model"), kind: super::PlantKind::Model(directory.join("plant.obj")) },
+		];
+
+		let mut world = super::World::new(&mut assets, &config).unwrap();
+		world.update(&std::time::Duration::from_millis(300));
+
+		let state = |plant: &super::Plant| match &plant.visual
+		{
+			super::PlantVisual::Sprite(animation)       => animation.timer.as_secs_f32(),
+			super::PlantVisual::Model { rotation, .. } => *rotation,
+		};
+
+		let rotation = super::MODEL_ROTATION_SPEED * std::f32::consts::TAU * 0.3;
+		assert_eq!(world.plants.iter().map(state).collect::<Vec<f32>>(), vec![0.3, rotation]);
+
+		//NOTE(fpalacios): Las del primer monitor son las que ya estaban, las del segundo arrancan de cero
+		world.layout(&[
+			super::Area { x: -1.0, y: -1.0, width: 1.0, height: 2.0 },
+			super::Area { x:  0.0, y: -1.0, width: 1.0, height: 2.0 },
+		]);
+
+		assert_eq!(world.plants.iter().map(state).collect::<Vec<f32>>(), vec![0.3, rotation, 0.0, 0.0]);
+		assert_area(&world.plants[0].sprite_area, &super::Area { x: -0.78, y: -0.5, width: 0.28, height: 1.0 });
+
+		//NOTE(fpalacios): Cambiar el tema tampoco las reinicia, pero si cambia la especie es otra planta
+		config.theme.opacity = 0.5;
+		config.plants[0] = sprite_plant("a", "b.png");
+		world.configure(&mut assets, &config).unwrap();
+
+		assert_eq!(world.plants.iter().map(state).collect::<Vec<f32>>(), vec![0.0, rotation, 0.0, 0.0]);
+		assert!(world.plants.iter().all(|plant| plant.transform.opacity == 0.5));
+
+		let _ = std::fs::remove_dir_all(directory);
+	}
+
+	#[test]
+	fn catch_up_skips_whole_animation_loops()
+	{
+		let (mut assets, directory) = crate::assets::test_assets("world-catch-up");
+
+		let mut config = crate::config::Config::new();
+		config.plants = vec![
+			super::PlantDefinition
+			{
+				name: String::from("a"),
+				kind: super::PlantKind::Sprite
+				{
+					frames        : vec![String::from("a.png"), String::from("b.png")],
+					frame_duration: std::time::Duration::from_secs(1),
+				},
+			},
+		];
+
+		let mut world = super::World::new(&mut assets, &config).unwrap();
+		world.layout(&[super::Area::full_window()]);
+
+		let b = std::rc::Rc::as_ptr(&assets.texture("b.png").unwrap().image()) as usize;
+
+		//NOTE(fpalacios): Tres horas de suspension y un segundo y medio, queda en la mitad del segundo cuadro
+		assert!(world.catch_up(&std::time::Duration::from_millis(3 * 60 * 60 * 1000 + 1500)));
+		assert_eq!(world.time_until_next_change(), Some(std::time::Duration::from_millis(500)));
+
+		let mut renderer = crate::recording_renderer::RecordingRenderer::new();
+		world.draw(&mut renderer, 0.0);
+		assert_eq!(renderer.last_frame().iter().map(|(image, _)| *image).collect::<Vec<usize>>(), vec![b]);
+
+		let _ = std::fs::remove_dir_all(directory);
+	}
+
+	#[test]
+	fn tint_mixes_theme_weather_and_dryness()
+	{
+		let (mut assets, directory) = crate::assets::test_assets("world-tint");
+
+		let mut config = crate::config::Config::new();
+		config.plants        = vec![sprite_plant("a", "a.png")];
+		config.theme.tint    = crate::color::RgbaColor::new(0.5, 1.0, 1.0, 1.0);
+		config.theme.opacity = 0.5;
+		config.garden.drying = Some(std::time::Duration::from_secs(100));
+
+		let mut world = super::World::new(&mut assets, &config).unwrap();
+		world.set_weather(crate::garden::Weather::Cloudy);
+
+		//NOTE(fpalacios): Queda con 0.125 de agua, la mitad de THIRSTY_WATER
+		world.update(&std::time::Duration::from_secs_f32(87.5));
+
+		let mut renderer = crate::recording_renderer::RecordingRenderer::new();
+		world.draw(&mut renderer, 0.0);
+
+		let (_, _, transform) = sprites(&renderer)[0];
+		let mut thirsty = crate::garden::GardenPlant::new("a");
+		thirsty.water = 0.125;
+		let weather_tint = config.theme.tint.multiply(&crate::garden::Weather::Cloudy.tint());
+		assert_eq!(transform.tint, config.theme.tint.multiply(&crate::garden::tint(crate::garden::Weather::Cloudy, config.garden.drying, &thirsty)));
+		assert_eq!(transform.opacity, 0.5);
+
+		world.water(None);
+		renderer.clear();
+		world.draw(&mut renderer, 0.0);
+
+		let (_, _, transform) = sprites(&renderer)[0];
+		assert_eq!(transform.tint, weather_tint);
+
+		//NOTE(fpalacios): Sin drying_hours nunca se ponen amarillas
+		config.garden.drying = None;
+		world.configure(&mut assets, &config).unwrap();
+		world.set_weather(crate::garden::Weather::Clear);
+		world.update(&std::time::Duration::from_secs(1000));
+		renderer.clear();
+		world.draw(&mut renderer, 0.0);
+
+		let (_, _, transform) = sprites(&renderer)[0];
+		assert_eq!(transform.tint, config.theme.tint);
+
+		let _ = std::fs::remove_dir_all(directory);
+	}
+
+	#[test]
+	fn transforms_follow_the_config_and_interpolate_models()
+	{
+		let (mut assets, directory) = crate::assets::test_assets("world-transform");
+
+		let mut config = crate::config::Config::new();
+		config.plants = vec![
+			sprite_plant("a", "a.png"),
+			super::PlantDefinition { name: String::from("model"), kind: super::PlantKind::Model(directory.join("plant.obj")) },
+		];
+		config.window.scale = 0.5;
+
+		let mut world = super::World::new(&mut assets, &config).unwrap();
+
+		let mut renderer = crate::recording_renderer::RecordingRenderer::new();
+		world.draw(&mut renderer, 0.0);
+
+		let mut expected = crate::renderer::Transform2D::new();
+		expected.tint    = config.theme.tint;
+		expected.opacity = config.theme.opacity;
+
+		let (_, area, transform) = sprites(&renderer)[0];
+		assert_eq!(transform, expected);
+		assert_area(&area, &super::Area { x: -0.28, y: -0.25, width: 0.28, height: 0.5 });
+
+		world.update(&std::time::Duration::from_secs(2));
+		renderer.clear();
+		world.draw(&mut renderer, 0.5);
+
+		let (mesh, model, area) = renderer.calls.iter().find_map(|call| match call
+		{
+			crate::recording_renderer::RenderCall::Mesh { mesh, model, area } => Some((*mesh, *model, *area)),
+			_                                                                 => None,
+		}).unwrap();
+
+		let part_transform = match &world.plants[1].visual
+		{
+			super::PlantVisual::Model { model, .. } =>
+			{
+				let pose = &model.pose(0.0)[0];
+				assert_eq!(std::rc::Rc::as_ptr(pose.mesh) as usize, mesh);
+				pose.transform
+			},
+			super::PlantVisual::Sprite(_) => panic!("La segunda planta tendria que ser el modelo"),
+		};
+
+		//NOTE(fpalacios): Dos segundos simulados mas la mitad del ultimo tick sin simular
+		let angle = super::MODEL_ROTATION_SPEED * std::f32::consts::TAU * 3.0;
+		let expected_model = nalgebra::Matrix4::from_euler_angles(0.0, angle, 0.0) * part_transform;
+		assert!((model - expected_model).abs().max() < 1e-5, "{} en vez de {}", model, expected_model);
+		assert_area(&area, &super::Area { x: 0.0, y: -0.25, width: 0.28, height: 0.5 });
+
+		let _ = std::fs::remove_dir_all(directory);
+	}
+}
@@ -0,0 +1,269 @@
+#![allow(clippy::needless_return)]
+
+//NOTE(fpalacios): Prueban platform::ewmh contra un Xvfb propio sin window manager. Nunca se usa el DISPLAY del usuario porque
+//los tests escriben propiedades en la ventana root y se hacen pasar por window manager. Sin Xvfb instalado se saltean
+static NEXT_DISPLAY: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
+
+const XVFB_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+struct Xvfb
+{
+	process: std::process::Child,
+	display: String,
+}
+
+impl Drop for Xvfb
+{
+	fn drop(&mut self)
+	{
+		let _ = self.process.kill();
+		let _ = self.process.wait();
+	}
+}
+
+//NOTE(fpalacios): Los tests corren en paralelo, cada uno levanta su server en un numero de display libre
+fn start_xvfb() -> Option<Xvfb>
+{
+	for _ in 0 .. 20
+	{
+		let number  = 100 + (std::process::id() % 200) * 20 + NEXT_DISPLAY.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+		let display = format!(":{}", number);
+
+		if std::path::Path::new(&format!("/tmp/.X{}-lock", number)).exists()
+		{
+			continue;
+		}
+
+		let mut process = match std::process::Command::new("Xvfb")
+			.args([display.as_str(), "-nolisten", "tcp", "-screen", "0", "640x480x24"])
+			.stdout(std::process::Stdio::null())
+			.stderr(std::process::Stdio::null())
+			.spawn()
+		{
+			Ok(process) => process,
+			Err(_)      =>
+			{
+				eprintln!("No se encontro Xvfb, se saltea el test");
+				return None;
+			},
+		};
+
+		let deadline = std::time::Instant::now() + XVFB_TIMEOUT;
+		while std::time::Instant::now() < deadline
+		{
+			if let Ok(Some(_)) = process.try_wait()
+			{
+				break;
+			}
+
+			if xcb::Connection::connect(Some(&display)).is_ok()
+			{
+				return Some(Xvfb { process, display });
+			}
+
+			std::thread::sleep(std::time::Duration::from_millis(20));
+		}
+
+		let _ = process.kill();
+		let _ = process.wait();
+	}
+
+	eprintln!("No se pudo levantar Xvfb, se saltea el test");
+	return None;
+}
+
+fn with_screen(test: impl FnOnce(&jardincho::platform::ewmh::Ewmh, &jardincho::platform::xcb::Screen))
+{
+	let xvfb = match start_xvfb()
+	{
+		Some(xvfb) => xvfb,
+		None       => return,
+	};
+
+	let (xcb_conn, screen_id) = xcb::Connection::connect(Some(&xvfb.display)).unwrap();
+	let xcb_client = jardincho::platform::xcb::Client::new(&xcb_conn);
+	let xcb_screen = jardincho::platform::xcb::Screen::from_id(&xcb_client, screen_id).unwrap();
+	let ewmh       = jardincho::platform::ewmh::Ewmh::new(&xcb_client);
+
+	test(&ewmh, &xcb_screen);
+}
+
+//NOTE(fpalacios): root_window ata todo al lifetime de la pantalla
+fn create_window<'screen>(screen: &'screen jardincho::platform::xcb::Screen) -> jardincho::platform::xcb::Window<'screen, 'screen, 'screen>
+{
+	return screen.root_window().create_child_window((0, 0, 64, 64), 0, None, None, None, None).unwrap();
+}
+
+fn property(window: &jardincho::platform::xcb::Window, ewmh: &jardincho::platform::ewmh::Ewmh, name: &'static str)
+-> jardincho::platform::xcb::PropertyValue
+{
+	return window.get_property(ewmh.atom(name)).unwrap().value;
+}
+
+fn set_property(window: &jardincho::platform::xcb::Window, ewmh: &jardincho::platform::ewmh::Ewmh, name: &'static str, value: jardincho::platform::xcb::PropertyValue)
+{
+	window.set_property(&jardincho::platform::xcb::Property { key: ewmh.atom(name), value });
+}
+
+//NOTE(fpalacios): Los mensajes _NET_WM_STATE que le llegarian al window manager hasta que pasa un rato sin ninguno
+fn wm_state_messages(ewmh: &jardincho::platform::ewmh::Ewmh) -> Vec<jardincho::platform::xcb::ClientMessageEvent>
+{
+	let net_wm_state = ewmh.atom("_NET_WM_STATE");
+	let mut messages = Vec::new();
+
+	let quiet = std::time::Duration::from_millis(200);
+	let mut deadline = std::time::Instant::now() + quiet;
+
+	while std::time::Instant::now() < deadline
+	{
+		match ewmh.client.poll_events()
+		{
+			Some(jardincho::platform::xcb::Event::ClientMessageEvent(message)) if message.event_type == net_wm_state =>
+			{
+				messages.push(message);
+				deadline = std::time::Instant::now() + quiet;
+			},
+			Some(_) => deadline = std::time::Instant::now() + quiet,
+			None    => std::thread::sleep(std::time::Duration::from_millis(5)),
+		}
+	}
+
+	return messages;
+}
+
+#[test]
+fn atoms_are_cached()
+{
+	with_screen(|ewmh, _|
+	{
+		let atom = ewmh.atom("_NET_WM_STATE_BELOW");
+		assert_eq!(ewmh.atom("_NET_WM_STATE_BELOW"), atom);
+		assert_eq!(ewmh.client.find_atom_name(atom), "_NET_WM_STATE_BELOW");
+	});
+}
+
+#[test]
+fn window_properties()
+{
+	with_screen(|ewmh, screen|
+	{
+		let window = create_window(screen);
+
+		ewmh.set_window_type(&window, jardincho::platform::ewmh::WindowType::Utility);
+		assert_eq!(
+			property(&window, ewmh, "_NET_WM_WINDOW_TYPE"),
+			jardincho::platform::xcb::PropertyValue::Atom(ewmh.atom("_NET_WM_WINDOW_TYPE_UTILITY"))
+		);
+
+		ewmh.set_desktop(&window, jardincho::platform::ewmh::Desktop::All);
+		assert_eq!(property(&window, ewmh, "_NET_WM_DESKTOP"), jardincho::platform::xcb::PropertyValue::U32(0xFFFFFFFF));
+
+		ewmh.set_desktop(&window, jardincho::platform::ewmh::Desktop::Index(2));
+		assert_eq!(property(&window, ewmh, "_NET_WM_DESKTOP"), jardincho::platform::xcb::PropertyValue::U32(2));
+
+		ewmh.set_wm_name(&window, "jardincho ñ");
+		assert_eq!(property(&window, ewmh, "_NET_WM_NAME"), jardincho::platform::xcb::PropertyValue::String(String::from("jardincho ñ")));
+	});
+}
+
+#[test]
+fn states_reads_net_wm_state()
+{
+	with_screen(|ewmh, screen|
+	{
+		let window = create_window(screen);
+
+		assert_eq!(ewmh.states(&window), Vec::new());
+
+		set_property(
+			&window,
+			ewmh,
+			"_NET_WM_STATE",
+			jardincho::platform::xcb::PropertyValue::AtomList(vec![
+				ewmh.atom("_NET_WM_STATE_BELOW"),
+				ewmh.atom("_NET_WM_STATE_STICKY"),
+				ewmh.atom("_NET_WM_STATE_SKIP_TASKBAR"),
+			])
+		);
+
+		assert_eq!(
+			ewmh.states(&window),
+			vec![
+				jardincho::platform::ewmh::WindowState::Sticky,
+				jardincho::platform::ewmh::WindowState::SkipTaskbar,
+				jardincho::platform::ewmh::WindowState::Below,
+			]
+		);
+
+		set_property(&window, ewmh, "_NET_WM_STATE", jardincho::platform::xcb::PropertyValue::Atom(ewmh.atom("_NET_WM_STATE_HIDDEN")));
+		assert_eq!(ewmh.states(&window), vec![jardincho::platform::ewmh::WindowState::Hidden]);
+	});
+}
+
+#[test]
+fn state_changes_are_sent_in_pairs()
+{
+	with_screen(|ewmh, screen|
+	{
+		let window = create_window(screen);
+
+		//NOTE(fpalacios): No hay window manager, asi que el test recibe lo que le llegaria a el
+		screen.root_window().select_events(xcb::EVENT_MASK_SUBSTRUCTURE_REDIRECT).unwrap();
+
+		let below        = ewmh.atom("_NET_WM_STATE_BELOW");
+		let sticky       = ewmh.atom("_NET_WM_STATE_STICKY");
+		let skip_taskbar = ewmh.atom("_NET_WM_STATE_SKIP_TASKBAR");
+
+		ewmh.add_state(
+			&window,
+			&[
+				jardincho::platform::ewmh::WindowState::Below,
+				jardincho::platform::ewmh::WindowState::Sticky,
+				jardincho::platform::ewmh::WindowState::SkipTaskbar,
+			]
+		);
+
+		let messages = wm_state_messages(ewmh);
+		assert_eq!(messages.len(), 2);
+		assert!(messages.iter().all(|message| message.window == window.id));
+		assert_eq!(messages[0].data, [1, below, sticky, 1, 0]);
+		assert_eq!(messages[1].data, [1, skip_taskbar, 0, 1, 0]);
+
+		ewmh.remove_state(&window, &[jardincho::platform::ewmh::WindowState::Below]);
+		ewmh.toggle_state(&window, &[jardincho::platform::ewmh::WindowState::Sticky]);
+
+		let messages = wm_state_messages(ewmh);
+		assert_eq!(messages.len(), 2);
+		assert_eq!(messages[0].data, [0, below, 0, 1, 0]);
+		assert_eq!(messages[1].data, [2, sticky, 0, 1, 0]);
+	});
+}
+
+#[test]
+fn root_window_properties()
+{
+	with_screen(|ewmh, screen|
+	{
+		let root = screen.root_window();
+
+		assert_eq!(ewmh.workarea(screen), None);
+		assert_eq!(ewmh.active_window(screen), None);
+		assert_eq!(ewmh.client_list(screen), Vec::new());
+
+		set_property(&root, ewmh, "_NET_WORKAREA", jardincho::platform::xcb::PropertyValue::U32List(vec![0, 0, 640, 480, 0, 24, 640, 456]));
+		assert_eq!(ewmh.workarea(screen), Some(vec![(0, 0, 640, 480), (0, 24, 640, 456)]));
+
+		let first  = create_window(screen);
+		let second = create_window(screen);
+
+		set_property(&root, ewmh, "_NET_ACTIVE_WINDOW", jardincho::platform::xcb::PropertyValue::Window(first.id));
+		assert_eq!(ewmh.active_window(screen), Some(first.id));
+
+		//NOTE(fpalacios): EWMH usa None cuando no hay ninguna ventana activa
+		set_property(&root, ewmh, "_NET_ACTIVE_WINDOW", jardincho::platform::xcb::PropertyValue::Window(xcb::NONE));
+		assert_eq!(ewmh.active_window(screen), None);
+
+		set_property(&root, ewmh, "_NET_CLIENT_LIST", jardincho::platform::xcb::PropertyValue::WindowList(vec![first.id, second.id]));
+		assert_eq!(ewmh.client_list(screen), vec![first.id, second.id]);
+	});
+}
@@ -0,0 +1,175 @@
+#![allow(clippy::needless_return)]
+
+//NOTE(fpalacios): Dibujan escenas chicas con el renderer de offscreen y las comparan contra tests/golden. Las escenas son
+//colores planos con bordes justo entre pixeles, asi las referencias se pueden calcular a mano y no dependen de la version de
+//Mesa. Sin OSMesa se saltean. Con JARDINCHO_UPDATE_GOLDEN=1 se pisan las referencias con lo que se dibujo. El backend por
+//software se compara contra las mismas referencias y corre siempre
+const WIDTH : u32 = 64;
+const HEIGHT: u32 = 64;
+
+//NOTE(fpalacios): Por canal, para el redondeo de la placa al pasar a sRGB
+const TOLERANCE: u8 = 3;
+
+fn golden_path(name: &str) -> std::path::PathBuf
+{
+	return std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden").join(format!("{}.png", name));
+}
+
+fn assert_golden(name: &str, image: &image::RgbaImage)
+{
+	let path = golden_path(name);
+
+	if std::env::var_os("JARDINCHO_UPDATE_GOLDEN").is_some()
+	{
+		image.save(&path).unwrap();
+		return;
+	}
+
+	let expected = image::open(&path).unwrap().to_rgba8();
+	assert_eq!(image.dimensions(), expected.dimensions(), "{} no mide lo mismo que la referencia", name);
+
+	let different = image.enumerate_pixels()
+		.zip(expected.pixels())
+		.find(|((_, _, pixel), expected)| pixel.0.iter().zip(expected.0.iter()).any(|(value, expected)| value.abs_diff(*expected) > TOLERANCE));
+
+	if let Some(((x, y, pixel), expected)) = different
+	{
+		let actual_path = std::env::temp_dir().join(format!("jardincho-golden-{}.png", name));
+		let _ = image.save(&actual_path);
+
+		panic!(
+			"{} no coincide con la referencia en ({}, {}): {:?} en vez de {:?}. Lo que se dibujo quedo en {}",
+			name,
+			x,
+			y,
+			pixel.0,
+			expected.0,
+			actual_path.display()
+		);
+	}
+}
+
+fn offscreen_target() -> Option<jardincho::offscreen::OffscreenTarget>
+{
+	return match jardincho::offscreen::OffscreenTarget::new(WIDTH, HEIGHT)
+	{
+		Ok(target) => Some(target),
+		Err(jardincho::offscreen::OffscreenError::Context(err)) =>
+		{
+			eprintln!("No hay OSMesa, se saltea el test ({})", err);
+			None
+		},
+		Err(err) => panic!("No se pudo crear el renderer de offscreen {:?}", err),
+	};
+}
+
+//NOTE(fpalacios): Una imagen de 2x2 con nearest en el centro de la pantalla, cada texel ocupa 16x16 pixeles. El de abajo a la
+//derecha es blanco con la mitad de alpha
+fn draw_sprite_scene(renderer: &mut impl jardincho::renderer::Renderer)
+{
+	let mut image = image::RgbaImage::new(2, 2);
+	image.put_pixel(0, 0, image::Rgba([255, 0, 0, 255]));
+	image.put_pixel(1, 0, image::Rgba([0, 255, 0, 255]));
+	image.put_pixel(0, 1, image::Rgba([0, 0, 255, 255]));
+	image.put_pixel(1, 1, image::Rgba([255, 255, 255, 128]));
+
+	let options = jardincho::assets::TextureOptions
+	{
+		mipmaps: false,
+		filter : jardincho::assets::TextureFilter::Nearest,
+		..jardincho::assets::TextureOptions::new()
+	};
+
+	let area = jardincho::world::Area
+	{
+		x     : -0.5,
+		y     : -0.5,
+		width :  1.0,
+		height:  1.0,
+	};
+
+	renderer.begin_frame();
+	renderer.draw_sprite(&std::rc::Rc::new(image), &options, &area, &jardincho::renderer::Transform2D::new());
+	renderer.end_frame();
+}
+
+fn quad(x: (f32, f32), y: (f32, f32), z: f32, color: [f32; 3]) -> jardincho::mesh::Mesh
+{
+	let vertex = |x: f32, y: f32| jardincho::renderer::Vertex3D
+	{
+		position      : [x, y, z],
+		normal        : [0.0, 0.0, 1.0],
+		texture_coords: [0.0, 0.0],
+		color,
+	};
+
+	return jardincho::mesh::Mesh::new(
+		vec![vertex(x.0, y.0), vertex(x.1, y.0), vertex(x.1, y.1), vertex(x.0, y.1)],
+		vec![0, 1, 2, 0, 2, 3]
+	);
+}
+
+//NOTE(fpalacios): Con 90 grados de campo visual y la camara a 2 del origen, el cuadrado de -1 a 1 en z = 0 ocupa la mitad
+//del centro de la pantalla. Arriba rojo, abajo verde, y atras en z = -2 uno azul mas grande que se dibuja despues y solo
+//tiene que verse alrededor
+fn draw_mesh_scene(renderer: &mut impl jardincho::renderer::Renderer)
+{
+	let camera = jardincho::camera::Camera
+	{
+		position: nalgebra::Point3::new(0.0, 0.0, 2.0),
+		target  : nalgebra::Point3::new(0.0, 0.0, 0.0),
+		up      : nalgebra::Vector3::y(),
+		fov_y   : std::f32::consts::FRAC_PI_2,
+		near    : 0.1,
+		far     : 100.0,
+	};
+
+	let meshes = [
+		quad((-1.0, 1.0), ( 0.0, 1.0),  0.0, [1.0, 0.2, 0.2]),
+		quad((-1.0, 1.0), (-1.0, 0.0),  0.0, [0.2, 1.0, 0.2]),
+		quad((-3.0, 3.0), (-3.0, 3.0), -2.0, [0.2, 0.2, 1.0]),
+	];
+
+	renderer.begin_frame();
+	for mesh in meshes
+	{
+		renderer.draw_mesh(&std::rc::Rc::new(mesh), None, &nalgebra::Matrix4::identity(), &camera, &jardincho::world::Area::full_window());
+	}
+	renderer.end_frame();
+}
+
+#[test]
+fn sprite()
+{
+	if let Some(mut target) = offscreen_target()
+	{
+		draw_sprite_scene(&mut target.renderer);
+		assert_golden("sprite", &target.renderer.read_image().unwrap());
+	}
+}
+
+#[test]
+fn mesh()
+{
+	if let Some(mut target) = offscreen_target()
+	{
+		draw_mesh_scene(&mut target.renderer);
+		assert_golden("mesh", &target.renderer.read_image().unwrap());
+	}
+}
+
+#[test]
+fn software_sprite()
+{
+	let mut renderer = jardincho::software_renderer::SoftwareRenderer::new(WIDTH, HEIGHT);
+	draw_sprite_scene(&mut renderer);
+	assert_golden("sprite", &renderer.to_image());
+}
+
+#[test]
+fn software_mesh()
+{
+	let mut renderer = jardincho::software_renderer::SoftwareRenderer::new(WIDTH, HEIGHT);
+	draw_mesh_scene(&mut renderer);
+	assert_golden("mesh", &renderer.to_image());
+}